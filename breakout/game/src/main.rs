@@ -1,14 +1,15 @@
 #![allow(dead_code, unused_imports, unused_variables)]
 
 use features::*;
+use game_loop::{controls, GameLoop};
 use vectors::*;
 use sdl2::render::Canvas;
 use sdl2::pixels::Color;
 use sdl2::keyboard::Keycode;
 use sdl2::event::Event;
-use std::time::Duration;
 use std::ptr;
 
+static TITLE: &str = "Pong game";
 static CANVAS_SIZE: (u32, u32) = (800, 400);
 static CANVAS_COLOR: Color = Color::RGB(0, 0, 0);
 static BAR_COLOR: Color = Color::RGB(255, 255, 255);
@@ -22,6 +23,7 @@ static BALL_POSITION: (u32, u32) = (CANVAS_SIZE.0 / 2 - BALL_SIZE / 2,
 static BARS_NUMBER_X: usize = (CANVAS_SIZE.0 / BAR_SIZE.0) as usize;
 static BARS_NUMBER_Y: usize = 5;
 static BARS_NUMBER: usize = BARS_NUMBER_X * BARS_NUMBER_Y;
+static UPDATES_PER_SECOND: f64 = 240.0;
 static mut GAME_STATUS: GameStatus = Start;
 
 #[derive(PartialEq)]
@@ -37,7 +39,7 @@ fn create_context_and_canvas() -> (sdl2::Sdl, Canvas<sdl2::video::Window>) {
     let sdl_context = sdl2::init().unwrap();
     let video_subystem = sdl_context.video().unwrap();
     let window = video_subystem
-            .window("Pong game", CANVAS_SIZE.0, CANVAS_SIZE.1)
+            .window(TITLE, CANVAS_SIZE.0, CANVAS_SIZE.1)
             .position_centered()
             .build()
             .unwrap();
@@ -71,8 +73,15 @@ fn main() {
         BALL_SIZE,
     );
 
+    // espaco pausa, N avanca um passo, [ e ] mudam a escala de tempo
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
+
     'running: loop {
         for event in event_pump.poll_iter() {
+            if controls::handle(&mut game_loop, &event) {
+                continue;
+            }
+
             match event {
                 Event::Quit { .. } => break 'running,
                 Event::KeyDown { keycode: Some(Keycode::Return), .. } => {
@@ -97,6 +106,12 @@ fn main() {
             bar.move_by_keyboard(&event);
         }
 
+        let frame = game_loop.tick();
+
+        if frame.measured {
+            controls::show_status(canvas.window_mut(), TITLE, &game_loop);
+        }
+
         canvas.set_draw_color(CANVAS_COLOR);
         canvas.clear();
 
@@ -117,12 +132,21 @@ fn main() {
         unsafe {
             match GAME_STATUS {
                 Start => start_game(&mut bar, &mut ball, &mut breakable_bars),
-                Running => run_game(&mut bar, &mut ball, &mut breakable_bars),
+                Running => {
+                    for _ in 0..frame.updates {
+                        run_game(&mut bar, &mut ball, &mut breakable_bars);
+
+                        if GAME_STATUS != Running {
+                            break;
+                        }
+                    }
+                },
                 End => end_game(),
             }
         }
 
         canvas.present();
+        game_loop.wait();
     }
 }
 
@@ -223,7 +247,6 @@ fn run_game
     ball.check_collision_bar(bar);
     bar.update_position();
     ball.update_position();
-    ::std::thread::sleep(Duration::new(0, 1_000_000u32));
 }
 
 fn start_game
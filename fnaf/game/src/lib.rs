@@ -1,6 +1,6 @@
 #![allow(dead_code, unused_imports, unused_variables, static_mut_refs)]
 
-use std::time::{Duration, Instant};
+use std::time::Instant;
 use vectors::*;
 use map::*;
 use player::*;
@@ -497,7 +497,6 @@ impl Manager {
         self.hour_handler.draw_text(hour.as_ref(), &mut self.canvas,
                                     &self.ttf);
 
-        ::std::thread::sleep(Duration::from_millis(16));
         Running
     }
 
@@ -12,6 +12,7 @@ use sdl2::keyboard::Keycode;
 use sdl2::mouse::MouseButton;
 use game::GameStatus;
 use game::Manager;
+use game_loop::GameLoop;
 use std::ptr;
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -46,7 +47,12 @@ fn main() {
         ttf,
     );
 
+    // a noite anda pelo relogio, entao o loop so segura os quadros
+    let mut game_loop = GameLoop::new(GameLoop::DEFAULT_FPS);
+
     'running: loop {
+        game_loop.tick();
+
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit {..} => break 'running,
@@ -82,5 +88,6 @@ fn main() {
         }
 
         manager.present_canvas();
+        game_loop.wait();
     }
 }
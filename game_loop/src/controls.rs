@@ -0,0 +1,44 @@
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::video::Window;
+
+use crate::GameLoop;
+
+// fator de `[` e `]` na escala de tempo
+static TIME_SCALE_STEP: f64 = 2.0;
+
+/// Keys every binary shares: space pauses and resumes, N steps once while
+/// paused, `[` and `]` halve and double the time scale.
+///
+/// Returns false for events it does not use.
+pub fn handle(game_loop: &mut GameLoop, event: &Event) -> bool {
+    match *event {
+        Event::KeyDown { keycode: Some(Keycode::Space), repeat: false, .. } => {
+            game_loop.toggle_pause();
+        },
+        Event::KeyDown { keycode: Some(Keycode::N), .. } => {
+            game_loop.step_once();
+        },
+        Event::KeyDown { keycode: Some(Keycode::LeftBracket), .. } => {
+            game_loop.set_time_scale(game_loop.time_scale() / TIME_SCALE_STEP);
+        },
+        Event::KeyDown { keycode: Some(Keycode::RightBracket), .. } => {
+            game_loop.set_time_scale(game_loop.time_scale() * TIME_SCALE_STEP);
+        },
+        _ => return false,
+    }
+
+    true
+}
+
+/// Pause state, time scale and measured rates, for a status line.
+pub fn status(game_loop: &GameLoop) -> String {
+    format!("{}x{}  {:.0} FPS  {:.0} UPS",
+            if game_loop.is_paused() { "PAUSED  " } else { "" },
+            game_loop.time_scale(), game_loop.fps(), game_loop.ups())
+}
+
+/// Puts `status` after `title` in the window title.
+pub fn show_status(window: &mut Window, title: &str, game_loop: &GameLoop) {
+    window.set_title(&format!("{}  -  {}", title, status(game_loop))).unwrap();
+}
@@ -0,0 +1,217 @@
+//! Fixed-timestep loop shared by the SDL binaries.
+//!
+//! The physics advances in steps of a fixed length of real time, however
+//! fast the machine draws, so a simulation runs at the same speed
+//! everywhere:
+//!
+//! ```no_run
+//! # let mut running = true;
+//! let mut game_loop = game_loop::GameLoop::new(240.0);
+//!
+//! while running {
+//!     // eventos...
+//!     let frame = game_loop.tick();
+//!
+//!     for _ in 0..frame.updates {
+//!         // um passo da fisica
+//!     }
+//!
+//!     // desenho, podendo usar frame.alpha para interpolar
+//!     game_loop.wait();
+//! #   running = false;
+//! }
+//! ```
+
+#[cfg(feature = "sdl2")]
+pub mod controls;
+mod rates;
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub use rates::Rates;
+
+/// What to do in one rendered frame.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Frame {
+    /// Fixed steps to run before drawing.
+    pub updates: u32,
+    /// How far the time left over is into the next step, from 0 to 1.
+    pub alpha: f64,
+    /// Set when `fps` and `ups` were just measured again.
+    pub measured: bool,
+}
+
+impl Frame {
+    /// Value to draw for something that went from `previous` to `current`
+    /// in the last step.
+    pub fn interpolate(&self, previous: f64, current: f64) -> f64 {
+        previous + (current - previous) * self.alpha
+    }
+}
+
+/// Fixed-timestep accumulator with pause, single-stepping, time scaling,
+/// a frame rate cap and FPS/UPS counters.
+#[derive(Debug, Clone)]
+pub struct GameLoop {
+    timestep: Duration,
+    accumulator: Duration,
+    time_scale: f64,
+    paused: bool,
+    // passos pedidos enquanto pausado
+    pending: u32,
+    max_frame_time: Duration,
+    frame_interval: Option<Duration>,
+    // inicio do quadro atual
+    frame_start: Option<Instant>,
+    rates: Rates,
+}
+
+impl GameLoop {
+    pub const DEFAULT_FPS: f64 = 60.0;
+    /// Longest frame counted in full; time beyond it is dropped so a slow
+    /// frame does not make the next ones slower.
+    pub const DEFAULT_MAX_FRAME_TIME: Duration = Duration::from_millis(250);
+    pub const MIN_TIME_SCALE: f64 = 1.0 / 64.0;
+    pub const MAX_TIME_SCALE: f64 = 64.0;
+    /// Bounds for the update rate and the frame rate cap, per second.
+    pub const MIN_RATE: f64 = 1.0;
+    pub const MAX_RATE: f64 = 100_000.0;
+
+    /// A loop running `updates_per_second` fixed steps, drawing at most
+    /// `DEFAULT_FPS` frames per second. The rate is kept between
+    /// `MIN_RATE` and `MAX_RATE`.
+    pub fn new(updates_per_second: f64) -> Self {
+        Self {
+            timestep: Self::interval(updates_per_second),
+            accumulator: Duration::ZERO,
+            time_scale: 1.0,
+            paused: false,
+            pending: 0,
+            max_frame_time: Self::DEFAULT_MAX_FRAME_TIME,
+            frame_interval: Some(Self::interval(Self::DEFAULT_FPS)),
+            frame_start: None,
+            rates: Rates::default(),
+        }
+    }
+
+    /// Real time covered by each step, at a time scale of 1.
+    pub fn timestep(&self) -> Duration {
+        self.timestep
+    }
+
+    pub fn updates_per_second(&self) -> f64 {
+        1.0 / self.timestep.as_secs_f64()
+    }
+
+    /// Caps the frame rate, kept between `MIN_RATE` and `MAX_RATE`; `None`
+    /// draws as fast as possible.
+    pub fn set_max_fps(&mut self, fps: Option<f64>) {
+        self.frame_interval = fps.map(Self::interval);
+    }
+
+    pub fn max_fps(&self) -> Option<f64> {
+        self.frame_interval.map(|interval| 1.0 / interval.as_secs_f64())
+    }
+
+    pub fn set_max_frame_time(&mut self, max_frame_time: Duration) {
+        self.max_frame_time = max_frame_time;
+    }
+
+    pub fn max_frame_time(&self) -> Duration {
+        self.max_frame_time
+    }
+
+    /// Simulated seconds per real second, kept between `MIN_TIME_SCALE`
+    /// and `MAX_TIME_SCALE`.
+    pub fn set_time_scale(&mut self, time_scale: f64) {
+        if time_scale.is_nan() {
+            return;
+        }
+
+        self.time_scale = time_scale.clamp(Self::MIN_TIME_SCALE, Self::MAX_TIME_SCALE);
+    }
+
+    pub fn time_scale(&self) -> f64 {
+        self.time_scale
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        self.pending = 0;
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.set_paused(!self.paused);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Runs one more step on the next frame; does nothing unless paused.
+    pub fn step_once(&mut self) {
+        if self.paused {
+            self.pending += 1;
+        }
+    }
+
+    /// Starts a frame, measuring the time since the previous one.
+    pub fn tick(&mut self) -> Frame {
+        let now = Instant::now();
+        let elapsed = self.frame_start.map_or(Duration::ZERO, |start| now - start);
+        self.frame_start = Some(now);
+
+        self.advance(elapsed)
+    }
+
+    /// Starts a frame `elapsed` after the previous one, without looking at
+    /// the clock.
+    pub fn advance(&mut self, elapsed: Duration) -> Frame {
+        let updates = if self.paused {
+            std::mem::take(&mut self.pending)
+        } else {
+            self.accumulator += elapsed.min(self.max_frame_time).mul_f64(self.time_scale);
+
+            let updates = (self.accumulator.as_nanos() / self.timestep.as_nanos()) as u32;
+            self.accumulator -= self.timestep * updates;
+
+            updates
+        };
+
+        let measured = self.rates.record(elapsed, updates);
+
+        Frame {
+            updates,
+            alpha: self.accumulator.as_secs_f64() / self.timestep.as_secs_f64(),
+            measured,
+        }
+    }
+
+    /// Sleeps out the rest of the frame when the frame rate is capped.
+    pub fn wait(&self) {
+        if let (Some(interval), Some(start)) = (self.frame_interval, self.frame_start) {
+            if let Some(rest) = interval.checked_sub(start.elapsed()) {
+                thread::sleep(rest);
+            }
+        }
+    }
+
+    // 1 / rate; zero, negativo ou NaN virariam uma Duration invalida e
+    // derrubariam o programa, entao o rate e limitado antes
+    fn interval(rate: f64) -> Duration {
+        let rate = if rate.is_nan() { Self::MIN_RATE } else { rate.clamp(Self::MIN_RATE, Self::MAX_RATE) };
+
+        Duration::from_secs_f64(1.0 / rate)
+    }
+
+    /// Frames drawn per second, measured about once a second.
+    pub fn fps(&self) -> f64 {
+        self.rates.fps()
+    }
+
+    /// Steps run per second, measured about once a second.
+    pub fn ups(&self) -> f64 {
+        self.rates.ups()
+    }
+}
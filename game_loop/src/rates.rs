@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+/// Frames and steps per second, averaged over a window of real time.
+#[derive(Debug, Clone)]
+pub struct Rates {
+    window: Duration,
+    elapsed: Duration,
+    frames: u32,
+    updates: u32,
+    fps: f64,
+    ups: f64,
+}
+
+impl Rates {
+    pub const DEFAULT_WINDOW: Duration = Duration::from_secs(1);
+
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            elapsed: Duration::ZERO,
+            frames: 0,
+            updates: 0,
+            fps: 0.0,
+            ups: 0.0,
+        }
+    }
+
+    /// Counts a frame that took `elapsed` and ran `updates` steps; true
+    /// when a window closed and the rates changed.
+    pub fn record(&mut self, elapsed: Duration, updates: u32) -> bool {
+        self.elapsed += elapsed;
+        self.frames += 1;
+        self.updates += updates;
+
+        if self.elapsed < self.window {
+            return false;
+        }
+
+        let seconds = self.elapsed.as_secs_f64();
+        self.fps = self.frames as f64 / seconds;
+        self.ups = self.updates as f64 / seconds;
+
+        self.elapsed = Duration::ZERO;
+        self.frames = 0;
+        self.updates = 0;
+
+        true
+    }
+
+    /// Zero until the first window closes.
+    pub fn fps(&self) -> f64 {
+        self.fps
+    }
+
+    pub fn ups(&self) -> f64 {
+        self.ups
+    }
+}
+
+impl Default for Rates {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
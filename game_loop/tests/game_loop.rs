@@ -0,0 +1,141 @@
+extern crate game_loop;
+
+use game_loop::{Frame, GameLoop, Rates};
+use std::time::Duration;
+
+fn millis(ms: u64) -> Duration {
+    Duration::from_millis(ms)
+}
+
+#[test]
+fn steps_follow_real_time_not_frames() {
+    let mut fast = GameLoop::new(100.0);
+    let mut slow = GameLoop::new(100.0);
+
+    let fast_updates: u32 = (0..100).map(|_| fast.advance(millis(5)).updates).sum();
+    let slow_updates: u32 = (0..10).map(|_| slow.advance(millis(50)).updates).sum();
+
+    assert_eq!(fast_updates, 50);
+    assert_eq!(slow_updates, 50);
+}
+
+#[test]
+fn leftover_time_becomes_alpha() {
+    let mut game_loop = GameLoop::new(100.0);
+
+    let frame = game_loop.advance(millis(25));
+    assert_eq!(frame.updates, 2);
+    assert!((frame.alpha - 0.5).abs() < 1e-9);
+
+    let frame = game_loop.advance(millis(5));
+    assert_eq!(frame.updates, 1);
+    assert!(frame.alpha.abs() < 1e-9);
+}
+
+#[test]
+fn interpolate_blends_the_last_step() {
+    let frame = Frame { updates: 1, alpha: 0.25, measured: false };
+
+    assert_eq!(frame.interpolate(10.0, 20.0), 12.5);
+}
+
+#[test]
+fn time_scale_speeds_up_and_slows_down() {
+    let mut game_loop = GameLoop::new(100.0);
+
+    game_loop.set_time_scale(2.0);
+    assert_eq!(game_loop.advance(millis(100)).updates, 20);
+
+    game_loop.set_time_scale(0.5);
+    assert_eq!(game_loop.advance(millis(100)).updates, 5);
+
+    game_loop.set_time_scale(1.0e9);
+    assert_eq!(game_loop.time_scale(), GameLoop::MAX_TIME_SCALE);
+
+    game_loop.set_time_scale(0.0);
+    assert_eq!(game_loop.time_scale(), GameLoop::MIN_TIME_SCALE);
+}
+
+#[test]
+fn long_frames_are_cut_short() {
+    let mut game_loop = GameLoop::new(100.0);
+    game_loop.set_max_frame_time(millis(100));
+
+    assert_eq!(game_loop.advance(Duration::from_secs(5)).updates, 10);
+    assert_eq!(game_loop.advance(millis(10)).updates, 1);
+}
+
+#[test]
+fn pause_holds_time_and_steps_on_request() {
+    let mut game_loop = GameLoop::new(100.0);
+    game_loop.advance(millis(15));
+
+    game_loop.toggle_pause();
+    assert!(game_loop.is_paused());
+    assert_eq!(game_loop.advance(millis(100)).updates, 0);
+
+    game_loop.step_once();
+    game_loop.step_once();
+    assert_eq!(game_loop.advance(millis(100)).updates, 2);
+    assert_eq!(game_loop.advance(millis(100)).updates, 0);
+
+    // o meio passo de antes da pausa continua guardado
+    game_loop.toggle_pause();
+    assert_eq!(game_loop.advance(millis(5)).updates, 1);
+}
+
+#[test]
+fn step_once_is_ignored_while_running() {
+    let mut game_loop = GameLoop::new(100.0);
+
+    game_loop.step_once();
+    assert_eq!(game_loop.advance(Duration::ZERO).updates, 0);
+}
+
+#[test]
+fn rates_are_measured_once_a_window() {
+    let mut game_loop = GameLoop::new(100.0);
+
+    let measured: Vec<bool> = (0..20).map(|_| game_loop.advance(millis(100)).measured).collect();
+
+    assert_eq!(measured.iter().filter(|&&measured| measured).count(), 2);
+    assert!(measured[9] && measured[19]);
+    assert!((game_loop.fps() - 10.0).abs() < 1e-9);
+    assert!((game_loop.ups() - 100.0).abs() < 1e-9);
+}
+
+#[test]
+fn rates_start_at_zero() {
+    let mut rates = Rates::new(millis(500));
+
+    assert!(!rates.record(millis(100), 3));
+    assert_eq!((rates.fps(), rates.ups()), (0.0, 0.0));
+
+    assert!(rates.record(millis(400), 2));
+    assert_eq!((rates.fps(), rates.ups()), (4.0, 10.0));
+}
+
+#[test]
+fn frame_rate_cap_is_optional() {
+    let mut game_loop = GameLoop::new(100.0);
+    assert_eq!(game_loop.max_fps().map(f64::round), Some(GameLoop::DEFAULT_FPS));
+
+    game_loop.set_max_fps(None);
+    assert_eq!(game_loop.max_fps(), None);
+    game_loop.tick();
+    game_loop.wait();
+}
+
+#[test]
+fn rates_out_of_range_are_clamped() {
+    for rate in [0.0, -5.0, f64::NAN] {
+        let mut game_loop = GameLoop::new(rate);
+        assert_eq!(game_loop.updates_per_second().round(), GameLoop::MIN_RATE);
+
+        game_loop.set_max_fps(Some(rate));
+        assert_eq!(game_loop.max_fps().map(f64::round), Some(GameLoop::MIN_RATE));
+    }
+
+    let game_loop = GameLoop::new(f64::INFINITY);
+    assert_eq!(game_loop.updates_per_second().round(), GameLoop::MAX_RATE);
+}
@@ -1,18 +1,76 @@
 extern crate sdl2;
-extern crate vectors;
+extern crate forces;
+extern crate game_loop;
 extern crate pendulum;
 
-use vectors::*;
+mod options;
+
+use forces::*;
+use game_loop::{controls, GameLoop};
+use options::{FlipMapOptions, Options};
 use pendulum::*;
+use pendulum::chaos::{Ensemble, FlipMap, Lyapunov};
+use pendulum::image;
+use pendulum::screen::Screen;
+use std::env;
 use std::f64::consts::PI;
+use std::process;
+use std::time::Instant;
 use sdl2::pixels::Color;
-use std::time::Duration;
 
+static TITLE: &str = "Pendulum Simulation";
 static CANVAS_SIZE: (u32, u32) = (800, 600);
 static CANVAS_COLOR: Color =  Color::RGB(255, 255, 255);
 static PENDULUM_COLOR: Color = Color::RGB(0, 0, 0);
+static UPDATES_PER_SECOND: f64 = 240.0;
+static PIXELS_PER_METER: f64 = 100.0;
+// rastro de cada pendulo, em passos
+static TRAJECTORY_LENGTH: usize = 2000;
+
+// cor do i-esimo pendulo do conjunto, do preto ao vermelho
+fn ensemble_color(i: usize, count: usize) -> Color {
+    if count <= 1 {
+        return PENDULUM_COLOR;
+    }
+
+    let t = i as f64 / (count - 1) as f64;
+    Color::RGB((220.0 * t) as u8, 0, (120.0 * (1.0 - t)) as u8)
+}
+
+// mapa do tempo ate a primeira volta completa, escrito num arquivo
+fn run_flip_map(options: &FlipMapOptions) -> Result<(), String> {
+    // hastes e massas iguais, como no fractal classico
+    let template = DoublePendulum::new((0.0, 0.0), (1.0, 1.0), (1.0, 1.0));
+
+    let start = Instant::now();
+    let map = FlipMap::compute(&template, (options.size, options.size), options.dt, options.max_time);
+    let flipped = map.times.iter().filter(|time| time.is_some()).count();
+
+    image::write(&options.path, map.width, map.height, &map.to_rgb(), options.format)
+        .map_err(|err| format!("could not write {}: {}", options.path.display(), err))?;
+
+    println!("{}x{} flip map in {:.1} s, {} of {} cells flipped within {} s, written to {}",
+             map.width, map.height, start.elapsed().as_secs_f64(), flipped, map.times.len(),
+             options.max_time, options.path.display());
+
+    Ok(())
+}
 
 fn main() {
+    let options = Options::new(env::args()).unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, options::USAGE);
+        process::exit(1);
+    });
+
+    if let Some(flip_map) = &options.flip_map {
+        if let Err(err) = run_flip_map(flip_map) {
+            eprintln!("Application error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
     let sdl_context = sdl2::init().unwrap();
     // inicializa a biblioteca sdl2 e retorna o tipo
     // do sld_context (Sdl)
@@ -21,7 +79,7 @@ fn main() {
     // inicializa a video subsystem de Sdl
 
     let window = video_subsystem
-            .window("Pendulum Simulation", CANVAS_SIZE.0, CANVAS_SIZE.1)
+            .window(TITLE, CANVAS_SIZE.0, CANVAS_SIZE.1)
             // inicializa um WindowsBuilder
             .position_centered()
             // centra a janela
@@ -37,20 +95,59 @@ fn main() {
     // é permitido existir apenas um event_pump
 
     let mut double_pendulum = DoublePendulum::new(
-        Vector::new(400.0, 300.0),
         (PI, PI / 2.0),
-        (100.0, 100.0),
+        (1.0, 1.0),
         (100.0, 0.5),
     );
+    double_pendulum.set_trajectory_length(Some(TRAJECTORY_LENGTH));
+    let screen = Screen::new(Vector::new(400.0, 300.0), PIXELS_PER_METER);
+    // hastes de 1 m penduradas no meio da janela
+
+    let initial_energy = double_pendulum.energy();
+
+    // copias quase iguais lado a lado; a primeira e a referencia
+    let mut ensemble = Ensemble::perturbed(&double_pendulum, options.ensemble, options.perturbation);
+    let mut lyapunov = Lyapunov::new(&double_pendulum, Lyapunov::DEFAULT_SEPARATION);
+
+    // passos de tamanho fixo em tempo real, qualquer que seja a
+    // velocidade da maquina; espaco pausa, N avanca um passo, [ e ]
+    // mudam a escala de tempo
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
 
     'running: loop {
         for event in event_pump.poll_iter() {
             // iterador sobre os eventos
-            match event {
-                sdl2::event::Event::Quit { .. } => break 'running,
+            if controls::handle(&mut game_loop, &event) {
+                continue;
+            }
+
+            if let sdl2::event::Event::Quit { .. } = event {
                 // clicar no X
-                _ => {}
+                break 'running;
+            }
+        }
+
+        let frame = game_loop.tick();
+        // quantos passos cabem no tempo que passou desde o ultimo quadro
+
+        for _ in 0..frame.updates {
+            let dt = game_loop.timestep().as_secs_f64();
+
+            ensemble.step(dt);
+            lyapunov.step(dt);
+        }
+
+        if frame.measured {
+            let energy = ensemble.pendulums()[0].energy();
+            let drift = ((energy - initial_energy) / initial_energy).abs();
+            let mut title = format!("{}  -  E {:.3} J  DRIFT {:.1e}  LYAPUNOV {:.2} /s",
+                                    TITLE, energy, drift, lyapunov.exponent());
+
+            if options.ensemble > 1 {
+                title += &format!("  DIVERGENCE {:.1e}", ensemble.max_divergence());
             }
+
+            controls::show_status(canvas.window_mut(), &title, &game_loop);
         }
 
         canvas.set_draw_color(CANVAS_COLOR);
@@ -59,17 +156,20 @@ fn main() {
         canvas.clear();
         // desenha a tela inteira
 
-        double_pendulum.apply_force();
-        double_pendulum.update_position();
-        double_pendulum.draw(&mut canvas, PENDULUM_COLOR);
+        let count = ensemble.pendulums().len();
+
+        for (i, pendulum) in ensemble.pendulums().iter().enumerate().rev() {
+            pendulum.draw(&mut canvas, &screen, ensemble_color(i, count));
+        }
 
-        double_pendulum.draw_trajectory(&mut canvas);
-        // println!("{}", double_pendulum.energy());
+        // so o rastro da referencia, para nao embaralhar o desenho
+        ensemble.pendulums()[0].draw_trajectory(&mut canvas, &screen);
 
         canvas.present();
         // atualiza a tela com qualquer renderização
         // feita antes da chamada
 
-        ::std::thread::sleep(Duration::new(0, 1_000_000u32));
+        game_loop.wait();
+        // dorme o resto do quadro
     }
 }
@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use pendulum::image::ImageFormat;
+
+pub static USAGE: &str = "usage: double_pendulum [--ensemble N [--perturbation RAD]] \
+                          [--flip-map FILE.ppm|FILE.png [--size PIXELS] [--max-time S] [--dt S]]";
+
+/// Draws the flip-time fractal to a file without opening a window.
+pub struct FlipMapOptions {
+    pub path: PathBuf,
+    pub format: ImageFormat,
+    pub size: usize,
+    pub max_time: f64,
+    pub dt: f64,
+}
+
+pub struct Options {
+    /// Pendulums run side by side, each a little off the previous one.
+    pub ensemble: usize,
+    pub perturbation: f64,
+    pub flip_map: Option<FlipMapOptions>,
+}
+
+fn parse<T: FromStr>(name: &str, value: String) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value `{}` for {}", value, name))
+}
+
+impl Options {
+    pub fn new<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        args.next();
+
+        let mut ensemble = 1;
+        let mut perturbation: Option<f64> = None;
+        let mut path = None;
+        let mut size = None;
+        let mut max_time: Option<f64> = None;
+        let mut dt: Option<f64> = None;
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+
+            match arg.as_str() {
+                "--ensemble" => ensemble = parse("--ensemble", value("--ensemble")?)?,
+                "--perturbation" => perturbation = Some(parse("--perturbation", value("--perturbation")?)?),
+                "--flip-map" => path = Some(PathBuf::from(value("--flip-map")?)),
+                "--size" => size = Some(parse("--size", value("--size")?)?),
+                "--max-time" => max_time = Some(parse("--max-time", value("--max-time")?)?),
+                "--dt" => dt = Some(parse("--dt", value("--dt")?)?),
+                _ if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg)),
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            }
+        }
+
+        if ensemble == 0 {
+            return Err("--ensemble needs at least one pendulum".to_string());
+        }
+
+        let flip_map_only = size.is_some() || max_time.is_some() || dt.is_some();
+        let perturbation = perturbation.unwrap_or(1.0e-4);
+        let size = size.unwrap_or(200);
+        let max_time = max_time.unwrap_or(20.0);
+        let dt = dt.unwrap_or(0.01);
+
+        if !perturbation.is_finite() {
+            return Err("--perturbation must be a finite number".to_string());
+        }
+
+        if size == 0 {
+            return Err("--size must be at least one pixel".to_string());
+        }
+
+        if !(max_time > 0.0 && max_time.is_finite()) {
+            return Err("--max-time must be a positive number of seconds".to_string());
+        }
+
+        if !(dt > 0.0 && dt.is_finite()) {
+            return Err("--dt must be a positive number of seconds".to_string());
+        }
+
+        let flip_map = match path {
+            Some(path) => Some(FlipMapOptions {
+                format: ImageFormat::from_path(&path)?,
+                path,
+                size,
+                max_time,
+                dt,
+            }),
+            None if flip_map_only => {
+                return Err("--size, --max-time and --dt need --flip-map".to_string());
+            },
+            None => None,
+        };
+
+        Ok(Options {
+            ensemble,
+            perturbation,
+            flip_map,
+        })
+    }
+}
@@ -1,22 +1,86 @@
 extern crate sdl2;
-extern crate vectors;
+extern crate forces;
+extern crate game_loop;
 extern crate pendulum;
 
-use vectors::*;
+use forces::*;
+use game_loop::{controls, GameLoop};
 use pendulum::*;
+use pendulum::forcing::{Damping, Drive};
+use pendulum::phase::{PhasePortrait, PoincareSection};
+use pendulum::screen::Screen;
 use std::f64::consts::PI;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
-use std::time::Duration;
+use sdl2::rect::Rect;
 
+static TITLE: &str = "Pendulum Simulation";
 static CANVAS_SIZE: (u32, u32) = (800, 600);
 static CANVAS_COLOR: Color =  Color::RGB(255, 255, 255);
 static PENDULUM_COLOR: Color = Color::RGB(0, 0, 0);
+static PHASE_COLOR: Color = Color::RGB(0, 0, 150);
+static SECTION_COLOR: Color = Color::RGB(150, 0, 0);
+static UPDATES_PER_SECOND: f64 = 240.0;
+static PIXELS_PER_METER: f64 = 200.0;
+static LENGTH: f64 = 1.5;
+static MASS: f64 = 1.0;
+// pendulo forcado classico, caotico: fator de qualidade 2, forca 1.5 e
+// frequencia 2/3, tudo relativo a frequencia natural
+static QUALITY: f64 = 2.0;
+static DRIVE_STRENGTH: f64 = 1.5;
+static DRIVE_FREQUENCY: f64 = 2.0 / 3.0;
+
+#[derive(Clone, Copy, PartialEq)]
+enum View {
+    Swing,
+    Phase,
+    Poincare,
+}
+
+impl View {
+    fn next(self) -> Self {
+        match self {
+            View::Swing => View::Phase,
+            View::Phase => View::Poincare,
+            View::Poincare => View::Swing,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            View::Swing => "pendulum",
+            View::Phase => "phase portrait",
+            View::Poincare => "Poincare section",
+        }
+    }
+}
+
+fn new_pendulum() -> Pendulum {
+    Pendulum::new(PI / 4.0, 0.0, LENGTH, MASS)
+}
+
+// amortecimento e forca do pendulo caotico, em unidades fisicas
+fn chaotic(pendulum: &Pendulum) -> (Damping, Drive) {
+    let natural = (pendulum.gravity() / pendulum.length).sqrt();
+    let inertia = pendulum.moment_of_inertia();
+
+    (Damping::new(natural / QUALITY * inertia, 0.0),
+     Drive::new(DRIVE_STRENGTH * natural * natural * inertia, DRIVE_FREQUENCY * natural, 0.0))
+}
+
+fn title(view: View, pendulum: &Pendulum) -> String {
+    format!("{}  -  {}{}{}  E {:.3} J", TITLE, view.name(),
+            if pendulum.damping() != Damping::default() { ", damped" } else { "" },
+            if pendulum.drive().is_some() { ", driven" } else { "" },
+            pendulum.energy())
+}
 
 fn main() {
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
     let window = video_subsystem
-            .window("Pendulum Simulation", CANVAS_SIZE.0, CANVAS_SIZE.1)
+            .window(TITLE, CANVAS_SIZE.0, CANVAS_SIZE.1)
             .position_centered()
             .build()
             .unwrap();
@@ -24,30 +88,90 @@ fn main() {
     let mut canvas = window.into_canvas().build().unwrap();
     let mut event_pump = sdl_context.event_pump().unwrap();
 
-    let mut pendulum = Pendulum::new(
-        Vector::new(400.0, 0.0),
-        PI / 4.0,
-        0.0,
-        300.0,
-        1.0,
-    );
+    // 1.5 m, pendurado no meio do topo da janela
+    let mut pendulum = new_pendulum();
+    let screen = Screen::new(Vector::new(400.0, 0.0), PIXELS_PER_METER);
+
+    // D liga o amortecimento, F a forca periodica; a secao de Poincare
+    // amostra uma vez por periodo da forca
+    let (damping, drive) = chaotic(&pendulum);
+    let mut portrait = PhasePortrait::default();
+    let mut section = PoincareSection::for_drive(&drive);
+
+    // V troca a vista, C apaga os graficos e R recomeca
+    let mut view = View::Swing;
+    let viewport = Rect::new(0, 0, CANVAS_SIZE.0, CANVAS_SIZE.1);
+
+    // espaco pausa, N avanca um passo, [ e ] mudam a escala de tempo
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
 
     'running: loop {
+        let mut retitle = false;
+
         for event in event_pump.poll_iter() {
+            if controls::handle(&mut game_loop, &event) {
+                continue;
+            }
+
             match event {
-                sdl2::event::Event::Quit { .. } => break 'running,
-                _ => {}
+                Event::Quit { .. } => break 'running,
+                Event::KeyDown { keycode: Some(Keycode::V), .. } => {
+                    view = view.next();
+                },
+                Event::KeyDown { keycode: Some(Keycode::D), .. } => {
+                    pendulum.set_damping(if pendulum.damping() == damping {
+                        Damping::default()
+                    } else {
+                        damping
+                    });
+                },
+                Event::KeyDown { keycode: Some(Keycode::F), .. } => {
+                    pendulum.set_drive(match pendulum.drive() {
+                        Some(_) => None,
+                        None => Some(drive),
+                    });
+                },
+                Event::KeyDown { keycode: Some(Keycode::C), .. } => {
+                    portrait.clear();
+                    section.clear();
+                },
+                Event::KeyDown { keycode: Some(Keycode::R), .. } => {
+                    let (damping, drive) = (pendulum.damping(), pendulum.drive());
+
+                    pendulum = new_pendulum();
+                    pendulum.set_damping(damping);
+                    pendulum.set_drive(drive);
+                    portrait.clear();
+                    section.clear();
+                },
+                _ => continue,
             }
+
+            retitle = true;
+        }
+
+        let frame = game_loop.tick();
+
+        for _ in 0..frame.updates {
+            pendulum.step(game_loop.timestep().as_secs_f64());
+            portrait.record(&pendulum);
+            section.record(&pendulum);
+        }
+
+        if frame.measured || retitle {
+            controls::show_status(canvas.window_mut(), &title(view, &pendulum), &game_loop);
         }
 
         canvas.set_draw_color(CANVAS_COLOR);
         canvas.clear();
 
-        pendulum.apply_force();
-        pendulum.update_position();
-        pendulum.draw(&mut canvas, PENDULUM_COLOR);
+        match view {
+            View::Swing => pendulum.draw(&mut canvas, &screen, PENDULUM_COLOR),
+            View::Phase => portrait.draw(&mut canvas, viewport, PHASE_COLOR),
+            View::Poincare => section.draw(&mut canvas, viewport, SECTION_COLOR),
+        }
 
         canvas.present();
-        ::std::thread::sleep(Duration::new(0, 1_000_000u32));
+        game_loop.wait();
     }
 }
@@ -0,0 +1,273 @@
+use std::f64::consts::PI;
+use std::thread;
+
+use crate::DoublePendulum;
+
+/// Distance between two double pendulums in phase space, mixing radians
+/// and radians per second.
+pub fn separation(a: &DoublePendulum, b: &DoublePendulum) -> f64 {
+    let differences = [
+        a.thetas.0 - b.thetas.0,
+        a.thetas.1 - b.thetas.1,
+        a.ang_velocities.0 - b.ang_velocities.0,
+        a.ang_velocities.1 - b.ang_velocities.1,
+    ];
+
+    differences.iter().map(|d| d * d).sum::<f64>().sqrt()
+}
+
+/// Copies of one double pendulum whose first angles differ by a tiny
+/// amount, run side by side.
+#[derive(Debug, Clone)]
+pub struct Ensemble {
+    pendulums: Vec<DoublePendulum>,
+}
+
+impl Ensemble {
+    /// `count` copies of `pendulum`, the i-th with its first angle moved by
+    /// `i * perturbation`; the first one is the unperturbed reference.
+    pub fn perturbed(pendulum: &DoublePendulum, count: usize, perturbation: f64) -> Self {
+        let pendulums = (0..count)
+            .map(|i| {
+                let mut copy = pendulum.clone();
+                copy.thetas.0 += i as f64 * perturbation;
+                copy
+            })
+            .collect();
+
+        Self { pendulums }
+    }
+
+    pub fn pendulums(&self) -> &[DoublePendulum] {
+        &self.pendulums
+    }
+
+    pub fn pendulums_mut(&mut self) -> &mut [DoublePendulum] {
+        &mut self.pendulums
+    }
+
+    pub fn step(&mut self, dt: f64) {
+        for pendulum in &mut self.pendulums {
+            pendulum.step(dt);
+        }
+    }
+
+    /// Separation of every pendulum from the reference, in order.
+    pub fn divergence(&self) -> Vec<f64> {
+        match self.pendulums.first() {
+            Some(reference) => self.pendulums.iter()
+                .map(|pendulum| separation(reference, pendulum))
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Largest separation from the reference.
+    pub fn max_divergence(&self) -> f64 {
+        self.divergence().into_iter().fold(0.0, f64::max)
+    }
+}
+
+/// Finite-time estimate of the largest Lyapunov exponent, in 1/s.
+///
+/// Follows a shadow copy `separation` away from the pendulum and, after
+/// every step, adds up how much the gap grew and shrinks it back to
+/// `separation` along the same direction (Benettin's method).
+#[derive(Debug, Clone)]
+pub struct Lyapunov {
+    reference: DoublePendulum,
+    shadow: DoublePendulum,
+    separation: f64,
+    // soma dos logaritmos de quanto a distancia cresceu
+    growth: f64,
+    time: f64,
+}
+
+impl Lyapunov {
+    pub const DEFAULT_SEPARATION: f64 = 1.0e-8;
+
+    pub fn new(pendulum: &DoublePendulum, separation: f64) -> Self {
+        let mut reference = pendulum.clone();
+        reference.set_trajectory_length(Some(0));
+
+        let mut shadow = reference.clone();
+        shadow.thetas.0 += separation;
+
+        Self {
+            reference,
+            shadow,
+            separation,
+            growth: 0.0,
+            time: 0.0,
+        }
+    }
+
+    pub fn reference(&self) -> &DoublePendulum {
+        &self.reference
+    }
+
+    pub fn step(&mut self, dt: f64) {
+        self.reference.step(dt);
+        self.shadow.step(dt);
+        self.time += dt;
+
+        let distance = separation(&self.reference, &self.shadow);
+
+        if distance == 0.0 || !distance.is_finite() {
+            return;
+        }
+
+        self.growth += (distance / self.separation).ln();
+
+        let scale = self.separation / distance;
+        let (reference, shadow) = (&self.reference, &mut self.shadow);
+
+        shadow.thetas.0 = reference.thetas.0 + (shadow.thetas.0 - reference.thetas.0) * scale;
+        shadow.thetas.1 = reference.thetas.1 + (shadow.thetas.1 - reference.thetas.1) * scale;
+        shadow.ang_velocities.0 = reference.ang_velocities.0
+            + (shadow.ang_velocities.0 - reference.ang_velocities.0) * scale;
+        shadow.ang_velocities.1 = reference.ang_velocities.1
+            + (shadow.ang_velocities.1 - reference.ang_velocities.1) * scale;
+    }
+
+    /// Average exponential growth rate so far; zero before the first step.
+    pub fn exponent(&self) -> f64 {
+        if self.time == 0.0 {
+            return 0.0;
+        }
+
+        self.growth / self.time
+    }
+
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
+
+/// Whether a rod can ever go over the top with the energy it has.
+///
+/// Flipping the second rod needs at least `2 m2 g l2` and flipping the
+/// first `2 (m1 + m2) g l1`.
+pub fn can_flip(pendulum: &DoublePendulum) -> bool {
+    let (m1, m2) = pendulum.masses;
+    let (l1, l2) = pendulum.lengths;
+    let g = pendulum.gravity();
+
+    pendulum.energy() >= (2.0 * m2 * g * l2).min(2.0 * (m1 + m2) * g * l1)
+}
+
+/// Seconds until either rod first goes over the top, starting from
+/// `pendulum` with both angles in [-π, π]; `None` if it does not happen
+/// within `max_time`.
+///
+/// Panics unless `dt` is positive and both times are finite, since the
+/// search would never end otherwise.
+pub fn flip_time(pendulum: &DoublePendulum, dt: f64, max_time: f64) -> Option<f64> {
+    assert!(dt > 0.0 && dt.is_finite(), "dt must be positive and finite, got {}", dt);
+    assert!(max_time.is_finite(), "max_time must be finite, got {}", max_time);
+
+    if !can_flip(pendulum) {
+        return None;
+    }
+
+    let mut pendulum = pendulum.clone();
+    pendulum.set_trajectory_length(Some(0));
+
+    let start = pendulum.time();
+
+    while pendulum.time() - start < max_time {
+        pendulum.step(dt);
+
+        if pendulum.thetas.0.abs() > PI || pendulum.thetas.1.abs() > PI {
+            return Some(pendulum.time() - start);
+        }
+    }
+
+    None
+}
+
+/// Flip times over a grid of initial angles, both from -π to π, starting
+/// at rest. `width` columns sweep the first angle and `height` rows the
+/// second, top row at π.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlipMap {
+    pub width: usize,
+    pub height: usize,
+    pub max_time: f64,
+    /// Row by row, `None` where nothing flipped within `max_time`.
+    pub times: Vec<Option<f64>>,
+}
+
+impl FlipMap {
+    /// Runs every cell of the grid, copying `pendulum` for its lengths,
+    /// masses, gravity and integrator. Rows are split between the
+    /// available cores. Panics on the same `dt` and `max_time` as
+    /// [`flip_time`].
+    pub fn compute(pendulum: &DoublePendulum,
+                   (width, height): (usize, usize),
+                   dt: f64,
+                   max_time: f64) -> Self
+    {
+        assert!(dt > 0.0 && dt.is_finite(), "dt must be positive and finite, got {}", dt);
+        assert!(max_time.is_finite(), "max_time must be finite, got {}", max_time);
+
+        let threads = thread::available_parallelism().map_or(1, |count| count.get());
+        let mut times = vec![None; width * height];
+
+        if times.is_empty() {
+            return Self { width, height, max_time, times };
+        }
+
+        thread::scope(|scope| {
+            let rows_per_thread = height.div_ceil(threads);
+
+            for (chunk, cells) in times.chunks_mut(rows_per_thread * width).enumerate() {
+                scope.spawn(move || {
+                    for (i, cell) in cells.iter_mut().enumerate() {
+                        let (row, column) = (chunk * rows_per_thread + i / width, i % width);
+
+                        let mut start = pendulum.clone();
+                        start.thetas = Self::angles((width, height), column, row);
+                        start.ang_velocities = (0.0, 0.0);
+
+                        *cell = flip_time(&start, dt, max_time);
+                    }
+                });
+            }
+        });
+
+        Self { width, height, max_time, times }
+    }
+
+    /// Initial angles at the center of a cell.
+    pub fn angles((width, height): (usize, usize), column: usize, row: usize) -> (f64, f64) {
+        (-PI + 2.0 * PI * (column as f64 + 0.5) / width as f64,
+         PI - 2.0 * PI * (row as f64 + 0.5) / height as f64)
+    }
+
+    pub fn get(&self, column: usize, row: usize) -> Option<f64> {
+        self.times[row * self.width + column]
+    }
+
+    /// RGB pixels, row by row: black where nothing flipped, otherwise
+    /// a color running from bright (fast flips) to dark (slow ones) on a
+    /// logarithmic scale.
+    pub fn to_rgb(&self) -> Vec<u8> {
+        let longest = self.max_time.ln_1p();
+
+        self.times.iter()
+            .flat_map(|time| match time {
+                Some(time) => color(time.ln_1p() / longest),
+                None => [0, 0, 0],
+            })
+            .collect()
+    }
+}
+
+// 0 = branco-amarelado, 1 = roxo escuro
+fn color(t: f64) -> [u8; 3] {
+    let t = t.clamp(0.0, 1.0);
+    let channel = |from: f64, to: f64| (255.0 * (from + (to - from) * t)).round() as u8;
+
+    [channel(1.0, 0.25), channel(0.95, 0.0), channel(0.6, 0.35)]
+}
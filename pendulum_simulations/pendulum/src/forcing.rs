@@ -0,0 +1,48 @@
+use std::f64::consts::PI;
+
+/// Friction torque opposing the swing, `-linear ω - quadratic ω|ω|`.
+///
+/// `linear` is in N·m·s (viscous drag at low speed) and `quadratic` in
+/// N·m·s² (air drag at high speed).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Damping {
+    pub linear: f64,
+    pub quadratic: f64,
+}
+
+impl Damping {
+    pub fn new(linear: f64, quadratic: f64) -> Self {
+        Self { linear, quadratic }
+    }
+
+    /// Torque at angular velocity `ang_velocity`.
+    pub fn torque(&self, ang_velocity: f64) -> f64 {
+        -self.linear * ang_velocity - self.quadratic * ang_velocity * ang_velocity.abs()
+    }
+}
+
+/// Periodic driving torque, `torque cos(frequency t + phase)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drive {
+    /// Peak torque, in N·m.
+    pub torque: f64,
+    /// Angular frequency, in rad/s.
+    pub frequency: f64,
+    pub phase: f64,
+}
+
+impl Drive {
+    pub fn new(torque: f64, frequency: f64, phase: f64) -> Self {
+        Self { torque, frequency, phase }
+    }
+
+    /// Torque at `time`.
+    pub fn torque_at(&self, time: f64) -> f64 {
+        self.torque * (self.frequency * time + self.phase).cos()
+    }
+
+    /// Seconds between two peaks.
+    pub fn period(&self) -> f64 {
+        2.0 * PI / self.frequency
+    }
+}
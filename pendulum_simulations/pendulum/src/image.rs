@@ -0,0 +1,132 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+// maior bloco "stored" do deflate
+const MAX_STORED_BLOCK: usize = 65535;
+
+/// Picture formats `write` knows, both written without compression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Binary PPM (P6).
+    Ppm,
+    Png,
+}
+
+impl ImageFormat {
+    /// Format matching the extension of `path`.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("ppm") => Ok(ImageFormat::Ppm),
+            Some(extension) if extension.eq_ignore_ascii_case("png") => Ok(ImageFormat::Png),
+            _ => Err(format!("`{}` should end in .ppm or .png", path.display())),
+        }
+    }
+}
+
+/// Writes `rgb`, three bytes per pixel row by row, as a `width` by
+/// `height` picture.
+pub fn write(path: &Path, width: usize, height: usize, rgb: &[u8], format: ImageFormat) -> io::Result<()> {
+    assert_eq!(rgb.len(), width * height * 3, "expected {}x{} RGB pixels", width, height);
+
+    let mut file = BufWriter::new(File::create(path)?);
+
+    match format {
+        ImageFormat::Ppm => write_ppm(&mut file, width, height, rgb)?,
+        ImageFormat::Png => write_png(&mut file, width, height, rgb)?,
+    }
+
+    file.flush()
+}
+
+pub fn write_ppm<W: Write>(out: &mut W, width: usize, height: usize, rgb: &[u8]) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", width, height)?;
+    out.write_all(rgb)
+}
+
+/// Fails with `InvalidInput` for an empty picture or one too large for
+/// the 32 bit sizes of the header; nothing is written then.
+pub fn write_png<W: Write>(out: &mut W, width: usize, height: usize, rgb: &[u8]) -> io::Result<()> {
+    let dimension = |value: usize| match u32::try_from(value) {
+        Ok(value) if value > 0 => Ok(value),
+        _ => Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                format!("PNG size {}x{} out of range", width, height))),
+    };
+
+    let (png_width, png_height) = (dimension(width)?, dimension(height)?);
+
+    out.write_all(b"\x89PNG\r\n\x1a\n")?;
+
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&png_width.to_be_bytes());
+    header.extend_from_slice(&png_height.to_be_bytes());
+    // 8 bits, RGB, deflate, filtro adaptativo, sem entrelacamento
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    write_chunk(out, b"IHDR", &header)?;
+
+    // cada linha comeca com o filtro 0 (nenhum)
+    let mut raw = Vec::with_capacity(height * (1 + width * 3));
+    for row in rgb.chunks(width * 3).take(height) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+
+    write_chunk(out, b"IDAT", &zlib_stored(&raw))?;
+    write_chunk(out, b"IEND", &[])
+}
+
+fn write_chunk<W: Write>(out: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    out.write_all(&(data.len() as u32).to_be_bytes())?;
+    out.write_all(kind)?;
+    out.write_all(data)?;
+
+    let crc = crc32(&[kind.as_slice(), data].concat());
+    out.write_all(&crc.to_be_bytes())
+}
+
+// fluxo zlib com blocos deflate sem compressao
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+    let blocks: Vec<&[u8]> = data.chunks(MAX_STORED_BLOCK).collect();
+
+    if blocks.is_empty() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+
+    for (i, block) in blocks.iter().enumerate() {
+        let length = block.len() as u16;
+
+        out.push((i == blocks.len() - 1) as u8);
+        out.extend_from_slice(&length.to_le_bytes());
+        out.extend_from_slice(&(!length).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+
+    for &byte in data {
+        crc ^= byte as u32;
+
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
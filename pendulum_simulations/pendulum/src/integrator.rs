@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+
+pub use forces::{Rk4, Rk45};
+
+/// Maps the time, the angles and the angular velocities to the angular
+/// accelerations.
+pub type Acceleration<'a> = dyn Fn(f64, &[f64], &[f64]) -> Vec<f64> + 'a;
+
+/// Advances a set of angles by one timestep.
+pub trait Integrator: Debug + Send + Sync + CloneIntegrator {
+    fn step(&mut self,
+            time: f64,
+            angles: &mut [f64],
+            velocities: &mut [f64],
+            dt: f64,
+            acceleration: &Acceleration);
+}
+
+/// Lets a boxed integrator be cloned along with its pendulum; implemented
+/// for every `Integrator` that is `Clone`.
+pub trait CloneIntegrator {
+    fn clone_box(&self) -> Box<dyn Integrator>;
+}
+
+impl<I: Integrator + Clone + 'static> CloneIntegrator for I {
+    fn clone_box(&self) -> Box<dyn Integrator> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn Integrator> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Semi-implicit Euler: the velocity is kicked first and then moves the
+/// angle. With `dt = 1.0` this is what the pendulum originally did.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euler;
+
+impl Integrator for Euler {
+    fn step(&mut self,
+            time: f64,
+            angles: &mut [f64],
+            velocities: &mut [f64],
+            dt: f64,
+            acceleration: &Acceleration)
+    {
+        let accelerations = acceleration(time, angles, velocities);
+
+        for i in 0..angles.len() {
+            velocities[i] += accelerations[i] * dt;
+            angles[i] += velocities[i] * dt;
+        }
+    }
+}
+
+/// Kick-drift-kick velocity Verlet: second order, and symplectic while the
+/// acceleration only depends on the angles.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VelocityVerlet;
+
+impl Integrator for VelocityVerlet {
+    fn step(&mut self,
+            time: f64,
+            angles: &mut [f64],
+            velocities: &mut [f64],
+            dt: f64,
+            acceleration: &Acceleration)
+    {
+        let before = acceleration(time, angles, velocities);
+
+        for i in 0..angles.len() {
+            velocities[i] += before[i] * (0.5 * dt);
+            angles[i] += velocities[i] * dt;
+        }
+
+        let after = acceleration(time + dt, angles, velocities);
+
+        for i in 0..angles.len() {
+            velocities[i] += after[i] * (0.5 * dt);
+        }
+    }
+}
+
+/// The fourth order Runge-Kutta shared with the planets simulation.
+impl Integrator for Rk4 {
+    fn step(&mut self,
+            time: f64,
+            angles: &mut [f64],
+            velocities: &mut [f64],
+            dt: f64,
+            acceleration: &Acceleration)
+    {
+        self.advance(time, angles, velocities, dt, acceleration);
+    }
+}
+
+/// The adaptive integrator shared with the planets simulation.
+impl Integrator for Rk45 {
+    fn step(&mut self,
+            time: f64,
+            angles: &mut [f64],
+            velocities: &mut [f64],
+            dt: f64,
+            acceleration: &Acceleration)
+    {
+        self.advance(time, angles, velocities, dt, acceleration);
+    }
+}
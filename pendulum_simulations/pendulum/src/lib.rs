@@ -1,66 +1,183 @@
-extern crate vectors;
+extern crate forces;
 extern crate sdl2;
 
-use vectors::*;
+pub mod chaos;
+pub mod forcing;
+pub mod image;
+pub mod integrator;
+pub mod phase;
+pub mod screen;
+
+use forces::*;
+use forcing::{Damping, Drive};
+use integrator::{Euler, Integrator, Rk4};
+use screen::Screen;
 use sdl2::pixels::Color;
 use sdl2::render::Canvas;
 use sdl2::rect::Rect;
 use std::f64::consts::PI;
 
-#[derive(Debug)]
+/// A rigid pendulum swinging about a fixed pivot.
+///
+/// Works in meters, kilograms and seconds, with `theta` measured from
+/// straight down; a `Screen` turns it into pixels when drawing. The bob
+/// is a point mass on a massless rod, optionally damped and driven.
+#[derive(Debug, Clone)]
 pub struct Pendulum {
-    pub origin: Vector,
-    pub end: Vector,
     pub theta: f64,
     pub ang_velocity: f64,
     pub length: f64,
     pub mass: f64,
+    gravity: f64,
+    damping: Damping,
+    drive: Option<Drive>,
+    time: f64,
+    integrator: Box<dyn Integrator>,
 }
 
 impl Pendulum {
-    const g: f64 = 1.0e-1;
+    /// Standard gravity, in m/s².
+    pub const G: f64 = 9.81;
 
-    pub fn new(origin: Vector,
-               theta: f64,
+    pub fn new(theta: f64,
                ang_velocity: f64,
                length: f64,
-               mass: f64) -> Self 
+               mass: f64) -> Self
     {
-        let end_x = origin.x + length * theta.sin();
-        let end_y = origin.y + length * theta.cos();
-
         Self {
-            origin,
-            end: Vector::new(end_x, end_y),
             theta,
             ang_velocity,
             length,
             mass,
+            gravity: Self::G,
+            damping: Damping::default(),
+            drive: None,
+            time: 0.0,
+            integrator: Box::new(Rk4),
         }
     }
 
-    pub fn update_position(&mut self) {
-        self.end.x = &self.origin.x + self.length * self.theta.sin();
-        self.end.y = &self.origin.y + self.length * self.theta.cos();
+    pub fn set_gravity(&mut self, gravity: f64) {
+        self.gravity = gravity;
+    }
+
+    pub fn gravity(&self) -> f64 {
+        self.gravity
+    }
+
+    pub fn set_damping(&mut self, damping: Damping) {
+        self.damping = damping;
     }
 
-    pub fn apply_force(&mut self) {
-        self.ang_velocity += -(Self::g * self.theta.sin()) / self.length;
-        self.theta += self.ang_velocity;
+    pub fn damping(&self) -> Damping {
+        self.damping
+    }
+
+    /// `None` leaves the pendulum swinging freely.
+    pub fn set_drive(&mut self, drive: Option<Drive>) {
+        self.drive = drive;
+    }
+
+    pub fn drive(&self) -> Option<Drive> {
+        self.drive
+    }
+
+    pub fn set_integrator<I: Integrator + 'static>(&mut self, integrator: I) {
+        self.integrator = Box::new(integrator);
+    }
+
+    /// Seconds simulated so far.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// m L², in kg·m².
+    pub fn moment_of_inertia(&self) -> f64 {
+        self.mass * self.length * self.length
+    }
+
+    /// Angular acceleration from gravity, damping and the drive.
+    pub fn angular_acceleration(&self, time: f64, theta: f64, ang_velocity: f64) -> f64 {
+        let torque = self.damping.torque(ang_velocity)
+            + self.drive.map_or(0.0, |drive| drive.torque_at(time));
+
+        -self.gravity / self.length * theta.sin() + torque / self.moment_of_inertia()
+    }
+
+    /// Advances the pendulum by `dt` seconds.
+    pub fn step(&mut self, dt: f64) {
+        let mut angles = [self.theta];
+        let mut velocities = [self.ang_velocity];
+
+        // o integrador sai emprestado enquanto a aceleracao le o resto
+        let mut integrator = std::mem::replace(&mut self.integrator, Box::new(Euler));
+        let acceleration = |time: f64, angles: &[f64], velocities: &[f64]| {
+            vec![self.angular_acceleration(time, angles[0], velocities[0])]
+        };
+
+        integrator.step(self.time, &mut angles, &mut velocities, dt, &acceleration);
+        self.integrator = integrator;
+
+        self.theta = angles[0];
+        self.ang_velocity = velocities[0];
+        self.time += dt;
+    }
+
+    /// Kinetic plus potential energy, zero when hanging still.
+    pub fn energy(&self) -> f64 {
+        let kinetic = 0.5 * self.moment_of_inertia() * self.ang_velocity.powi(2);
+        let potential = self.mass * self.gravity * self.length * (1.0 - self.theta.cos());
+
+        kinetic + potential
+    }
+
+    /// Position of the bob in meters from the pivot, `y` pointing down.
+    pub fn bob(&self) -> Vector {
+        Vector::new(self.length * self.theta.sin(), self.length * self.theta.cos())
+    }
+
+    /// Period of small swings, 2π√(L/g).
+    pub fn small_angle_period(&self) -> f64 {
+        2.0 * PI * (self.length / self.gravity).sqrt()
+    }
+
+    /// Widest angle the pendulum reaches without damping or drive, `None`
+    /// if it goes over the top.
+    pub fn amplitude(&self) -> Option<f64> {
+        let cos = self.theta.cos() - self.ang_velocity.powi(2) * self.length / (2.0 * self.gravity);
+
+        if cos <= -1.0 {
+            return None;
+        }
+
+        Some(cos.acos())
+    }
+
+    /// Exact period of the current swing without damping or drive, from
+    /// the complete elliptic integral of the first kind; `None` if the
+    /// pendulum goes over the top.
+    pub fn period(&self) -> Option<f64> {
+        let amplitude = self.amplitude()?;
+
+        // K(k) = π / (2 agm(1, √(1 - k²))) com k = sen(θ0 / 2)
+        Some(self.small_angle_period() / agm(1.0, (amplitude / 2.0).cos()))
     }
 
     pub fn draw(&self, canvas: &mut Canvas<sdl2::video::Window>,
+                screen: &Screen,
                 color: Color)
     {
+        let pivot = screen.to_pixels(&Vector::new(0.0, 0.0));
+        let bob = screen.to_pixels(&self.bob());
+
         canvas.set_draw_color(color);
-        canvas.draw_line((self.origin.x as i32, self.origin.y as i32),
-                         (self.end.x as i32, self.end.y as i32)).unwrap();
+        canvas.draw_line(pivot, bob).unwrap();
 
         let rect = Rect::new(
-            (self.end.x - 20.0 / 2.0) as i32,
-            (self.end.y - 20.0 / 2.0) as i32,
-            20.0 as u32,
-            20.0 as u32,
+            bob.0 - 20 / 2,
+            bob.1 - 20 / 2,
+            20,
+            20,
         );
 
         canvas.set_draw_color(color);
@@ -68,80 +185,95 @@ impl Pendulum {
     }
 }
 
+// media aritmetico-geometrica
+fn agm(mut a: f64, mut b: f64) -> f64 {
+    while (a - b).abs() > 1e-15 * a {
+        (a, b) = (0.5 * (a + b), (a * b).sqrt());
+    }
+
+    a
+}
+
+/// Two rigid pendulums, the second hanging from the bob of the first.
+///
+/// Like `Pendulum` it works in meters, kilograms and seconds, both angles
+/// measured from straight down and left unwrapped, so a full flip shows as
+/// a change of 2π.
+#[derive(Debug, Clone)]
 pub struct DoublePendulum {
-    pub origins: (Vector, Vector),
-    pub ends: (Vector, Vector),
     pub thetas: (f64, f64),
     pub ang_velocities: (f64, f64),
     pub lengths: (f64, f64),
     pub masses: (f64, f64),
+    /// Bob positions after each step, in meters from the pivot.
     pub trajetories: Vec<Vec<Vector>>,
+    trajectory_length: Option<usize>,
+    gravity: f64,
+    time: f64,
+    integrator: Box<dyn Integrator>,
 }
 
 impl DoublePendulum {
-    const g: f64 = 1.0;
-
     pub fn new(
-        origin: Vector,
         thetas: (f64, f64),
         lengths: (f64, f64),
         masses: (f64, f64)
     ) -> Self
     {
-        let end_x_0 = origin.x + lengths.0 * thetas.0.sin();
-        let end_y_0 = origin.y + lengths.0 * thetas.0.cos();
-
-        let end_0 = Vector::new(end_x_0, end_y_0);
-        let origin_1 = end_0.clone();
-
-        let end_x_1 = origin_1.x + lengths.1 * thetas.1.sin();
-        let end_y_1 = origin_1.y + lengths.1 * thetas.1.cos();
-
-        let ends = (Vector::new(end_x_0, end_y_0),
-                    Vector::new(end_x_1, end_y_1));
-
         Self {
-            origins: (origin, origin_1),
-            ends,
             thetas,
             ang_velocities: (0.0, 0.0),
             lengths,
             masses,
             trajetories: vec![vec![], vec![]],
+            trajectory_length: None,
+            gravity: Pendulum::G,
+            time: 0.0,
+            integrator: Box::new(Rk4),
         }
     }
 
-    pub fn update_position(&mut self) {
-        self.ends.0.x = &self.origins.0.x + self.lengths.0 *
-                        self.thetas.0.sin();
-        self.ends.0.y = &self.origins.0.y + self.lengths.0 *
-                        self.thetas.0.cos();
+    pub fn set_gravity(&mut self, gravity: f64) {
+        self.gravity = gravity;
+    }
+
+    pub fn gravity(&self) -> f64 {
+        self.gravity
+    }
+
+    pub fn set_integrator<I: Integrator + 'static>(&mut self, integrator: I) {
+        self.integrator = Box::new(integrator);
+    }
+
+    /// Keeps only the last `length` positions of each bob; `None` keeps
+    /// them all and `Some(0)` records nothing.
+    pub fn set_trajectory_length(&mut self, length: Option<usize>) {
+        self.trajectory_length = length;
+        self.trim_trajectories();
+    }
 
-        self.ends.1.x = &self.origins.1.x + self.lengths.1 *
-                        self.thetas.1.sin();
-        self.ends.1.y = &self.origins.1.y + self.lengths.1 *
-                        self.thetas.1.cos();
+    pub fn trajectory_length(&self) -> Option<usize> {
+        self.trajectory_length
+    }
 
-        self.origins.1 = self.ends.0.clone();
-        self.trajetories[0].push(self.ends.0.clone());
-        self.trajetories[1].push(self.ends.1.clone())
+    /// Seconds simulated so far.
+    pub fn time(&self) -> f64 {
+        self.time
     }
 
-    pub fn apply_force(&mut self) {
-        let m1 = self.masses.0;
-        let m2 = self.masses.1;
-        let l1 = self.lengths.0;
-        let l2 = self.lengths.1;
-        let g = Self::g;
-        let theta1 = self.thetas.0;
-        let theta2 = self.thetas.1;
-        let omega1 = self.ang_velocities.0;
-        let omega2 = self.ang_velocities.1;
+    /// Angular accelerations of both rods for the given angles and angular
+    /// velocities, from the equations of motion of the Lagrangian.
+    pub fn angular_accelerations(&self, thetas: (f64, f64), ang_velocities: (f64, f64)) -> (f64, f64) {
+        let (m1, m2) = self.masses;
+        let (l1, l2) = self.lengths;
+        let g = self.gravity;
+        let (theta1, theta2) = thetas;
+        let (omega1, omega2) = ang_velocities;
 
         let denom = 2.0 * m1 + m2 - m2 * (2.0 * (theta1 - theta2)).cos();
 
         if denom.abs() < 1e-10 {
-            return;
+            return (0.0, 0.0);
         }
 
         let num1 = -g * (2.0 * m1 + m2) * theta1.sin()
@@ -150,38 +282,83 @@ impl DoublePendulum {
                 * m2
                 * (omega2.powi(2) * l2 + omega1.powi(2) * l1 * (theta1 - theta2).cos());
 
-        let acel_0 = num1 / (l1 * denom);
-
         let num2 = 2.0 * (theta1 - theta2).sin()
             * (omega1.powi(2) * l1 * (m1 + m2)
                 + g * (m1 + m2) * theta1.cos()
                 + omega2.powi(2) * l2 * m2 * (theta1 - theta2).cos());
 
-        let acel_1 = num2 / (l2 * denom);
+        (num1 / (l1 * denom), num2 / (l2 * denom))
+    }
 
-        let dt = 1.0;
+    /// Advances both rods by `dt` seconds and records where the bobs went.
+    pub fn step(&mut self, dt: f64) {
+        let mut angles = [self.thetas.0, self.thetas.1];
+        let mut velocities = [self.ang_velocities.0, self.ang_velocities.1];
+
+        // como em `Pendulum::step`
+        let mut integrator = std::mem::replace(&mut self.integrator, Box::new(Euler));
+        let acceleration = |_: f64, angles: &[f64], velocities: &[f64]| {
+            let (a1, a2) = self.angular_accelerations((angles[0], angles[1]), (velocities[0], velocities[1]));
+            vec![a1, a2]
+        };
+
+        integrator.step(self.time, &mut angles, &mut velocities, dt, &acceleration);
+        self.integrator = integrator;
+
+        self.thetas = (angles[0], angles[1]);
+        self.ang_velocities = (velocities[0], velocities[1]);
+        self.time += dt;
+
+        let (first, second) = self.bobs();
+        self.trajetories[0].push(first);
+        self.trajetories[1].push(second);
+        self.trim_trajectories();
+    }
 
-        self.ang_velocities.0 += acel_0 * dt;
-        self.ang_velocities.1 += acel_1 * dt;
+    fn trim_trajectories(&mut self) {
+        if let Some(length) = self.trajectory_length {
+            for trajectory in &mut self.trajetories {
+                let excess = trajectory.len().saturating_sub(length);
+                trajectory.drain(..excess);
+            }
+        }
+    }
 
-        self.thetas.0 += self.ang_velocities.0 * dt;
-        self.thetas.1 += self.ang_velocities.1 * dt;
+    /// Positions of both bobs in meters from the pivot, `y` pointing down.
+    pub fn bobs(&self) -> (Vector, Vector) {
+        let first = Vector::new(self.lengths.0 * self.thetas.0.sin(),
+                                self.lengths.0 * self.thetas.0.cos());
+        let second = Vector::new(first.x + self.lengths.1 * self.thetas.1.sin(),
+                                 first.y + self.lengths.1 * self.thetas.1.cos());
 
-        self.thetas.0 = self.thetas.0.rem_euclid(2.0 * std::f64::consts::PI);
-        self.thetas.1 = self.thetas.1.rem_euclid(2.0 * std::f64::consts::PI);
+        (first, second)
     }
 
-    pub fn energy(&self) -> f64 {
-        let c1 = 0.5 * self.masses.0 * (self.ang_velocities.0 * self.lengths.0).powi(2);
-        let c2 = 0.5 * self.masses.1 * (self.ang_velocities.1 * self.lengths.1).powi(2);
+    pub fn kinetic_energy(&self) -> f64 {
+        let (m1, m2) = self.masses;
+        let (l1, l2) = self.lengths;
+        let (omega1, omega2) = self.ang_velocities;
 
-        let p1 = self.masses.0 * Self::g * self.ends.0.y;
-        let p2 = self.masses.1 * Self::g * self.ends.1.y;
+        0.5 * (m1 + m2) * (l1 * omega1).powi(2)
+            + 0.5 * m2 * (l2 * omega2).powi(2)
+            + m2 * l1 * l2 * omega1 * omega2 * (self.thetas.0 - self.thetas.1).cos()
+    }
+
+    /// Zero when both rods hang straight down.
+    pub fn potential_energy(&self) -> f64 {
+        let (m1, m2) = self.masses;
+        let (l1, l2) = self.lengths;
 
-        c1 + c2 + p1 + p2
+        self.gravity * ((m1 + m2) * l1 * (1.0 - self.thetas.0.cos())
+            + m2 * l2 * (1.0 - self.thetas.1.cos()))
+    }
+
+    /// Kinetic plus potential energy, conserved by the exact motion.
+    pub fn energy(&self) -> f64 {
+        self.kinetic_energy() + self.potential_energy()
     }
 
-    pub fn draw_trajectory(&mut self, canvas: &mut Canvas<sdl2::video::Window>) {
+    pub fn draw_trajectory(&self, canvas: &mut Canvas<sdl2::video::Window>, screen: &Screen) {
         if self.trajetories[0].len() < 2 || self.trajetories[1].len() < 2{
             return;
         }
@@ -191,8 +368,7 @@ impl DoublePendulum {
             let end = &self.trajetories[0][i];
 
             canvas.set_draw_color(Color::RGB(0, 0, 100));
-            canvas.draw_line((start.x as i32, start.y as i32),
-                             (end.x as i32, end.y as i32)).unwrap();
+            canvas.draw_line(screen.to_pixels(start), screen.to_pixels(end)).unwrap();
         }
 
         for i in 1..self.trajetories[1].len() {
@@ -200,38 +376,38 @@ impl DoublePendulum {
             let end = &self.trajetories[1][i];
 
             canvas.set_draw_color(Color::RGB(0, 50, 255));
-            canvas.draw_line((start.x as i32, start.y as i32),
-                             (end.x as i32, end.y as i32)).unwrap();
+            canvas.draw_line(screen.to_pixels(start), screen.to_pixels(end)).unwrap();
         }
     }
 
     pub fn draw(&self, canvas: &mut Canvas<sdl2::video::Window>,
+                screen: &Screen,
                 color: Color)
     {
-        canvas.set_draw_color(color);
-        canvas.draw_line((self.origins.0.x as i32, self.origins.0.y as i32),
-                         (self.ends.0.x as i32, self.ends.0.y as i32)).unwrap();
-
-        canvas.draw_line((self.origins.1.x as i32, self.origins.1.y as i32),
-                         (self.ends.1.x as i32, self.ends.1.y as i32)).unwrap();
+        let pivot = screen.to_pixels(&Vector::new(0.0, 0.0));
+        let (first, second) = self.bobs();
+        let (first, second) = (screen.to_pixels(&first), screen.to_pixels(&second));
 
+        canvas.set_draw_color(color);
+        canvas.draw_line(pivot, first).unwrap();
+        canvas.draw_line(first, second).unwrap();
 
         let rect_0 = Rect::new(
-            (self.ends.0.x - 20.0 / 2.0) as i32,
-            (self.ends.0.y - 20.0 / 2.0) as i32,
-            20.0 as u32,
-            20.0 as u32,
+            first.0 - 20 / 2,
+            first.1 - 20 / 2,
+            20,
+            20,
         );
 
         let rect_1 = Rect::new(
-            (self.ends.1.x - 10.0 / 2.0) as i32,
-            (self.ends.1.y - 10.0 / 2.0) as i32,
-            10.0 as u32,
-            10.0 as u32,
+            second.0 - 10 / 2,
+            second.1 - 10 / 2,
+            10,
+            10,
         );
 
         canvas.set_draw_color(color);
         canvas.fill_rect(rect_0).unwrap();
         canvas.fill_rect(rect_1).unwrap();
     }
-}
\ No newline at end of file
+}
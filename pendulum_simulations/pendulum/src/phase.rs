@@ -0,0 +1,174 @@
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use sdl2::pixels::Color;
+use sdl2::rect::{Point, Rect};
+use sdl2::render::Canvas;
+
+use crate::forcing::Drive;
+use crate::Pendulum;
+
+static AXIS_COLOR: Color = Color::RGB(200, 200, 200);
+// folga acima da maior velocidade do grafico
+static MARGIN: f64 = 1.1;
+
+/// `theta` brought into [-π, π).
+pub fn wrap_angle(theta: f64) -> f64 {
+    (theta + PI).rem_euclid(2.0 * PI) - PI
+}
+
+/// Points (θ, ω) of phase space, θ wrapped into [-π, π); the oldest are
+/// dropped past `length`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhasePortrait {
+    points: VecDeque<(f64, f64)>,
+    length: usize,
+}
+
+impl PhasePortrait {
+    pub const DEFAULT_LENGTH: usize = 5000;
+
+    /// Panics when `length` is 0: the portrait would keep every point.
+    pub fn new(length: usize) -> Self {
+        assert!(length > 0, "a phase portrait needs room for at least one point");
+
+        Self {
+            points: VecDeque::new(),
+            length,
+        }
+    }
+
+    pub fn push(&mut self, theta: f64, ang_velocity: f64) {
+        if self.points.len() == self.length {
+            self.points.pop_front();
+        }
+
+        self.points.push_back((wrap_angle(theta), ang_velocity));
+    }
+
+    /// Adds the current state of `pendulum`.
+    pub fn record(&mut self, pendulum: &Pendulum) {
+        self.push(pendulum.theta, pendulum.ang_velocity);
+    }
+
+    pub fn points(&self) -> &VecDeque<(f64, f64)> {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Plots the points inside `viewport`, θ across and ω up, scaled to the
+    /// fastest point.
+    pub fn draw(&self, canvas: &mut Canvas<sdl2::video::Window>, viewport: Rect, color: Color) {
+        let center = viewport.center();
+
+        canvas.set_draw_color(AXIS_COLOR);
+        canvas.draw_line((viewport.left(), center.y), (viewport.right(), center.y)).unwrap();
+        canvas.draw_line((center.x, viewport.top()), (center.x, viewport.bottom())).unwrap();
+
+        let fastest = self.points.iter()
+            .map(|&(_, ang_velocity)| ang_velocity.abs())
+            .fold(0.0, f64::max);
+
+        if fastest == 0.0 {
+            return;
+        }
+
+        let range = fastest * MARGIN;
+        let points: Vec<Point> = self.points.iter()
+            .map(|&(theta, ang_velocity)| Point::new(
+                center.x + (theta / PI * viewport.width() as f64 / 2.0) as i32,
+                center.y - (ang_velocity / range * viewport.height() as f64 / 2.0) as i32,
+            ))
+            .collect();
+
+        canvas.set_draw_color(color);
+        canvas.draw_points(&points[..]).unwrap();
+    }
+}
+
+impl Default for PhasePortrait {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_LENGTH)
+    }
+}
+
+/// Phase space sampled once every `period` seconds, at times
+/// `offset + n period`.
+///
+/// With the period of the drive, a periodic motion shows up as a few
+/// points and a chaotic one as a fractal cloud.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoincareSection {
+    portrait: PhasePortrait,
+    period: f64,
+    offset: f64,
+    // tempo, angulo e velocidade no passo anterior
+    previous: Option<(f64, f64, f64)>,
+}
+
+impl PoincareSection {
+    pub const DEFAULT_LENGTH: usize = 20000;
+
+    pub fn new(period: f64, offset: f64) -> Self {
+        Self {
+            portrait: PhasePortrait::new(Self::DEFAULT_LENGTH),
+            period,
+            offset,
+            previous: None,
+        }
+    }
+
+    /// Samples whenever `drive` is at its peak.
+    pub fn for_drive(drive: &Drive) -> Self {
+        Self::new(drive.period(), -drive.phase / drive.frequency)
+    }
+
+    pub fn period(&self) -> f64 {
+        self.period
+    }
+
+    /// Call after every step; adds a point, interpolated between the last
+    /// two steps, whenever a sampling time was passed.
+    pub fn record(&mut self, pendulum: &Pendulum) {
+        let now = (pendulum.time(), pendulum.theta, pendulum.ang_velocity);
+
+        if let Some(before) = self.previous {
+            let sample = ((now.0 - self.offset) / self.period).floor();
+
+            if sample > ((before.0 - self.offset) / self.period).floor() {
+                let time = self.offset + sample * self.period;
+                let fraction = (time - before.0) / (now.0 - before.0);
+
+                self.portrait.push(before.1 + (now.1 - before.1) * fraction,
+                                   before.2 + (now.2 - before.2) * fraction);
+            }
+        }
+
+        self.previous = Some(now);
+    }
+
+    pub fn portrait(&self) -> &PhasePortrait {
+        &self.portrait
+    }
+
+    /// Drops the points and starts over, e.g. after the pendulum was reset.
+    pub fn clear(&mut self) {
+        self.portrait.clear();
+        self.previous = None;
+    }
+
+    pub fn draw(&self, canvas: &mut Canvas<sdl2::video::Window>, viewport: Rect, color: Color) {
+        self.portrait.draw(canvas, viewport, color);
+    }
+}
@@ -0,0 +1,22 @@
+use forces::Vector;
+
+/// Where the pivot sits on the window and how many pixels make a meter.
+///
+/// The pendulums work in meters with `y` pointing down, like the window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Screen {
+    pub pivot: Vector,
+    pub pixels_per_meter: f64,
+}
+
+impl Screen {
+    pub fn new(pivot: Vector, pixels_per_meter: f64) -> Self {
+        Self { pivot, pixels_per_meter }
+    }
+
+    /// Pixel of a point given in meters from the pivot.
+    pub fn to_pixels(&self, point: &Vector) -> (i32, i32) {
+        ((self.pivot.x + point.x * self.pixels_per_meter).round() as i32,
+         (self.pivot.y + point.y * self.pixels_per_meter).round() as i32)
+    }
+}
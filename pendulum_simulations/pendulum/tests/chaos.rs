@@ -0,0 +1,129 @@
+extern crate pendulum;
+
+use pendulum::chaos::{self, Ensemble, FlipMap, Lyapunov};
+use pendulum::image::{self, ImageFormat};
+use pendulum::DoublePendulum;
+use std::f64::consts::PI;
+use std::path::Path;
+
+fn pendulum(thetas: (f64, f64)) -> DoublePendulum {
+    DoublePendulum::new(thetas, (1.0, 1.0), (1.0, 1.0))
+}
+
+#[test]
+fn ensemble_starts_perturbed_and_drifts_apart() {
+    let mut ensemble = Ensemble::perturbed(&pendulum((PI / 2.0, PI / 2.0)), 3, 1e-6);
+
+    let start = ensemble.divergence();
+    assert_eq!(start.len(), 3);
+    assert_eq!(start[0], 0.0);
+    assert!((start[1] - 1e-6).abs() < 1e-12);
+    assert!((start[2] - 2e-6).abs() < 1e-12);
+
+    for _ in 0..20_000 {
+        ensemble.step(1e-3);
+    }
+
+    assert!(ensemble.max_divergence() > 1.0);
+}
+
+#[test]
+fn lyapunov_exponent_is_positive_only_when_chaotic() {
+    let mut chaotic = Lyapunov::new(&pendulum((PI / 2.0, PI / 2.0)), Lyapunov::DEFAULT_SEPARATION);
+    let mut regular = Lyapunov::new(&pendulum((0.05, 0.05)), Lyapunov::DEFAULT_SEPARATION);
+
+    for _ in 0..30_000 {
+        chaotic.step(1e-3);
+        regular.step(1e-3);
+    }
+
+    assert!((chaotic.time() - 30.0).abs() < 1e-9);
+    assert!(chaotic.exponent() > 0.5, "chaotic exponent {}", chaotic.exponent());
+    assert!(regular.exponent() < 0.2, "regular exponent {}", regular.exponent());
+    assert!(regular.exponent() < chaotic.exponent() / 4.0);
+}
+
+#[test]
+fn low_energy_never_flips() {
+    let low = pendulum((0.5, 0.5));
+
+    assert!(!chaos::can_flip(&low));
+    assert_eq!(chaos::flip_time(&low, 1e-2, 10.0), None);
+}
+
+#[test]
+fn near_upright_flips_quickly() {
+    let upright = pendulum((PI - 0.1, PI - 0.1));
+    let time = chaos::flip_time(&upright, 1e-3, 10.0).expect("should flip");
+
+    assert!(time > 0.0 && time < 2.0, "flipped after {}", time);
+}
+
+#[test]
+#[should_panic(expected = "dt must be positive")]
+fn flip_time_refuses_a_zero_timestep() {
+    chaos::flip_time(&pendulum((PI - 0.1, PI - 0.1)), 0.0, 10.0);
+}
+
+#[test]
+fn flip_map_covers_the_grid() {
+    let map = FlipMap::compute(&pendulum((0.0, 0.0)), (5, 4), 1e-2, 2.0);
+
+    assert_eq!(map.times.len(), 20);
+    assert_eq!(map.to_rgb().len(), 60);
+
+    // o centro da grade fica perto de pendurado e nunca vira
+    let (theta1, theta2) = FlipMap::angles((5, 4), 2, 1);
+    assert!(theta1.abs() < 1e-12 && (theta2 - PI / 4.0).abs() < 1e-12);
+    assert_eq!(map.get(2, 1), None);
+
+    let (first, second) = FlipMap::angles((5, 4), 0, 0);
+    assert!(first < -PI / 2.0 && second > PI / 2.0);
+
+    assert!(map.times.iter().flatten().all(|&time| time > 0.0 && time <= 2.0 + 1e-9));
+}
+
+#[test]
+fn image_format_follows_the_extension() {
+    assert_eq!(ImageFormat::from_path(Path::new("map.ppm")), Ok(ImageFormat::Ppm));
+    assert_eq!(ImageFormat::from_path(Path::new("map.PNG")), Ok(ImageFormat::Png));
+    assert!(ImageFormat::from_path(Path::new("map.jpg")).is_err());
+    assert!(ImageFormat::from_path(Path::new("map")).is_err());
+}
+
+#[test]
+fn ppm_has_a_header_and_the_raw_pixels() {
+    let rgb = [255, 0, 0, 0, 255, 0];
+    let mut out = Vec::new();
+
+    image::write_ppm(&mut out, 2, 1, &rgb).unwrap();
+
+    assert_eq!(out, [b"P6\n2 1\n255\n".as_slice(), &rgb].concat());
+}
+
+#[test]
+fn png_has_the_signature_and_valid_chunks() {
+    let rgb = vec![128; 3 * 3 * 2];
+    let mut out = Vec::new();
+
+    image::write_png(&mut out, 3, 2, &rgb).unwrap();
+
+    assert_eq!(&out[..8], b"\x89PNG\r\n\x1a\n");
+
+    // IHDR com largura e altura, e o arquivo terminando em IEND
+    assert_eq!(&out[8..16], b"\0\0\0\x0dIHDR");
+    assert_eq!(&out[16..24], &[0, 0, 0, 3, 0, 0, 0, 2]);
+    assert_eq!(&out[out.len() - 12..], b"\0\0\0\0IEND\xae\x42\x60\x82");
+}
+
+#[test]
+fn png_refuses_sizes_it_cannot_store() {
+    let mut out = Vec::new();
+
+    for (width, height) in [(0, 2), (2, 0), (u32::MAX as usize + 1, 1)] {
+        let error = image::write_png(&mut out, width, height, &[]).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    assert!(out.is_empty());
+}
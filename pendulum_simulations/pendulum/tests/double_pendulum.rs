@@ -0,0 +1,106 @@
+extern crate pendulum;
+
+use pendulum::integrator::{Euler, Rk45};
+use pendulum::{DoublePendulum, Pendulum};
+use std::f64::consts::PI;
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    ((a - b) / b).abs() < tolerance
+}
+
+// deriva relativa da energia depois de `duration` segundos
+fn drift(pendulum: &mut DoublePendulum, duration: f64, dt: f64) -> f64 {
+    let initial = pendulum.energy();
+
+    for _ in 0..(duration / dt).round() as usize {
+        pendulum.step(dt);
+    }
+
+    ((pendulum.energy() - initial) / initial).abs()
+}
+
+#[test]
+fn energy_is_zero_hanging_still_and_grows_upward() {
+    let hanging = DoublePendulum::new((0.0, 0.0), (1.0, 2.0), (3.0, 4.0));
+    assert_eq!(hanging.energy(), 0.0);
+
+    let sideways = DoublePendulum::new((PI / 2.0, PI / 2.0), (1.0, 2.0), (3.0, 4.0));
+    let expected = Pendulum::G * ((3.0 + 4.0) * 1.0 + 4.0 * 2.0);
+    assert!(close(sideways.energy(), expected, 1e-12));
+
+    let upright = DoublePendulum::new((PI, PI), (1.0, 2.0), (3.0, 4.0));
+    assert!(upright.energy() > sideways.energy());
+}
+
+#[test]
+fn energy_matches_the_bobs_in_space() {
+    let mut pendulum = DoublePendulum::new((0.7, -1.9), (1.2, 0.8), (2.0, 0.5));
+    pendulum.ang_velocities = (1.3, -2.1);
+
+    let (m1, m2) = pendulum.masses;
+    let (l1, l2) = pendulum.lengths;
+    let (theta1, theta2) = pendulum.thetas;
+    let (omega1, omega2) = pendulum.ang_velocities;
+
+    // velocidades das massas derivando as posicoes de `bobs`
+    let v1 = (l1 * omega1 * theta1.cos(), -l1 * omega1 * theta1.sin());
+    let v2 = (v1.0 + l2 * omega2 * theta2.cos(), v1.1 - l2 * omega2 * theta2.sin());
+    let kinetic = 0.5 * m1 * (v1.0 * v1.0 + v1.1 * v1.1) + 0.5 * m2 * (v2.0 * v2.0 + v2.1 * v2.1);
+
+    // y cresce para baixo, entao a altura acima do ponto mais baixo e
+    // o comprimento menos y
+    let (first, second) = pendulum.bobs();
+    let potential = Pendulum::G * (m1 * (l1 - first.y) + m2 * (l1 + l2 - second.y));
+
+    assert!(close(pendulum.kinetic_energy(), kinetic, 1e-12));
+    assert!(close(pendulum.potential_energy(), potential, 1e-12));
+}
+
+#[test]
+fn rk4_keeps_the_energy_of_a_chaotic_swing() {
+    let mut pendulum = DoublePendulum::new((PI / 2.0, PI / 2.0), (1.0, 1.0), (1.0, 1.0));
+
+    assert!(drift(&mut pendulum, 60.0, 1e-3) < 1e-6);
+}
+
+#[test]
+fn adaptive_steps_keep_the_energy_at_frame_rate() {
+    let mut pendulum = DoublePendulum::new((PI / 2.0, PI / 2.0), (1.0, 1.0), (1.0, 1.0));
+    pendulum.set_integrator(Rk45::new(1e-10));
+
+    assert!(drift(&mut pendulum, 60.0, 1.0 / 60.0) < 1e-6);
+}
+
+#[test]
+fn adaptive_steps_finish_on_an_invalid_state() {
+    let mut pendulum = DoublePendulum::new((f64::NAN, PI / 2.0), (1.0, 1.0), (1.0, 1.0));
+    pendulum.set_integrator(Rk45::default());
+
+    pendulum.step(1.0 / 60.0);
+
+    assert!(pendulum.thetas.0.is_nan());
+    assert!(close(pendulum.time(), 1.0 / 60.0, 1e-12));
+}
+
+#[test]
+fn euler_drifts_far_more() {
+    let mut pendulum = DoublePendulum::new((PI / 2.0, PI / 2.0), (1.0, 1.0), (1.0, 1.0));
+    pendulum.set_integrator(Euler);
+
+    assert!(drift(&mut pendulum, 60.0, 1e-3) > 1e-4);
+}
+
+#[test]
+fn small_swings_stay_small_and_record_the_bobs() {
+    let mut pendulum = DoublePendulum::new((0.01, 0.01), (1.0, 1.0), (1.0, 1.0));
+
+    for _ in 0..1000 {
+        pendulum.step(1e-2);
+
+        assert!(pendulum.thetas.0.abs() < 0.05 && pendulum.thetas.1.abs() < 0.05);
+    }
+
+    assert!(close(pendulum.time(), 10.0, 1e-9));
+    assert_eq!(pendulum.trajetories[1].len(), 1000);
+    assert_eq!(pendulum.trajetories[1][999], pendulum.bobs().1);
+}
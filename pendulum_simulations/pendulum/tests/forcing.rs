@@ -0,0 +1,137 @@
+extern crate pendulum;
+
+use pendulum::forcing::{Damping, Drive};
+use pendulum::integrator::VelocityVerlet;
+use pendulum::Pendulum;
+use std::f64::consts::PI;
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    ((a - b) / b).abs() < tolerance
+}
+
+// maior |θ| durante `duration` segundos
+fn widest_swing(pendulum: &mut Pendulum, duration: f64, dt: f64) -> f64 {
+    let mut widest: f64 = 0.0;
+
+    for _ in 0..(duration / dt).round() as usize {
+        pendulum.step(dt);
+        widest = widest.max(pendulum.theta.abs());
+    }
+
+    widest
+}
+
+#[test]
+fn energy_is_zero_hanging_still_and_m_g_l_sideways() {
+    assert_eq!(Pendulum::new(0.0, 0.0, 2.0, 3.0).energy(), 0.0);
+    assert!(close(Pendulum::new(PI / 2.0, 0.0, 2.0, 3.0).energy(), 3.0 * Pendulum::G * 2.0, 1e-12));
+    assert!(close(Pendulum::new(0.0, 1.5, 2.0, 3.0).energy(), 0.5 * 3.0 * (1.5 * 2.0_f64).powi(2), 1e-12));
+}
+
+#[test]
+fn free_swing_keeps_its_energy() {
+    for symplectic in [false, true] {
+        let mut pendulum = Pendulum::new(2.0, 0.0, 1.0, 1.0);
+
+        if symplectic {
+            pendulum.set_integrator(VelocityVerlet);
+        }
+
+        let initial = pendulum.energy();
+
+        for _ in 0..100_000 {
+            pendulum.step(1e-3);
+        }
+
+        assert!(close(pendulum.energy(), initial, 1e-6));
+    }
+}
+
+#[test]
+fn linear_damping_decays_exponentially() {
+    let mut pendulum = Pendulum::new(0.01, 0.0, 1.0, 2.0);
+    let gamma = 0.2;
+    pendulum.set_damping(Damping::new(gamma * pendulum.moment_of_inertia(), 0.0));
+
+    let mut energy = pendulum.energy();
+    let mut extremes = vec![];
+
+    for _ in 0..20_000 {
+        let before = pendulum.ang_velocity;
+        pendulum.step(1e-3);
+
+        if before.signum() != pendulum.ang_velocity.signum() {
+            extremes.push(pendulum.theta);
+        }
+
+        assert!(pendulum.energy() <= energy);
+        energy = pendulum.energy();
+    }
+
+    // a cada periodo amortecido a amplitude cai por exp(-γ T / 2)
+    let damped_period = 2.0 * PI / (Pendulum::G - gamma * gamma / 4.0).sqrt();
+    let decrement = (-gamma * damped_period / 2.0).exp();
+
+    assert!(extremes.len() > 15);
+
+    for pair in extremes.windows(3) {
+        assert!(close(pair[2] / pair[0], decrement, 1e-3), "{} {}", pair[2] / pair[0], decrement);
+    }
+}
+
+#[test]
+fn quadratic_damping_slows_wide_swings_more() {
+    let damping = Damping::new(0.0, 0.5);
+
+    let mut fraction_left = vec![];
+
+    for amplitude in [0.2, 2.0] {
+        let mut pendulum = Pendulum::new(amplitude, 0.0, 1.0, 1.0);
+        pendulum.set_damping(damping);
+
+        let initial = pendulum.energy();
+        let mut energy = initial;
+
+        for _ in 0..5_000 {
+            pendulum.step(1e-3);
+
+            assert!(pendulum.energy() <= energy);
+            energy = pendulum.energy();
+        }
+
+        fraction_left.push(energy / initial);
+    }
+
+    assert!(fraction_left[1] < fraction_left[0]);
+    assert_eq!(damping.torque(-2.0), 2.0);
+}
+
+#[test]
+fn drive_settles_into_the_forced_amplitude() {
+    let mut pendulum = Pendulum::new(0.0, 0.0, 1.0, 1.0);
+    let natural_squared = Pendulum::G;
+    let (gamma, forcing) = (1.0, 0.01);
+    let frequency = 0.5 * natural_squared.sqrt();
+
+    let inertia = pendulum.moment_of_inertia();
+    pendulum.set_damping(Damping::new(gamma * inertia, 0.0));
+    pendulum.set_drive(Some(Drive::new(forcing * inertia, frequency, 0.0)));
+
+    // transiente some com exp(-γt/2)
+    widest_swing(&mut pendulum, 40.0, 1e-3);
+
+    let expected = forcing / ((natural_squared - frequency * frequency).powi(2)
+        + (gamma * frequency).powi(2)).sqrt();
+    let period = pendulum.drive().unwrap().period();
+
+    assert!(close(widest_swing(&mut pendulum, period, 1e-3), expected, 1e-3));
+}
+
+#[test]
+fn drive_torque_follows_the_cosine() {
+    let drive = Drive::new(2.0, PI, PI / 2.0);
+
+    assert!(close(drive.period(), 2.0, 1e-12));
+    assert!(drive.torque_at(0.0).abs() < 1e-12);
+    assert!(close(drive.torque_at(0.5), -2.0, 1e-12));
+}
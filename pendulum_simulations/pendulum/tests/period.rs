@@ -0,0 +1,117 @@
+extern crate pendulum;
+
+use pendulum::integrator::{Euler, Integrator, Rk4, VelocityVerlet};
+use pendulum::Pendulum;
+use std::f64::consts::PI;
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    ((a - b) / b).abs() < tolerance
+}
+
+// tempo ate a velocidade voltar a ficar negativa, interpolando o zero
+fn measured_period<I: Integrator + 'static>(integrator: I, amplitude: f64, dt: f64) -> f64 {
+    let mut pendulum = Pendulum::new(amplitude, 0.0, 1.0, 1.0);
+    pendulum.set_integrator(integrator);
+
+    let mut crossings = 0;
+
+    loop {
+        let before = pendulum.ang_velocity;
+        pendulum.step(dt);
+        let after = pendulum.ang_velocity;
+
+        if before != 0.0 && before.signum() != after.signum() {
+            crossings += 1;
+
+            if crossings == 2 {
+                return pendulum.time() - dt * after / (after - before);
+            }
+        }
+    }
+}
+
+// K(k) pela serie π/2 Σ ((2n)! / (2^2n n!²))² k^2n
+fn elliptic_k(k: f64) -> f64 {
+    let (mut coefficient, mut power, mut sum) = (1.0, 1.0, 1.0);
+
+    for n in 1.. {
+        let ratio = (2 * n - 1) as f64 / (2 * n) as f64;
+        coefficient *= ratio * ratio;
+        power *= k * k;
+
+        let term = coefficient * power;
+        sum += term;
+
+        if term < 1e-17 {
+            break;
+        }
+    }
+
+    PI / 2.0 * sum
+}
+
+fn elliptic_period(amplitude: f64) -> f64 {
+    4.0 * (1.0 / Pendulum::G).sqrt() * elliptic_k((amplitude / 2.0).sin())
+}
+
+#[test]
+fn small_swings_take_two_pi_root_l_over_g() {
+    let expected = Pendulum::new(0.01, 0.0, 1.0, 1.0).small_angle_period();
+
+    assert!(close(expected, 2.0 * PI * (1.0 / 9.81_f64).sqrt(), 1e-12));
+    assert!(close(measured_period(Rk4, 0.01, 1e-3), expected, 1e-5));
+    assert!(close(measured_period(VelocityVerlet, 0.01, 1e-3), expected, 1e-5));
+}
+
+#[test]
+fn wide_swings_follow_the_elliptic_integral() {
+    let amplitude = 2.5;
+    let expected = elliptic_period(amplitude);
+
+    // quase 50% mais lento que a aproximacao linear
+    assert!(expected > 1.4 * Pendulum::new(amplitude, 0.0, 1.0, 1.0).small_angle_period());
+
+    assert!(close(measured_period(Rk4, amplitude, 1e-3), expected, 1e-5));
+    assert!(close(measured_period(VelocityVerlet, amplitude, 1e-3), expected, 1e-5));
+}
+
+#[test]
+fn period_is_the_elliptic_integral() {
+    for amplitude in [0.1, 1.0, 2.0, 3.0] {
+        let pendulum = Pendulum::new(amplitude, 0.0, 1.0, 1.0);
+
+        assert!(close(pendulum.period().unwrap(), elliptic_period(amplitude), 1e-9));
+    }
+
+    // a mesma oscilacao vista passando pelo ponto mais baixo
+    let mut moving = Pendulum::new(0.0, 0.0, 1.0, 1.0);
+    moving.ang_velocity = (2.0 * Pendulum::G * (1.0 - 2.0_f64.cos())).sqrt();
+
+    assert!(close(moving.amplitude().unwrap(), 2.0, 1e-12));
+    assert!(close(moving.period().unwrap(), elliptic_period(2.0), 1e-9));
+}
+
+#[test]
+fn going_over_the_top_has_no_period() {
+    let pendulum = Pendulum::new(0.0, 10.0, 1.0, 1.0);
+
+    assert_eq!(pendulum.amplitude(), None);
+    assert_eq!(pendulum.period(), None);
+}
+
+#[test]
+fn euler_with_a_unit_step_is_the_old_update() {
+    let mut pendulum = Pendulum::new(PI / 4.0, 0.0, 300.0, 1.0);
+    pendulum.set_gravity(0.1);
+    pendulum.set_integrator(Euler);
+
+    let (mut theta, mut ang_velocity) = (PI / 4.0, 0.0_f64);
+
+    for _ in 0..100 {
+        ang_velocity += -(0.1 * theta.sin()) / 300.0;
+        theta += ang_velocity;
+        pendulum.step(1.0);
+    }
+
+    assert_eq!((pendulum.theta, pendulum.ang_velocity), (theta, ang_velocity));
+}
@@ -0,0 +1,89 @@
+extern crate pendulum;
+
+use pendulum::forcing::{Damping, Drive};
+use pendulum::phase::{wrap_angle, PhasePortrait, PoincareSection};
+use pendulum::Pendulum;
+use std::f64::consts::PI;
+
+#[test]
+fn angles_wrap_into_minus_pi_to_pi() {
+    assert!((wrap_angle(3.0 * PI / 2.0) + PI / 2.0).abs() < 1e-12);
+    assert!((wrap_angle(-5.0 * PI / 2.0) + PI / 2.0).abs() < 1e-12);
+    assert_eq!(wrap_angle(0.5), 0.5);
+    assert_eq!(wrap_angle(PI), -PI);
+}
+
+#[test]
+fn portrait_keeps_the_latest_points() {
+    let mut portrait = PhasePortrait::new(3);
+
+    for i in 0..5 {
+        portrait.push(0.1 * i as f64, i as f64);
+    }
+
+    let velocities: Vec<f64> = portrait.points().iter().map(|point| point.1).collect();
+    assert_eq!(velocities, vec![2.0, 3.0, 4.0]);
+
+    portrait.clear();
+    assert!(portrait.is_empty());
+}
+
+#[test]
+#[should_panic(expected = "at least one point")]
+fn portrait_refuses_a_zero_length() {
+    PhasePortrait::new(0);
+}
+
+#[test]
+fn section_samples_once_per_period() {
+    let mut pendulum = Pendulum::new(0.5, 0.0, 1.0, 1.0);
+    let mut section = PoincareSection::new(0.25, 0.0);
+
+    for _ in 0..900 {
+        pendulum.step(1e-3);
+        section.record(&pendulum);
+    }
+
+    assert_eq!(section.portrait().len(), 3);
+}
+
+#[test]
+fn section_of_a_free_swing_lands_on_the_start_each_period() {
+    let mut pendulum = Pendulum::new(1.0, 0.0, 1.0, 1.0);
+    let mut section = PoincareSection::new(pendulum.period().unwrap(), 0.0);
+
+    for _ in 0..20_000 {
+        pendulum.step(1e-3);
+        section.record(&pendulum);
+    }
+
+    assert!(section.portrait().len() >= 9);
+
+    for &(theta, ang_velocity) in section.portrait().points() {
+        assert!((theta - 1.0).abs() < 1e-4, "{}", theta);
+        assert!(ang_velocity.abs() < 1e-3, "{}", ang_velocity);
+    }
+}
+
+#[test]
+fn driven_damped_swing_settles_on_one_point() {
+    let mut pendulum = Pendulum::new(0.0, 0.0, 1.0, 1.0);
+    let inertia = pendulum.moment_of_inertia();
+    let drive = Drive::new(0.5 * inertia, 2.0, 0.3);
+
+    pendulum.set_damping(Damping::new(inertia, 0.0));
+    pendulum.set_drive(Some(drive));
+
+    let mut section = PoincareSection::for_drive(&drive);
+
+    for _ in 0..60_000 {
+        pendulum.step(1e-3);
+        section.record(&pendulum);
+    }
+
+    let points = section.portrait().points();
+    let (last, before) = (points[points.len() - 1], points[points.len() - 2]);
+
+    assert!((last.0 - before.0).abs() < 1e-6);
+    assert!((last.1 - before.1).abs() < 1e-6);
+}
@@ -0,0 +1,75 @@
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::vec2::Vec2;
+use crate::vec3::Vec3;
+
+/// `f64` vectors of either dimension, for code that works the same in the
+/// plane and in space.
+///
+/// Generic code only gets the operators taking `Self` on the left; clone
+/// when the left side is borrowed.
+pub trait Coordinates:
+    Clone + Debug + PartialEq + Sum +
+    Add<Output = Self> + for<'a> Add<&'a Self, Output = Self> +
+    Sub<Output = Self> + for<'a> Sub<&'a Self, Output = Self> +
+    Mul<f64, Output = Self> + Neg<Output = Self> +
+    AddAssign + for<'a> AddAssign<&'a Self> + SubAssign + for<'a> SubAssign<&'a Self> + MulAssign<f64>
+{
+    fn zero() -> Self;
+    fn dot_product(&self, other: &Self) -> f64;
+
+    fn magnitude(&self) -> f64 {
+        self.dot_product(self).sqrt()
+    }
+}
+
+// um unico angulo, como nos pendulos
+impl Coordinates for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+
+    fn dot_product(&self, other: &Self) -> f64 {
+        self * other
+    }
+
+    fn magnitude(&self) -> f64 {
+        self.abs()
+    }
+}
+
+impl Coordinates for Vec2<f64> {
+    fn zero() -> Self {
+        Vec2::zero()
+    }
+
+    fn dot_product(&self, other: &Self) -> f64 {
+        Vec2::dot_product(self, other)
+    }
+}
+
+impl Coordinates for Vec3<f64> {
+    fn zero() -> Self {
+        Vec3::zero()
+    }
+
+    fn dot_product(&self, other: &Self) -> f64 {
+        Vec3::dot_product(self, other)
+    }
+}
+
+/// `base + Σ weights[k] * terms[k]`, element by element; the stages of the
+/// Runge-Kutta integrators are built with it.
+pub fn combine<V: Coordinates>(base: &[V], terms: &[&[V]], weights: &[f64]) -> Vec<V> {
+    base.iter()
+        .enumerate()
+        .map(|(i, value)| {
+            terms.iter()
+                .zip(weights)
+                .filter(|(_, weight)| **weight != 0.0)
+                .fold(value.clone(), |sum, (term, weight)| sum + term[i].clone() * *weight)
+        })
+        .collect()
+}
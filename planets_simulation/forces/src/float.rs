@@ -0,0 +1,88 @@
+use std::fmt::Debug;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// Floating point numbers the vectors can be made of, `f32` or `f64`.
+pub trait Float:
+    Copy + Debug + Default + PartialEq + PartialOrd + Sum +
+    Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> +
+    Neg<Output = Self> + AddAssign + SubAssign + MulAssign + DivAssign
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const EPSILON: Self;
+
+    fn from_f64(value: f64) -> Self;
+    fn to_f64(self) -> f64;
+    fn sqrt(self) -> Self;
+    fn abs(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn acos(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+    fn min(self, other: Self) -> Self;
+    fn max(self, other: Self) -> Self;
+    fn is_finite(self) -> bool;
+    fn is_nan(self) -> bool;
+}
+
+macro_rules! float {
+    ($type:ident) => {
+        impl Float for $type {
+            const ZERO: Self = 0.0;
+            const ONE: Self = 1.0;
+            const EPSILON: Self = $type::EPSILON;
+
+            fn from_f64(value: f64) -> Self {
+                value as $type
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn sqrt(self) -> Self {
+                $type::sqrt(self)
+            }
+
+            fn abs(self) -> Self {
+                $type::abs(self)
+            }
+
+            fn sin(self) -> Self {
+                $type::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                $type::cos(self)
+            }
+
+            fn acos(self) -> Self {
+                $type::acos(self)
+            }
+
+            fn atan2(self, other: Self) -> Self {
+                $type::atan2(self, other)
+            }
+
+            fn min(self, other: Self) -> Self {
+                $type::min(self, other)
+            }
+
+            fn max(self, other: Self) -> Self {
+                $type::max(self, other)
+            }
+
+            fn is_finite(self) -> bool {
+                $type::is_finite(self)
+            }
+
+            fn is_nan(self) -> bool {
+                $type::is_nan(self)
+            }
+        }
+    };
+}
+
+float!(f32);
+float!(f64);
@@ -0,0 +1,131 @@
+// operadores componente a componente para `Vec2` e `Vec3`, com valores e
+// referencias dos dois lados
+macro_rules! vector_ops {
+    ($vector:ident { $($field:ident),+ }) => {
+        vector_ops!(@binary $vector, Add, add, AddAssign, add_assign, +, $($field),+);
+        vector_ops!(@binary $vector, Sub, sub, SubAssign, sub_assign, -, $($field),+);
+        vector_ops!(@scalar $vector, Mul, mul, MulAssign, mul_assign, *, $($field),+);
+        vector_ops!(@scalar $vector, Div, div, DivAssign, div_assign, /, $($field),+);
+        vector_ops!(@left $vector, f32, $($field),+);
+        vector_ops!(@left $vector, f64, $($field),+);
+
+        impl<T: Float> Neg for $vector<T> {
+            type Output = $vector<T>;
+
+            fn neg(self) -> $vector<T> {
+                $vector { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Float> Neg for &$vector<T> {
+            type Output = $vector<T>;
+
+            fn neg(self) -> $vector<T> {
+                $vector { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Float> Sum for $vector<T> {
+            fn sum<I: Iterator<Item = $vector<T>>>(iter: I) -> $vector<T> {
+                iter.fold($vector::zero(), |total, vector| total + vector)
+            }
+        }
+
+        impl<'a, T: Float> Sum<&'a $vector<T>> for $vector<T> {
+            fn sum<I: Iterator<Item = &'a $vector<T>>>(iter: I) -> $vector<T> {
+                iter.fold($vector::zero(), |total, vector| total + vector)
+            }
+        }
+    };
+
+    (@binary $vector:ident, $trait:ident, $method:ident, $assign:ident, $assign_method:ident,
+     $op:tt, $($field:ident),+) => {
+        impl<T: Float> $trait<$vector<T>> for $vector<T> {
+            type Output = $vector<T>;
+
+            fn $method(self, other: $vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field $op other.$field),+ }
+            }
+        }
+
+        impl<T: Float> $trait<&$vector<T>> for $vector<T> {
+            type Output = $vector<T>;
+
+            fn $method(self, other: &$vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field $op other.$field),+ }
+            }
+        }
+
+        impl<T: Float> $trait<$vector<T>> for &$vector<T> {
+            type Output = $vector<T>;
+
+            fn $method(self, other: $vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field $op other.$field),+ }
+            }
+        }
+
+        impl<T: Float> $trait<&$vector<T>> for &$vector<T> {
+            type Output = $vector<T>;
+
+            fn $method(self, other: &$vector<T>) -> $vector<T> {
+                $vector { $($field: self.$field $op other.$field),+ }
+            }
+        }
+
+        impl<T: Float> $assign<$vector<T>> for $vector<T> {
+            fn $assign_method(&mut self, other: $vector<T>) {
+                $(self.$field = self.$field $op other.$field;)+
+            }
+        }
+
+        impl<T: Float> $assign<&$vector<T>> for $vector<T> {
+            fn $assign_method(&mut self, other: &$vector<T>) {
+                $(self.$field = self.$field $op other.$field;)+
+            }
+        }
+    };
+
+    (@scalar $vector:ident, $trait:ident, $method:ident, $assign:ident, $assign_method:ident,
+     $op:tt, $($field:ident),+) => {
+        impl<T: Float> $trait<T> for $vector<T> {
+            type Output = $vector<T>;
+
+            fn $method(self, scalar: T) -> $vector<T> {
+                $vector { $($field: self.$field $op scalar),+ }
+            }
+        }
+
+        impl<T: Float> $trait<T> for &$vector<T> {
+            type Output = $vector<T>;
+
+            fn $method(self, scalar: T) -> $vector<T> {
+                $vector { $($field: self.$field $op scalar),+ }
+            }
+        }
+
+        impl<T: Float> $assign<T> for $vector<T> {
+            fn $assign_method(&mut self, scalar: T) {
+                $(self.$field = self.$field $op scalar;)+
+            }
+        }
+    };
+
+    // escalar a esquerda: `2.0 * v` (so da para tipos concretos)
+    (@left $vector:ident, $float:ident, $($field:ident),+) => {
+        impl Mul<$vector<$float>> for $float {
+            type Output = $vector<$float>;
+
+            fn mul(self, vector: $vector<$float>) -> $vector<$float> {
+                $vector { $($field: self * vector.$field),+ }
+            }
+        }
+
+        impl Mul<&$vector<$float>> for $float {
+            type Output = $vector<$float>;
+
+            fn mul(self, vector: &$vector<$float>) -> $vector<$float> {
+                $vector { $($field: self * vector.$field),+ }
+            }
+        }
+    };
+}
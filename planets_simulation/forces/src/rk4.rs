@@ -0,0 +1,50 @@
+use crate::coordinates::{combine, Coordinates};
+use crate::rk45::Acceleration;
+
+/// Classic fourth order Runge-Kutta. Very accurate per step but not
+/// symplectic, so the energy slowly drifts over long runs.
+///
+/// Shared by the planets and the pendulums like `Rk45`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Rk4;
+
+impl Rk4 {
+    /// Advances `positions` and `velocities` from `time` by `dt`.
+    pub fn advance<V: Coordinates>(&self,
+                                   time: f64,
+                                   positions: &mut [V],
+                                   velocities: &mut [V],
+                                   dt: f64,
+                                   acceleration: &Acceleration<V>)
+    {
+        let x = positions.to_vec();
+        let v = velocities.to_vec();
+
+        let k1 = derivative(time, &x, &v, acceleration);
+        let k2 = derivative(time + 0.5 * dt,
+                            &combine(&x, &[&k1.0], &[0.5 * dt]),
+                            &combine(&v, &[&k1.1], &[0.5 * dt]),
+                            acceleration);
+        let k3 = derivative(time + 0.5 * dt,
+                            &combine(&x, &[&k2.0], &[0.5 * dt]),
+                            &combine(&v, &[&k2.1], &[0.5 * dt]),
+                            acceleration);
+        let k4 = derivative(time + dt,
+                            &combine(&x, &[&k3.0], &[dt]),
+                            &combine(&v, &[&k3.1], &[dt]),
+                            acceleration);
+
+        let weights = [dt / 6.0, dt / 3.0, dt / 3.0, dt / 6.0];
+        positions.clone_from_slice(&combine(&x, &[&k1.0, &k2.0, &k3.0, &k4.0], &weights));
+        velocities.clone_from_slice(&combine(&v, &[&k1.1, &k2.1, &k3.1, &k4.1], &weights));
+    }
+}
+
+// derivada do estado (posicoes, velocidades)
+fn derivative<V: Coordinates>(time: f64,
+                              x: &[V],
+                              v: &[V],
+                              acceleration: &Acceleration<V>) -> (Vec<V>, Vec<V>)
+{
+    (v.to_vec(), acceleration(time, x, v))
+}
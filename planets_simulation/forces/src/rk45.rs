@@ -0,0 +1,182 @@
+use crate::coordinates::{combine, Coordinates};
+
+/// Maps the time, the positions and the velocities to the accelerations.
+pub type Acceleration<'a, V> = dyn Fn(f64, &[V], &[V]) -> Vec<V> + 'a;
+
+// tabela de Butcher de Dormand-Prince 5(4)
+const DP_C: [f64; 6] = [1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+
+const DP_A: [&[f64]; 6] = [
+    &[1.0 / 5.0],
+    &[3.0 / 40.0, 9.0 / 40.0],
+    &[44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0],
+    &[19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0],
+    &[9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0],
+    &[35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0],
+];
+
+const DP_B5: [f64; 7] = [
+    35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0, 0.0,
+];
+
+const DP_B4: [f64; 7] = [
+    5179.0 / 57600.0, 0.0, 7571.0 / 16695.0, 393.0 / 640.0,
+    -92097.0 / 339200.0, 187.0 / 2100.0, 1.0 / 40.0,
+];
+
+/// Adaptive Dormand-Prince 5(4). Each step covers exactly `dt`, split
+/// into as many substeps as needed to keep the estimated local error
+/// below `tolerance` (relative to the size of each component).
+///
+/// Works on any `Coordinates`, plain `f64` angles included, so the
+/// planets and the pendulums share it, like `Rk4`.
+#[derive(Debug, Clone, Copy)]
+pub struct Rk45 {
+    tolerance: f64,
+    substep: Option<f64>,
+}
+
+impl Rk45 {
+    const MIN_FACTOR: f64 = 0.2;
+    const MAX_FACTOR: f64 = 5.0;
+    // menor subpasso, como fracao de dt
+    const MIN_STEP: f64 = 1.0e-12;
+
+    pub fn new(tolerance: f64) -> Self {
+        Self {
+            tolerance,
+            substep: None,
+        }
+    }
+
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Substep the next step starts with, once a step was taken.
+    pub fn substep(&self) -> Option<f64> {
+        self.substep
+    }
+
+    /// Advances `positions` and `velocities` from `time` by `dt`.
+    pub fn advance<V: Coordinates>(&mut self,
+                                   time: f64,
+                                   positions: &mut [V],
+                                   velocities: &mut [V],
+                                   dt: f64,
+                                   acceleration: &Acceleration<V>)
+    {
+        let mut x = positions.to_vec();
+        let mut v = velocities.to_vec();
+        let mut elapsed = 0.0;
+        let mut h = self.substep.unwrap_or(dt).min(dt);
+        let min_step = dt * Self::MIN_STEP;
+
+        while elapsed < dt {
+            let remaining = dt - elapsed;
+            let last = h >= remaining;
+            let attempt = if last { remaining } else { h };
+
+            let (next_x, next_v, error) = self.attempt(time + elapsed, &x, &v, attempt, acceleration);
+
+            // erro NaN ou infinito (ex.: corpos coincidentes sem softening):
+            // encolhe ate o passo minimo e, se nem assim der, aceita o resto
+            // de uma vez para nao ficar preso com h = NaN
+            if !error.is_finite() {
+                if attempt > min_step {
+                    h = (attempt * Self::MIN_FACTOR).max(min_step);
+                    continue;
+                }
+
+                let (rest_x, rest_v, _) = self.attempt(time + elapsed, &x, &v, remaining, acceleration);
+                x = rest_x;
+                v = rest_v;
+                self.substep = None;
+                break;
+            }
+
+            // passos minusculos sao aceitos mesmo com erro alto para nao travar
+            if error <= 1.0 || attempt <= min_step {
+                x = next_x;
+                v = next_v;
+                elapsed = if last { dt } else { elapsed + attempt };
+            }
+
+            let factor = if error == 0.0 {
+                Self::MAX_FACTOR
+            } else {
+                (0.9 * error.powf(-0.2)).clamp(Self::MIN_FACTOR, Self::MAX_FACTOR)
+            };
+
+            h = attempt * factor;
+
+            // o ultimo passo encurtado nao deve reduzir a sugestao seguinte
+            if !last || error > 1.0 {
+                self.substep = Some(h);
+            }
+        }
+
+        positions.clone_from_slice(&x);
+        velocities.clone_from_slice(&v);
+    }
+
+    // tenta um passo de tamanho h; devolve o novo estado e o erro escalado
+    fn attempt<V: Coordinates>(&self,
+                               time: f64,
+                               x: &[V],
+                               v: &[V],
+                               h: f64,
+                               acceleration: &Acceleration<V>) -> (Vec<V>, Vec<V>, f64)
+    {
+        let mut kx: Vec<Vec<V>> = Vec::with_capacity(7);
+        let mut kv: Vec<Vec<V>> = Vec::with_capacity(7);
+
+        kx.push(v.to_vec());
+        kv.push(acceleration(time, x, v));
+
+        for (row, c) in DP_A.iter().zip(DP_C) {
+            let weights: Vec<f64> = row.iter().map(|a| a * h).collect();
+            let stage_x = combine(x, &kx.iter().map(Vec::as_slice).collect::<Vec<_>>(), &weights);
+            let stage_v = combine(v, &kv.iter().map(Vec::as_slice).collect::<Vec<_>>(), &weights);
+
+            kv.push(acceleration(time + c * h, &stage_x, &stage_v));
+            kx.push(stage_v);
+        }
+
+        let kx: Vec<&[V]> = kx.iter().map(Vec::as_slice).collect();
+        let kv: Vec<&[V]> = kv.iter().map(Vec::as_slice).collect();
+
+        let high: Vec<f64> = DP_B5.iter().map(|b| b * h).collect();
+        let low: Vec<f64> = DP_B4.iter().map(|b| b * h).collect();
+
+        let x5 = combine(x, &kx, &high);
+        let v5 = combine(v, &kv, &high);
+        let x4 = combine(x, &kx, &low);
+        let v4 = combine(v, &kv, &low);
+
+        let error = worst(self.error(&x5, &x4), self.error(&v5, &v4));
+
+        (x5, v5, error)
+    }
+
+    fn error<V: Coordinates>(&self, high: &[V], low: &[V]) -> f64 {
+        high.iter()
+            .zip(low)
+            .map(|(a, b)| {
+                let scale = self.tolerance * (1.0 + a.magnitude());
+                (a.clone() - b).magnitude() / scale
+            })
+            .fold(0.0, worst)
+    }
+}
+
+impl Default for Rk45 {
+    fn default() -> Self {
+        Self::new(1.0e-9)
+    }
+}
+
+// maior dos dois, mas NaN vence: f64::max esconderia um estado invalido
+fn worst(a: f64, b: f64) -> f64 {
+    if a.is_nan() || a > b { a } else { b }
+}
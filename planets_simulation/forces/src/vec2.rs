@@ -0,0 +1,125 @@
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::float::Float;
+
+/// A vector in the plane.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vec2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T: Float> Vec2<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self {
+            x,
+            y,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(T::ZERO, T::ZERO)
+    }
+
+    pub fn magnitude(&self) -> T {
+        self.magnitude_squared().sqrt()
+    }
+
+    pub fn magnitude_squared(&self) -> T {
+        self.dot_product(self)
+    }
+
+    /// Unit vector pointing from `self` towards `other`. NaN when they
+    /// are the same point; see `direction_or`.
+    pub fn direction(&self, other: &Self) -> Self {
+        (other - self).normalize()
+    }
+
+    /// Unit vector pointing from `self` towards `other`, or `fallback`
+    /// when the two are the same point.
+    pub fn direction_or(&self, other: &Self, fallback: Self) -> Self {
+        (other - self).try_normalize().unwrap_or(fallback)
+    }
+
+    /// Same direction, length one. The components are NaN for a zero
+    /// vector; see `try_normalize`.
+    pub fn normalize(&self) -> Self {
+        self / self.magnitude()
+    }
+
+    /// Same direction, length one, or `None` when the length is zero or
+    /// not finite.
+    pub fn try_normalize(&self) -> Option<Self> {
+        let magnitude = self.magnitude();
+
+        if magnitude > T::ZERO && magnitude.is_finite() {
+            Some(self / magnitude)
+        } else {
+            None
+        }
+    }
+
+    /// No component is NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
+    /// Some component is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.x.is_nan() || self.y.is_nan()
+    }
+
+    pub fn dot_product(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The `z` component of the 3D cross product, positive when `other` is
+    /// turned from `self` towards `+y`.
+    pub fn cross(&self, other: &Self) -> T {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Unsigned angle between the two vectors, in `[0, π]`. The cosine is
+    /// clamped so rounding cannot push it outside of what `acos` takes; NaN
+    /// if either vector is zero.
+    pub fn angle(&self, other: &Self) -> T {
+        let cos = self.dot_product(other) / (self.magnitude() * other.magnitude());
+
+        // `max` e `min` trocariam o NaN por um dos limites
+        if cos.is_nan() {
+            return cos;
+        }
+
+        cos.max(-T::ONE).min(T::ONE).acos()
+    }
+
+    /// Angle turning `self` onto `other`, in `[-π, π]` and positive towards
+    /// `+y`. Zero if either vector is zero.
+    pub fn signed_angle(&self, other: &Self) -> T {
+        self.cross(other).atan2(self.dot_product(other))
+    }
+
+    pub fn distance(&self, other: &Self) -> T {
+        (other - self).magnitude()
+    }
+
+    /// `self` at `t = 0`, `other` at `t = 1`.
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        self + &((other - self) * t)
+    }
+
+    /// Turned by `angle` radians from `+x` towards `+y`.
+    pub fn rotate(&self, angle: T) -> Self {
+        let (sin, cos) = (angle.sin(), angle.cos());
+
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Turned a quarter from `+x` towards `+y`.
+    pub fn perpendicular(&self) -> Self {
+        Self::new(-self.y, self.x)
+    }
+}
+
+vector_ops!(Vec2 { x, y });
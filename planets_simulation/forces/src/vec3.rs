@@ -0,0 +1,145 @@
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use crate::float::Float;
+use crate::vec2::Vec2;
+
+/// A vector in space.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Vec3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Float> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self {
+            x,
+            y,
+            z,
+        }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(T::ZERO, T::ZERO, T::ZERO)
+    }
+
+    pub fn magnitude(&self) -> T {
+        self.magnitude_squared().sqrt()
+    }
+
+    pub fn magnitude_squared(&self) -> T {
+        self.dot_product(self)
+    }
+
+    /// Unit vector pointing from `self` towards `other`. NaN when they
+    /// are the same point; see `direction_or`.
+    pub fn direction(&self, other: &Self) -> Self {
+        (other - self).normalize()
+    }
+
+    /// Unit vector pointing from `self` towards `other`, or `fallback`
+    /// when the two are the same point.
+    pub fn direction_or(&self, other: &Self, fallback: Self) -> Self {
+        (other - self).try_normalize().unwrap_or(fallback)
+    }
+
+    /// Same direction, length one. The components are NaN for a zero
+    /// vector; see `try_normalize`.
+    pub fn normalize(&self) -> Self {
+        self / self.magnitude()
+    }
+
+    /// Same direction, length one, or `None` when the length is zero or
+    /// not finite.
+    pub fn try_normalize(&self) -> Option<Self> {
+        let magnitude = self.magnitude();
+
+        if magnitude > T::ZERO && magnitude.is_finite() {
+            Some(self / magnitude)
+        } else {
+            None
+        }
+    }
+
+    /// No component is NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite() && self.z.is_finite()
+    }
+
+    /// Some component is NaN.
+    pub fn is_nan(&self) -> bool {
+        self.x.is_nan() || self.y.is_nan() || self.z.is_nan()
+    }
+
+    pub fn dot_product(&self, other: &Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn cross(&self, other: &Self) -> Self {
+        Self::new(self.y * other.z - self.z * other.y,
+                  self.z * other.x - self.x * other.z,
+                  self.x * other.y - self.y * other.x)
+    }
+
+    /// Unsigned angle between the two vectors, in `[0, π]`. The cosine is
+    /// clamped so rounding cannot push it outside of what `acos` takes; NaN
+    /// if either vector is zero.
+    pub fn angle(&self, other: &Self) -> T {
+        let cos = self.dot_product(other) / (self.magnitude() * other.magnitude());
+
+        // `max` e `min` trocariam o NaN por um dos limites
+        if cos.is_nan() {
+            return cos;
+        }
+
+        cos.max(-T::ONE).min(T::ONE).acos()
+    }
+
+    /// Angle turning `self` onto `other` about `axis`, in `[-π, π]` and
+    /// positive counterclockwise when the axis points at the viewer. Zero
+    /// if either vector is zero.
+    pub fn signed_angle(&self, other: &Self, axis: &Self) -> T {
+        let cross = self.cross(other);
+        let angle = cross.magnitude().atan2(self.dot_product(other));
+
+        if cross.dot_product(axis) < T::ZERO { -angle } else { angle }
+    }
+
+    pub fn distance(&self, other: &Self) -> T {
+        (other - self).magnitude()
+    }
+
+    /// `self` at `t = 0`, `other` at `t = 1`.
+    pub fn lerp(&self, other: &Self, t: T) -> Self {
+        self + &((other - self) * t)
+    }
+
+    /// Turned by `angle` radians around `axis`, counterclockwise when the
+    /// axis points at the viewer (Rodrigues' formula). `axis` need not be a
+    /// unit vector; a zero or non-finite one leaves `self` unchanged.
+    pub fn rotate(&self, axis: &Self, angle: T) -> Self {
+        let axis = match axis.try_normalize() {
+            Some(axis) => axis,
+            None => return self.clone(),
+        };
+        let (sin, cos) = (angle.sin(), angle.cos());
+
+        &(&(self * cos) + &(axis.cross(self) * sin)) +
+            &(&axis * (axis.dot_product(self) * (T::ONE - cos)))
+    }
+
+    /// `x` and `y`, dropping `z`.
+    pub fn xy(&self) -> Vec2<T> {
+        Vec2::new(self.x, self.y)
+    }
+}
+
+impl<T: Float> From<Vec2<T>> for Vec3<T> {
+    fn from(vector: Vec2<T>) -> Self {
+        Self::new(vector.x, vector.y, T::ZERO)
+    }
+}
+
+vector_ops!(Vec3 { x, y, z });
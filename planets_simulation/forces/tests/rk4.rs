@@ -0,0 +1,37 @@
+extern crate forces;
+
+use forces::{combine, Rk4, Vec2};
+
+#[test]
+fn follows_a_harmonic_oscillator() {
+    let (mut x, mut v) = ([1.0], [0.0]);
+    let spring = |_: f64, x: &[f64], _: &[f64]| vec![-x[0]];
+
+    for _ in 0..100 {
+        Rk4.advance(0.0, &mut x, &mut v, 0.1, &spring);
+    }
+
+    // erro global de quarta ordem
+    assert!((x[0] - 10.0_f64.cos()).abs() < 1.0e-5, "{}", x[0]);
+    assert!((v[0] + 10.0_f64.sin()).abs() < 1.0e-5, "{}", v[0]);
+}
+
+#[test]
+fn passes_the_time_of_each_stage() {
+    let (mut x, mut v) = ([0.0], [0.0]);
+
+    // a = t e exato para polinomios de grau baixo
+    Rk4.advance(2.0, &mut x, &mut v, 1.0, &|t, _, _| vec![t]);
+
+    assert!((v[0] - (9.0 - 4.0) / 2.0).abs() < 1.0e-12, "{}", v[0]);
+}
+
+#[test]
+fn combine_adds_weighted_terms() {
+    let base = [Vec2::new(1.0, 1.0)];
+    let a = [Vec2::new(1.0, 0.0)];
+    let b = [Vec2::new(0.0, f64::NAN)];
+
+    // peso zero ignora o termo, mesmo com NaN
+    assert_eq!(combine(&base, &[&a, &b], &[2.0, 0.0]), vec![Vec2::new(3.0, 1.0)]);
+}
@@ -0,0 +1,57 @@
+extern crate forces;
+
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+use forces::{Rk45, Vec3};
+
+#[test]
+fn follows_a_harmonic_oscillator_in_large_steps() {
+    let mut rk45 = Rk45::new(1.0e-10);
+    let (mut x, mut v) = ([1.0], [0.0]);
+    let spring = |_: f64, x: &[f64], _: &[f64]| vec![-x[0]];
+
+    // dez passos de um segundo: cada um e dividido em subpassos
+    for _ in 0..10 {
+        rk45.advance(0.0, &mut x, &mut v, 1.0, &spring);
+    }
+
+    assert!((x[0] - 10.0_f64.cos()).abs() < 1.0e-8, "{}", x[0]);
+    assert!((v[0] + 10.0_f64.sin()).abs() < 1.0e-8, "{}", v[0]);
+    assert!(rk45.substep().unwrap() < 1.0);
+}
+
+#[test]
+fn passes_the_time_of_each_stage() {
+    let mut rk45 = Rk45::default();
+    let (mut x, mut v) = ([0.0], [0.0]);
+
+    // a = t, entao v = t^2 / 2 a partir de t = 2
+    rk45.advance(2.0, &mut x, &mut v, 1.0, &|t, _, _| vec![t]);
+
+    assert!((v[0] - (9.0 - 4.0) / 2.0).abs() < 1.0e-12, "{}", v[0]);
+}
+
+#[test]
+fn finishes_when_the_error_is_not_finite() {
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        for value in [f64::NAN, f64::INFINITY] {
+            let mut x = [Vec3::new(0.0, 0.0, 0.0)];
+            let mut v = [Vec3::new(1.0, 0.0, 0.0)];
+            let acceleration = |_: f64, x: &[Vec3<f64>], _: &[Vec3<f64>]| {
+                vec![Vec3::new(value, 0.0, 0.0); x.len()]
+            };
+
+            let mut rk45 = Rk45::new(1.0e-9);
+            rk45.advance(0.0, &mut x, &mut v, 0.1, &acceleration);
+            rk45.advance(0.1, &mut x, &mut v, 0.1, &acceleration);
+        }
+
+        sender.send(()).unwrap();
+    });
+
+    assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok(), "Rk45 never finished the step");
+}
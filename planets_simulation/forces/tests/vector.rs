@@ -0,0 +1,308 @@
+extern crate forces;
+
+use std::f64::consts::PI;
+
+use forces::{Coordinates, Vec2, Vec3, Vector};
+
+const CASES: usize = 1_000;
+
+// gerador congruencial: os casos sao sempre os mesmos
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> f64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    fn range(&mut self, low: f64, high: f64) -> f64 {
+        low + (high - low) * self.next()
+    }
+
+    fn vec2(&mut self) -> Vector {
+        Vec2::new(self.range(-100.0, 100.0), self.range(-100.0, 100.0))
+    }
+
+    fn vec3(&mut self) -> Vec3<f64> {
+        Vec3::new(self.range(-100.0, 100.0), self.range(-100.0, 100.0), self.range(-100.0, 100.0))
+    }
+}
+
+fn check<F: FnMut(&mut Random)>(mut property: F) {
+    let mut random = Random(0x5eed);
+
+    for _ in 0..CASES {
+        property(&mut random);
+    }
+}
+
+fn close(a: f64, b: f64, scale: f64) -> bool {
+    (a - b).abs() <= 1.0e-9 * scale.max(1.0)
+}
+
+fn close2(a: &Vector, b: &Vector) -> bool {
+    (a - b).magnitude() <= 1.0e-9 * a.magnitude().max(b.magnitude()).max(1.0)
+}
+
+fn close3(a: &Vec3<f64>, b: &Vec3<f64>) -> bool {
+    (a - b).magnitude() <= 1.0e-9 * a.magnitude().max(b.magnitude()).max(1.0)
+}
+
+#[test]
+fn operators_agree_for_values_and_references() {
+    check(|random| {
+        let (a, b, s) = (random.vec2(), random.vec2(), random.range(-10.0, 10.0));
+
+        assert_eq!(&a + &b, a.clone() + b.clone());
+        assert_eq!(&a - &b, a.clone() - &b);
+        assert_eq!(&a * s, a.clone() * s);
+        assert_eq!(s * &a, &a * s);
+        assert_eq!(s * a.clone(), &a * s);
+        assert_eq!(-&a, a.clone() * -1.0);
+        assert!(close2(&(&a / s), &(&a * (1.0 / s))));
+
+        let mut c = a.clone();
+        c += &b;
+        c -= b.clone();
+        c *= s;
+        c /= s;
+        assert!(close2(&c, &a));
+    });
+}
+
+#[test]
+fn addition_is_commutative_and_subtraction_undoes_it() {
+    check(|random| {
+        let (a, b) = (random.vec2(), random.vec2());
+
+        assert_eq!(&a + &b, &b + &a);
+        assert!(close2(&(&(&a + &b) - &b), &a));
+
+        let (a, b) = (random.vec3(), random.vec3());
+
+        assert_eq!(&a + &b, &b + &a);
+        assert!(close3(&(&(&a + &b) - &b), &a));
+    });
+}
+
+#[test]
+fn normalize_gives_unit_vectors_along_the_original() {
+    check(|random| {
+        let a = random.vec2();
+        let unit = a.normalize();
+
+        assert!(close(unit.magnitude(), 1.0, 1.0));
+        assert!(close(unit.dot_product(&a), a.magnitude(), a.magnitude()));
+
+        let a = random.vec3();
+        assert!(close(a.normalize().magnitude(), 1.0, 1.0));
+    });
+
+    assert!(Vector::zero().normalize().x.is_nan());
+}
+
+#[test]
+fn direction_points_at_the_other_vector() {
+    check(|random| {
+        let (a, b) = (random.vec2(), random.vec2());
+
+        assert!(close2(&(&a + &(a.direction(&b) * a.distance(&b))), &b));
+    });
+}
+
+#[test]
+fn distance_is_a_metric() {
+    check(|random| {
+        let (a, b, c) = (random.vec2(), random.vec2(), random.vec2());
+
+        assert_eq!(a.distance(&a), 0.0);
+        assert_eq!(a.distance(&b), b.distance(&a));
+        assert!(a.distance(&c) <= a.distance(&b) + b.distance(&c) + 1.0e-9);
+
+        let (a, b) = (random.vec3(), random.vec3());
+        assert_eq!(a.distance(&b), (&a - &b).magnitude());
+    });
+}
+
+#[test]
+fn rotation_keeps_length_and_turns_by_the_angle() {
+    check(|random| {
+        let (a, angle) = (random.vec2(), random.range(-PI, PI));
+        let turned = a.rotate(angle);
+
+        assert!(close(turned.magnitude(), a.magnitude(), a.magnitude()));
+        assert!(close2(&turned.rotate(-angle), &a));
+        assert!((a.angle(&turned) - angle.abs()).abs() < 1.0e-6);
+        assert!(a.cross(&turned) * angle >= 0.0);
+    });
+
+    assert!(close2(&Vec2::new(1.0, 0.0).rotate(PI / 2.0), &Vec2::new(0.0, 1.0)));
+    assert_eq!(Vec2::new(1.0, 2.0).perpendicular(), Vec2::new(-2.0, 1.0));
+}
+
+#[test]
+fn rotation_in_space_keeps_the_axis_component() {
+    check(|random| {
+        let (a, axis, angle) = (random.vec3(), random.vec3(), random.range(-PI, PI));
+        let turned = a.rotate(&axis, angle);
+        let unit = axis.normalize();
+
+        assert!(close(turned.magnitude(), a.magnitude(), a.magnitude()));
+        assert!(close(turned.dot_product(&unit), a.dot_product(&unit), a.magnitude()));
+        assert!(close3(&turned.rotate(&axis, -angle), &a));
+    });
+
+    let x = Vec3::new(1.0, 0.0, 0.0);
+    let z = Vec3::new(0.0, 0.0, 1.0);
+    assert!(close3(&x.rotate(&z, PI / 2.0), &Vec3::new(0.0, 1.0, 0.0)));
+
+    // eixo degenerado nao gira nada
+    assert_eq!(x.rotate(&Vec3::zero(), 1.0), x);
+    assert_eq!(x.rotate(&Vec3::new(f64::NAN, 0.0, 1.0), 1.0), x);
+}
+
+#[test]
+fn cross_product_is_perpendicular_and_antisymmetric() {
+    check(|random| {
+        let (a, b) = (random.vec3(), random.vec3());
+        let c = a.cross(&b);
+        let scale = a.magnitude() * b.magnitude() * c.magnitude();
+
+        assert_eq!(c, -b.cross(&a));
+        assert!(close(c.dot_product(&a), 0.0, scale));
+        assert!(close(c.dot_product(&b), 0.0, scale));
+
+        // |a x b| = |a||b| sin(angulo)
+        let sin = a.angle(&b).sin();
+        assert!((c.magnitude() - a.magnitude() * b.magnitude() * sin).abs() < 1.0e-6 * scale.max(1.0));
+
+        let (a, b) = (random.vec2(), random.vec2());
+        assert_eq!(a.cross(&b), Vec3::from(a.clone()).cross(&Vec3::from(b.clone())).z);
+        assert_eq!(a.cross(&b), -b.cross(&a));
+    });
+}
+
+#[test]
+fn lerp_runs_along_the_segment() {
+    check(|random| {
+        let (a, b, t) = (random.vec2(), random.vec2(), random.next());
+        let point = a.lerp(&b, t);
+
+        assert_eq!(a.lerp(&b, 0.0), a);
+        assert!(close2(&a.lerp(&b, 1.0), &b));
+        assert!(close(a.distance(&point) + point.distance(&b), a.distance(&b), a.distance(&b)));
+
+        let (a, b) = (random.vec3(), random.vec3());
+        assert!(close3(&a.lerp(&b, 0.5), &(&(&a + &b) * 0.5)));
+    });
+}
+
+#[test]
+fn works_with_single_precision() {
+    let a: Vec2<f32> = Vec2::new(3.0, 4.0);
+    let b: Vec3<f32> = Vec3::new(1.0, 2.0, 2.0);
+
+    assert_eq!(a.magnitude(), 5.0);
+    assert_eq!(2.0f32 * &a, Vec2::new(6.0, 8.0));
+    assert_eq!(b.magnitude(), 3.0);
+    assert_eq!(b.xy(), Vec2::new(1.0, 2.0));
+    assert!((a.normalize().magnitude() - 1.0).abs() < f32::EPSILON * 2.0);
+}
+
+#[test]
+fn sums_vectors() {
+    let vectors = vec![Vec2::new(1.0, 2.0), Vec2::new(3.0, 4.0), Vec2::new(-1.0, 0.5)];
+
+    assert_eq!(vectors.iter().sum::<Vector>(), Vec2::new(3.0, 6.5));
+    assert_eq!(vectors.into_iter().sum::<Vector>(), Vec2::new(3.0, 6.5));
+    assert_eq!(Vec::<Vector>::new().iter().sum::<Vector>(), Vector::zero());
+}
+
+// so usa o que `Coordinates` oferece
+fn midpoint<V: Coordinates>(a: &V, b: &V) -> V {
+    (a.clone() + b) * 0.5
+}
+
+#[test]
+fn coordinates_work_in_both_dimensions() {
+    check(|random| {
+        let (a, b) = (random.vec2(), random.vec2());
+        assert!(close2(&midpoint(&a, &b), &a.lerp(&b, 0.5)));
+        assert_eq!(Coordinates::magnitude(&a), a.magnitude());
+
+        let (a, b) = (random.vec3(), random.vec3());
+        assert!(close3(&midpoint(&a, &b), &a.lerp(&b, 0.5)));
+        assert_eq!(Coordinates::dot_product(&a, &b), a.dot_product(&b));
+    });
+
+    assert_eq!(<Vec3<f64> as Coordinates>::zero(), Vec3::zero());
+}
+
+#[test]
+fn checked_normalize_refuses_degenerate_vectors() {
+    check(|random| {
+        let a = random.vec2();
+        assert_eq!(a.try_normalize(), Some(a.normalize()));
+
+        let (a, b) = (random.vec3(), random.vec3());
+        assert_eq!(a.direction_or(&b, Vec3::zero()), a.direction(&b));
+    });
+
+    let fallback = Vec2::new(1.0, 0.0);
+    assert_eq!(Vector::zero().try_normalize(), None);
+    assert_eq!(Vec2::new(f64::INFINITY, 0.0).try_normalize(), None);
+    assert_eq!(Vec2::new(f64::NAN, 1.0).try_normalize(), None);
+    assert_eq!(Vec2::new(3.0, 4.0).direction_or(&Vec2::new(3.0, 4.0), fallback.clone()), fallback);
+    assert_eq!(Vec3::<f64>::zero().try_normalize(), None);
+}
+
+#[test]
+fn angle_stays_defined_for_parallel_vectors() {
+    check(|random| {
+        let (a, scale) = (random.vec2(), random.range(0.1, 10.0));
+
+        assert!(a.angle(&(&a * scale)).abs() < 1.0e-6);
+        assert!((a.angle(&(&a * -scale)) - PI).abs() < 1.0e-6);
+
+        let a = random.vec3();
+        assert!(!a.angle(&(&a * scale)).is_nan());
+    });
+
+    // cos sairia de [-1, 1] por arredondamento
+    let a = Vec2::new(0.1, 0.7);
+    assert_eq!(a.angle(&(&a * 3.0)), 0.0);
+    assert!(a.angle(&Vector::zero()).is_nan());
+}
+
+#[test]
+fn signed_angle_follows_the_turn() {
+    check(|random| {
+        let (a, angle) = (random.vec2(), random.range(-3.0, 3.0));
+        let turned = a.rotate(angle);
+
+        assert!((a.signed_angle(&turned) - angle).abs() < 1.0e-6);
+        assert!((turned.signed_angle(&a) + angle).abs() < 1.0e-6);
+
+        let (a, axis) = (random.vec3(), random.vec3());
+        // so a parte perpendicular ao eixo gira pelo angulo pedido
+        let flat = &a - &(&axis.normalize() * a.dot_product(&axis.normalize()));
+        let turned = flat.rotate(&axis, angle);
+
+        assert!((flat.signed_angle(&turned, &axis) - angle).abs() < 1.0e-6);
+        assert!((flat.signed_angle(&turned, &-&axis) + angle).abs() < 1.0e-6);
+    });
+
+    assert_eq!(Vector::zero().signed_angle(&Vec2::new(1.0, 0.0)), 0.0);
+    assert_eq!(Vec2::new(1.0, -0.0).signed_angle(&Vec2::new(-1.0, -0.0)), -PI);
+}
+
+#[test]
+fn detects_nan_and_infinity() {
+    assert!(Vec2::new(1.0, 2.0).is_finite());
+    assert!(!Vec2::new(1.0, f64::INFINITY).is_finite());
+    assert!(!Vec2::new(1.0, f64::INFINITY).is_nan());
+    assert!(Vec2::new(f64::NAN, 2.0).is_nan());
+    assert!(!Vec3::new(0.0, 0.0, f64::NEG_INFINITY).is_finite());
+    assert!(Vec3::new(0.0, f64::NAN, 0.0).is_nan());
+    assert!(Vector::zero().normalize().is_nan());
+}
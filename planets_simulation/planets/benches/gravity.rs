@@ -0,0 +1,70 @@
+// cargo bench -p planets
+//
+// Compara o tempo de uma avaliacao de forcas por solver e o erro medio
+// do Barnes-Hut em relacao a soma direta.
+extern crate forces;
+extern crate planets;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use forces::Vector;
+use planets::gravity::{Gravity, Solver};
+
+// gerador congruencial: os mesmos corpos em toda execucao
+fn bodies(count: usize) -> (Vec<Vector>, Vec<f64>) {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    (0..count)
+        .map(|_| {
+            let radius = 1000.0 * next().sqrt();
+            let angle = 2.0 * std::f64::consts::PI * next();
+            let mass = 1.0e-3 + next();
+
+            (Vector::new(radius * angle.cos(), radius * angle.sin()), mass)
+        })
+        .unzip()
+}
+
+fn time(solver: Solver, positions: &[Vector], masses: &[f64]) -> Duration {
+    let runs = 5;
+    let start = Instant::now();
+
+    for _ in 0..runs {
+        black_box(solver.accelerations(black_box(positions), black_box(masses), &Gravity::new(100.0)));
+    }
+
+    start.elapsed() / runs
+}
+
+fn mean_error(approximate: &[Vector], exact: &[Vector]) -> f64 {
+    approximate.iter()
+        .zip(exact)
+        .map(|(a, e)| (a - e).magnitude() / e.magnitude())
+        .sum::<f64>() / exact.len() as f64
+}
+
+fn main() {
+    println!("{:>6} {:>12} {:>14} {:>14} {:>14}",
+             "bodies", "direct", "theta=0.3", "theta=0.5", "theta=1.0");
+
+    for count in [100, 1_000, 5_000] {
+        let (positions, masses) = bodies(count);
+        let exact = Solver::Direct.accelerations(&positions, &masses, &Gravity::new(100.0));
+
+        print!("{:>6} {:>12.2?}", count, time(Solver::Direct, &positions, &masses));
+
+        for theta in [0.3, 0.5, 1.0] {
+            let solver = Solver::BarnesHut { theta };
+            let error = mean_error(&solver.accelerations(&positions, &masses, &Gravity::new(100.0)), &exact);
+
+            print!(" {:>8.2?} {:.0e}", time(solver, &positions, &masses), error);
+        }
+
+        println!();
+    }
+}
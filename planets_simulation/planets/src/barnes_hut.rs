@@ -0,0 +1,235 @@
+use forces::Vector;
+
+use crate::gravity::Gravity;
+use crate::space::Space;
+
+// abaixo disso os corpos ficam todos na mesma folha (posicoes repetidas)
+const MAX_DEPTH: usize = 48;
+
+/// Quadtree over a set of bodies, each node summarised by its total mass
+/// and center of mass. In space every node splits in eight, an octree.
+pub struct Quadtree<V = Vector> {
+    nodes: Vec<Node<V>>,
+}
+
+struct Node<V> {
+    center: V,
+    half_size: f64,
+    mass: f64,
+    center_of_mass: V,
+    bodies: Vec<usize>,
+    // os filhos ficam juntos a partir deste indice
+    children: Option<usize>,
+}
+
+impl<V: Space> Node<V> {
+    fn new(center: V, half_size: f64) -> Self {
+        Self {
+            center_of_mass: center.clone(),
+            center,
+            half_size,
+            mass: 0.0,
+            bodies: vec![],
+            children: None,
+        }
+    }
+
+    fn contains(&self, position: &V) -> bool {
+        (0..V::AXES).all(|axis| (position.axis(axis) - self.center.axis(axis)).abs() <= self.half_size)
+    }
+
+    // bit i ligado = do lado positivo do eixo i
+    fn quadrant(&self, position: &V) -> usize {
+        (0..V::AXES)
+            .filter(|&axis| position.axis(axis) >= self.center.axis(axis))
+            .fold(0, |quadrant, axis| quadrant | 1 << axis)
+    }
+}
+
+impl<V: Space> Quadtree<V> {
+    pub fn new(positions: &[V], masses: &[f64]) -> Self {
+        let (center, half_size) = bounds(positions);
+        let mut tree = Self {
+            nodes: vec![Node::new(center, half_size)],
+        };
+
+        for body in 0..positions.len() {
+            tree.insert(0, body, positions, 0);
+        }
+
+        tree.summarise(0, positions, masses);
+        tree
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.nodes[0].mass
+    }
+
+    pub fn center_of_mass(&self) -> &V {
+        &self.nodes[0].center_of_mass
+    }
+
+    /// Acceleration of `body` at `positions[body]`.
+    ///
+    /// A node whose size seen from the body is below `theta` is treated as
+    /// a single mass at its center of mass; `theta = 0` visits every body
+    /// and gives the exact pairwise result.
+    pub fn acceleration(&self,
+                        body: usize,
+                        positions: &[V],
+                        masses: &[f64],
+                        gravity: &Gravity,
+                        theta: f64) -> V
+    {
+        let position = &positions[body];
+        let mut acceleration = V::zero();
+        let mut pending = vec![0];
+
+        while let Some(index) = pending.pop() {
+            let node = &self.nodes[index];
+
+            if node.mass == 0.0 {
+                continue;
+            }
+
+            match node.children {
+                None => {
+                    for &other in &node.bodies {
+                        if other != body {
+                            acceleration += gravity.pull(position, &positions[other], masses[other]);
+                        }
+                    }
+                },
+                Some(first) => {
+                    let distance = (node.center_of_mass.clone() - position).magnitude();
+                    let far = 2.0 * node.half_size < theta * distance;
+
+                    // um no que contem o proprio corpo nunca vira uma massa so
+                    if far && !node.contains(position) {
+                        acceleration += gravity.pull(position, &node.center_of_mass, node.mass);
+                    } else {
+                        pending.extend(first..first + children::<V>());
+                    }
+                },
+            }
+        }
+
+        acceleration
+    }
+
+    fn insert(&mut self, index: usize, body: usize, positions: &[V], depth: usize) {
+        if let Some(first) = self.nodes[index].children {
+            let quadrant = self.nodes[index].quadrant(&positions[body]);
+            self.insert(first + quadrant, body, positions, depth + 1);
+            return;
+        }
+
+        if self.nodes[index].bodies.is_empty() || depth >= MAX_DEPTH {
+            self.nodes[index].bodies.push(body);
+            return;
+        }
+
+        self.subdivide(index);
+
+        let bodies = std::mem::take(&mut self.nodes[index].bodies);
+
+        for other in bodies.into_iter().chain(Some(body)) {
+            self.insert(index, other, positions, depth);
+        }
+    }
+
+    fn subdivide(&mut self, index: usize) {
+        let quarter = self.nodes[index].half_size / 2.0;
+        let center = self.nodes[index].center.clone();
+        let first = self.nodes.len();
+
+        // mesma ordem de `Node::quadrant`
+        for quadrant in 0..children::<V>() {
+            let axes: Vec<f64> = (0..V::AXES)
+                .map(|axis| {
+                    let sign = if quadrant & 1 << axis != 0 { 1.0 } else { -1.0 };
+                    center.axis(axis) + sign * quarter
+                })
+                .collect();
+
+            self.nodes.push(Node::new(V::from_axes(&axes), quarter));
+        }
+
+        self.nodes[index].children = Some(first);
+    }
+
+    fn summarise(&mut self, index: usize, positions: &[V], masses: &[f64]) {
+        let weighted = match self.nodes[index].children {
+            None => {
+                self.nodes[index].bodies
+                    .iter()
+                    .map(|&body| (masses[body], positions[body].clone() * masses[body]))
+                    .collect::<Vec<_>>()
+            },
+            Some(first) => {
+                (first..first + children::<V>())
+                    .map(|child| {
+                        self.summarise(child, positions, masses);
+
+                        let node = &self.nodes[child];
+                        (node.mass, node.center_of_mass.clone() * node.mass)
+                    })
+                    .collect()
+            },
+        };
+
+        let (mass, moment) = weighted.into_iter()
+            .fold((0.0, V::zero()), |(mass, moment), (m, weighted)| (mass + m, moment + weighted));
+
+        let node = &mut self.nodes[index];
+        node.mass = mass;
+
+        if mass > 0.0 {
+            node.center_of_mass = moment * (1.0 / mass);
+        }
+    }
+}
+
+/// Barnes-Hut approximation of `gravity::direct`, O(n log n) instead of
+/// O(n²). Forces are no longer exactly pairwise symmetric, so momentum is
+/// only conserved up to the approximation error.
+pub fn accelerations<V: Space>(positions: &[V],
+                               masses: &[f64],
+                               gravity: &Gravity,
+                               theta: f64) -> Vec<V>
+{
+    let tree = Quadtree::new(positions, masses);
+
+    (0..positions.len())
+        .map(|body| tree.acceleration(body, positions, masses, gravity, theta))
+        .collect()
+}
+
+// filhos de cada no: 4 no plano, 8 no espaco
+fn children<V: Space>() -> usize {
+    1 << V::AXES
+}
+
+// quadrado (ou cubo) que envolve todas as posicoes
+fn bounds<V: Space>(positions: &[V]) -> (V, f64) {
+    if positions.is_empty() {
+        return (V::zero(), 1.0);
+    }
+
+    let mut min = vec![f64::INFINITY; V::AXES];
+    let mut max = vec![f64::NEG_INFINITY; V::AXES];
+
+    for position in positions {
+        for axis in 0..V::AXES {
+            min[axis] = min[axis].min(position.axis(axis));
+            max[axis] = max[axis].max(position.axis(axis));
+        }
+    }
+
+    let half_size = (0..V::AXES)
+        .map(|axis| (max[axis] - min[axis]) / 2.0)
+        .fold(1.0e-9, f64::max);
+    let center: Vec<f64> = (0..V::AXES).map(|axis| (min[axis] + max[axis]) / 2.0).collect();
+
+    (V::from_axes(&center), half_size)
+}
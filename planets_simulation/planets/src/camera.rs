@@ -0,0 +1,191 @@
+use forces::Vector;
+
+use crate::{PlanetId, Planets};
+
+// bem perto do corpo ainda conta como clique nele
+const PICK_MARGIN: f64 = 4.0;
+
+/// A frame turning at a constant rate about an origin moving at a
+/// constant velocity; `origin` and `angle` (of the frame's `+x` axis) are
+/// the current ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotatingFrame {
+    pub origin: Vector,
+    pub velocity: Vector,
+    pub angle: f64,
+    pub angular_velocity: f64,
+    /// Time between two steps, to place points recorded in the past.
+    pub timestep: f64,
+}
+
+impl RotatingFrame {
+    /// Coordinates in the frame of a point recorded `steps_ago` steps back.
+    pub fn to_frame(&self, point: &Vector, steps_ago: u64) -> Vector {
+        let time = steps_ago as f64 * self.timestep;
+        let origin = &self.origin - &(&self.velocity * time);
+        let angle = self.angle - self.angular_velocity * time;
+
+        (point - &origin).rotate(-angle)
+    }
+
+    /// Current position of a point given in the frame.
+    pub fn from_frame(&self, point: &Vector) -> Vector {
+        &self.origin + &point.rotate(self.angle)
+    }
+}
+
+/// Maps simulation coordinates to window pixels.
+///
+/// `center` is the point of the simulation shown in the middle of the
+/// viewport and `zoom` the number of pixels per simulation unit. A new
+/// camera over an 800x600 viewport shows the same picture as drawing
+/// positions straight as pixels.
+///
+/// With a rotating frame set, `center` is given in that frame and
+/// everything is drawn as seen from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera {
+    pub center: Vector,
+    zoom: f64,
+    viewport: (u32, u32),
+    following: Option<PlanetId>,
+    frame: Option<RotatingFrame>,
+}
+
+impl Camera {
+    pub const MIN_ZOOM: f64 = 1.0e-4;
+    pub const MAX_ZOOM: f64 = 1.0e4;
+
+    pub fn new(viewport: (u32, u32)) -> Self {
+        Self {
+            center: Vector::new(viewport.0 as f64 / 2.0, viewport.1 as f64 / 2.0),
+            zoom: 1.0,
+            viewport,
+            following: None,
+            frame: None,
+        }
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    pub fn viewport(&self) -> (u32, u32) {
+        self.viewport
+    }
+
+    pub fn set_viewport(&mut self, viewport: (u32, u32)) {
+        self.viewport = viewport;
+    }
+
+    pub fn set_frame(&mut self, frame: Option<RotatingFrame>) {
+        self.frame = frame;
+    }
+
+    pub fn frame(&self) -> Option<&RotatingFrame> {
+        self.frame.as_ref()
+    }
+
+    /// Pixel coordinates of a point of the simulation.
+    pub fn to_screen(&self, position: &Vector) -> (i32, i32) {
+        self.to_screen_at(position, 0)
+    }
+
+    /// Pixel coordinates of a position recorded `steps_ago` steps back,
+    /// which only differ from `to_screen` in a rotating frame.
+    pub fn to_screen_at(&self, position: &Vector, steps_ago: u64) -> (i32, i32) {
+        let (x, y) = self.to_screen_f64(position, steps_ago);
+
+        (x.round() as i32, y.round() as i32)
+    }
+
+    // posicao no referencial da camera
+    fn project(&self, position: &Vector, steps_ago: u64) -> Vector {
+        match &self.frame {
+            Some(frame) => frame.to_frame(position, steps_ago),
+            None => position.clone(),
+        }
+    }
+
+    fn to_screen_f64(&self, position: &Vector, steps_ago: u64) -> (f64, f64) {
+        let offset = &(&self.project(position, steps_ago) - &self.center) * self.zoom;
+
+        (offset.x + self.viewport.0 as f64 / 2.0, offset.y + self.viewport.1 as f64 / 2.0)
+    }
+
+    /// Point of the simulation under a pixel.
+    pub fn to_world(&self, x: i32, y: i32) -> Vector {
+        let offset = Vector::new(x as f64 - self.viewport.0 as f64 / 2.0,
+                                 y as f64 - self.viewport.1 as f64 / 2.0);
+
+        let point = &self.center + &(&offset * (1.0 / self.zoom));
+
+        match &self.frame {
+            Some(frame) => frame.from_frame(&point),
+            None => point,
+        }
+    }
+
+    /// Length in pixels of a distance in the simulation.
+    pub fn scale(&self, distance: f64) -> f64 {
+        distance * self.zoom
+    }
+
+    /// Moves the view as if dragged by `dx`, `dy` pixels. Stops following.
+    pub fn pan(&mut self, dx: i32, dy: i32) {
+        self.following = None;
+        self.center = &self.center - &(&Vector::new(dx as f64, dy as f64) * (1.0 / self.zoom));
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the point under pixel
+    /// (`x`, `y`) in place.
+    pub fn zoom_at(&mut self, factor: f64, x: i32, y: i32) {
+        let anchor = self.project(&self.to_world(x, y), 0);
+
+        self.zoom = (self.zoom * factor).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+
+        // quando seguindo, o centro continua no corpo
+        if self.following.is_none() {
+            let moved = self.project(&self.to_world(x, y), 0);
+            self.center = &self.center + &(&anchor - &moved);
+        }
+    }
+
+    /// Keeps `planet` in the middle of the view, updated by `update`.
+    pub fn follow(&mut self, planet: Option<PlanetId>) {
+        self.following = planet;
+    }
+
+    pub fn following(&self) -> Option<PlanetId> {
+        self.following
+    }
+
+    /// Recenters on the followed planet; forgets it once it is gone (e.g.
+    /// merged into another one).
+    pub fn update(&mut self, planets: &Planets) {
+        if let Some(id) = self.following {
+            match planets.get(id) {
+                Some(planet) => self.center = self.project(&planet.position, 0),
+                None => self.following = None,
+            }
+        }
+    }
+
+    /// Planet drawn under pixel (`x`, `y`), the closest one if several.
+    pub fn pick(&self, planets: &Planets, x: i32, y: i32) -> Option<PlanetId> {
+        planets.planets()
+            .iter()
+            .filter_map(|planet| {
+                let (px, py) = self.to_screen_f64(&planet.position, 0);
+                let distance = (px - x as f64).hypot(py - y as f64);
+
+                if distance <= self.scale(planet.radius()) + PICK_MARGIN {
+                    Some((distance, planet.id()?))
+                } else {
+                    None
+                }
+            })
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .map(|(_, id)| id)
+    }
+}
@@ -0,0 +1,94 @@
+use forces::Coordinates;
+use serde::Deserialize;
+
+use crate::Planet;
+
+/// What happens when two planets overlap (distance below the sum of their
+/// radii) at the end of a step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Collision {
+    /// Bodies pass through each other.
+    #[default]
+    Ignore,
+    /// Perfectly inelastic: the bodies become one, conserving mass and
+    /// momentum. The heavier body keeps its id, colors and trajectory and
+    /// the radius grows as if the volumes were added.
+    Merge,
+    /// Perfectly elastic bounce along the line between the centers, with
+    /// the overlap pushed apart around the center of mass.
+    Bounce,
+}
+
+fn overlapping<V: Coordinates>(a: &Planet<V>, b: &Planet<V>) -> bool {
+    (a.position.clone() - &b.position).magnitude() < a.radius + b.radius
+}
+
+/// Merges overlapping planets until none overlap.
+pub(crate) fn merge<V: Coordinates>(planets: &mut Vec<Planet<V>>) {
+    // um corpo que cresceu pode passar a tocar outro ja visitado,
+    // entao recomeca do zero a cada fusao
+    'search: loop {
+        for i in 0..planets.len() {
+            for j in (i + 1)..planets.len() {
+                if overlapping(&planets[i], &planets[j]) {
+                    let other = planets.remove(j);
+                    absorb(&mut planets[i], other);
+                    continue 'search;
+                }
+            }
+        }
+
+        break;
+    }
+}
+
+fn absorb<V: Coordinates>(planet: &mut Planet<V>, other: Planet<V>) {
+    let mass = planet.mass + other.mass;
+    let (a, b) = (planet.mass / mass, other.mass / mass);
+
+    planet.position = planet.position.clone() * a + other.position.clone() * b;
+    planet.velocity = planet.velocity.clone() * a + other.velocity.clone() * b;
+    planet.radius = (planet.radius.powi(3) + other.radius.powi(3)).cbrt();
+
+    if other.mass > planet.mass {
+        planet.id = other.id;
+        planet.color = other.color;
+        planet.trail_color = other.trail_color;
+        planet.trajectory = other.trajectory;
+    }
+
+    planet.mass = mass;
+}
+
+/// Bounces every overlapping pair that is still approaching.
+pub(crate) fn bounce<V: Coordinates>(planets: &mut [Planet<V>]) {
+    for i in 0..planets.len() {
+        let (left, right) = planets.split_at_mut(i + 1);
+        let a = &mut left[i];
+
+        for b in right.iter_mut() {
+            let offset = b.position.clone() - &a.position;
+            let distance = offset.magnitude();
+
+            if distance == 0.0 || distance >= a.radius + b.radius {
+                continue;
+            }
+
+            let normal = offset * (1.0 / distance);
+            let total = a.mass + b.mass;
+
+            let overlap = a.radius + b.radius - distance;
+            a.position -= normal.clone() * (overlap * b.mass / total);
+            b.position += normal.clone() * (overlap * a.mass / total);
+
+            // velocidade de aproximacao ao longo da normal (negativa = se aproximando)
+            let closing = (b.velocity.clone() - &a.velocity).dot_product(&normal);
+
+            if closing < 0.0 {
+                a.velocity += normal.clone() * (2.0 * b.mass / total * closing);
+                b.velocity -= normal * (2.0 * a.mass / total * closing);
+            }
+        }
+    }
+}
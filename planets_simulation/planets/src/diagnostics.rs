@@ -0,0 +1,93 @@
+use forces::{Coordinates, Vector};
+
+use crate::Planet;
+use crate::gravity::Gravity;
+use crate::space::Space;
+
+/// Conserved quantities of a system at one instant.
+///
+/// Angular momentum is taken about the origin; in 2D it only has a `z`
+/// component, stored as a scalar, and in space it is a vector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostics<V: Space = Vector> {
+    pub kinetic_energy: f64,
+    pub potential_energy: f64,
+    pub momentum: V,
+    pub angular_momentum: V::Angular,
+    pub center_of_mass: V,
+}
+
+/// Change of each quantity since a baseline, relative to the baseline value
+/// (or absolute when the baseline is zero, e.g. momentum in the center of
+/// mass frame).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drift {
+    pub energy: f64,
+    pub momentum: f64,
+    pub angular_momentum: f64,
+}
+
+impl<V: Space> Diagnostics<V> {
+    /// Measures `planets` under `gravity`. The potential energy sums every
+    /// pair, so this is O(n²).
+    pub fn measure(planets: &[Planet<V>], gravity: &Gravity) -> Self {
+        let mut kinetic_energy = 0.0;
+        let mut potential_energy = 0.0;
+        let mut momentum = V::zero();
+        let mut angular_momentum = V::Angular::zero();
+        let mut moment = V::zero();
+        let mut mass = 0.0;
+
+        for (i, planet) in planets.iter().enumerate() {
+            let (position, velocity) = (&planet.position, &planet.velocity);
+
+            kinetic_energy += 0.5 * planet.mass() * velocity.dot_product(velocity);
+            momentum += velocity.clone() * planet.mass();
+            angular_momentum += position.moment(velocity) * planet.mass();
+            moment += position.clone() * planet.mass();
+            mass += planet.mass();
+
+            for other in &planets[i + 1..] {
+                let distance = (other.position.clone() - position).magnitude();
+                potential_energy += gravity.potential(planet.mass(), other.mass(), distance);
+            }
+        }
+
+        let center_of_mass = if mass > 0.0 {
+            moment * (1.0 / mass)
+        } else {
+            V::zero()
+        };
+
+        Self {
+            kinetic_energy,
+            potential_energy,
+            momentum,
+            angular_momentum,
+            center_of_mass,
+        }
+    }
+
+    pub fn total_energy(&self) -> f64 {
+        self.kinetic_energy + self.potential_energy
+    }
+
+    pub fn drift_since(&self, baseline: &Self) -> Drift {
+        Drift {
+            energy: relative(self.total_energy() - baseline.total_energy(),
+                             baseline.total_energy()),
+            momentum: relative((self.momentum.clone() - &baseline.momentum).magnitude(),
+                               baseline.momentum.magnitude()),
+            angular_momentum: relative((self.angular_momentum.clone() - &baseline.angular_momentum).magnitude(),
+                                       baseline.angular_momentum.magnitude()),
+        }
+    }
+}
+
+fn relative(change: f64, baseline: f64) -> f64 {
+    if baseline == 0.0 {
+        change.abs()
+    } else {
+        (change / baseline).abs()
+    }
+}
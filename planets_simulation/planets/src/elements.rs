@@ -0,0 +1,159 @@
+use std::f64::consts::PI;
+
+use forces::{Vec3, Vector};
+
+// abaixo disso a orbita e tratada como circular e o periapsis fica em +x
+const CIRCULAR: f64 = 1.0e-10;
+
+/// Keplerian elements of a body relative to its primary, in the plane.
+///
+/// Angles are in radians and measured from `+x` towards `+y`. Since the
+/// window has `y` pointing down, that is clockwise on screen, and so is the
+/// direction of motion when `clockwise` is set; the default matches
+/// `Planet::orbit_velocity`.
+///
+/// Bound orbits have `0 <= eccentricity < 1` and a positive semi-major
+/// axis; hyperbolic ones have `eccentricity > 1` and a negative one.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Elements {
+    pub semi_major_axis: f64,
+    pub eccentricity: f64,
+    pub argument_of_periapsis: f64,
+    pub true_anomaly: f64,
+    pub clockwise: bool,
+}
+
+impl Elements {
+    /// Circular orbit of `radius` starting at angle `true_anomaly`.
+    pub fn circular(radius: f64, true_anomaly: f64) -> Self {
+        Self {
+            semi_major_axis: radius,
+            eccentricity: 0.0,
+            argument_of_periapsis: 0.0,
+            true_anomaly,
+            clockwise: false,
+        }
+    }
+
+    /// Elements of the relative state (`position`, `velocity`) of a body
+    /// around a primary, with `mu = G * (M + m)`.
+    pub fn from_state(position: &Vector, velocity: &Vector, mu: f64) -> Self {
+        let distance = position.magnitude();
+        let speed_squared = velocity.dot_product(velocity);
+        let angular_momentum = position.cross(velocity);
+
+        let energy = speed_squared / 2.0 - mu / distance;
+        let semi_major_axis = -mu / (2.0 * energy);
+
+        // vetor de excentricidade, aponta para o periapsis
+        let radial = position.dot_product(velocity);
+        let eccentricity = &(&(position * (speed_squared - mu / distance)) - &(velocity * radial)) * (1.0 / mu);
+
+        // y para baixo: momento angular negativo e anti-horario na tela
+        let clockwise = angular_momentum > 0.0;
+        let sign = if clockwise { 1.0 } else { -1.0 };
+
+        let argument_of_periapsis = if eccentricity.magnitude() < CIRCULAR {
+            0.0
+        } else {
+            eccentricity.y.atan2(eccentricity.x)
+        };
+
+        let angle = position.y.atan2(position.x);
+
+        Self {
+            semi_major_axis,
+            eccentricity: eccentricity.magnitude(),
+            argument_of_periapsis: wrap(argument_of_periapsis),
+            true_anomaly: wrap(sign * (angle - argument_of_periapsis)),
+            clockwise,
+        }
+    }
+
+    /// Relative position and velocity for these elements.
+    pub fn to_state(&self, mu: f64) -> (Vector, Vector) {
+        let e = self.eccentricity;
+        let nu = self.true_anomaly;
+        let sign = if self.clockwise { 1.0 } else { -1.0 };
+
+        let semi_latus_rectum = self.semi_major_axis * (1.0 - e * e);
+        let distance = semi_latus_rectum / (1.0 + e * nu.cos());
+        let angle = self.argument_of_periapsis + sign * nu;
+
+        let radial = Vector::new(angle.cos(), angle.sin());
+        let tangential = Vector::new(-angle.sin(), angle.cos());
+
+        let scale = (mu / semi_latus_rectum).sqrt();
+        let radial_speed = scale * e * nu.sin();
+        let tangential_speed = scale * (1.0 + e * nu.cos());
+
+        let position = &radial * distance;
+        let velocity = &(&radial * radial_speed) + &(&tangential * (sign * tangential_speed));
+
+        (position, velocity)
+    }
+
+    /// Relative position and velocity in space, with the orbit plane
+    /// tilted by `orientation`.
+    pub fn to_state_in_space(&self, orientation: &Orientation, mu: f64) -> (Vec3<f64>, Vec3<f64>) {
+        let (position, velocity) = self.to_state(mu);
+        let sense = if self.clockwise { 1.0 } else { -1.0 };
+
+        (orientation.apply(position, sense), orientation.apply(velocity, sense))
+    }
+
+    pub fn is_bound(&self) -> bool {
+        self.eccentricity < 1.0 && self.semi_major_axis > 0.0
+    }
+
+    /// Orbital period, `None` for orbits that escape.
+    pub fn period(&self, mu: f64) -> Option<f64> {
+        if self.is_bound() {
+            Some(2.0 * PI * (self.semi_major_axis.powi(3) / mu).sqrt())
+        } else {
+            None
+        }
+    }
+
+    /// Closest distance to the primary.
+    pub fn periapsis(&self) -> f64 {
+        self.semi_major_axis * (1.0 - self.eccentricity)
+    }
+
+    /// Farthest distance from the primary, `None` for orbits that escape.
+    pub fn apoapsis(&self) -> Option<f64> {
+        if self.is_bound() {
+            Some(self.semi_major_axis * (1.0 + self.eccentricity))
+        } else {
+            None
+        }
+    }
+}
+
+/// How an orbit plane sits in space, angles in radians.
+///
+/// The plane is tilted by `inclination` about the line of nodes, which
+/// starts along `+x` and is then turned by `longitude_of_ascending_node`
+/// about `+z`. At the ascending node the body crosses `z = 0` towards
+/// `+z`; orbits with an inclination above π/2 go the other way around.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Orientation {
+    pub inclination: f64,
+    pub longitude_of_ascending_node: f64,
+}
+
+impl Orientation {
+    // `sense` e +1 quando o angulo no plano cresce com o movimento
+    fn apply(&self, vector: Vector, sense: f64) -> Vec3<f64> {
+        let (x, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+
+        Vec3::from(vector)
+            .rotate(&x, sense * self.inclination)
+            .rotate(&z, self.longitude_of_ascending_node)
+    }
+}
+
+// angulo em [0, 2pi)
+fn wrap(angle: f64) -> f64 {
+    angle.rem_euclid(2.0 * PI)
+}
@@ -0,0 +1,160 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use forces::Vector;
+
+use crate::trail::TrailPoint;
+use crate::Planet;
+
+// identifica o formato binario no inicio do arquivo
+const MAGIC: &[u8; 4] = b"TRJ2";
+// formato anterior, sem o passo de cada ponto; ainda e lido
+const MAGIC_V1: &[u8; 4] = b"TRJ1";
+
+/// File format for exported trajectories.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// `step,time,x,y` with a header line, one row per recorded point.
+    #[default]
+    Csv,
+    /// `TRJ2`, the timestep as a little-endian `f64`, the number of points
+    /// as a little-endian `u64`, then for every point its step as a
+    /// little-endian `u64` and `x` and `y` as little-endian `f64`.
+    ///
+    /// Files from before points carried their step start with `TRJ1` and
+    /// store only `x` and `y`; point `i` was recorded after step `i + 1`.
+    Binary,
+}
+
+impl Format {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Csv => "csv",
+            Format::Binary => "bin",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "csv" => Ok(Format::Csv),
+            "binary" | "bin" => Ok(Format::Binary),
+            _ => Err(format!("unknown format `{}`, expected `csv` or `binary`", name)),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Format::Csv => write!(f, "csv"),
+            Format::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+/// Writes the trajectory of `planet` as CSV, with the time of each point
+/// as its step times `timestep`.
+///
+/// Only the points still in the trail are written; see `Planets::set_trails`
+/// to keep all of them.
+pub fn write_csv<W: Write>(planet: &Planet, timestep: f64, out: &mut W) -> io::Result<()> {
+    writeln!(out, "step,time,x,y")?;
+
+    for point in planet.trajectory() {
+        writeln!(out, "{},{},{},{}", point.step, point.step as f64 * timestep,
+                 point.position.x, point.position.y)?;
+    }
+
+    Ok(())
+}
+
+/// Writes the trajectory of `planet` in the `Format::Binary` layout.
+pub fn write_binary<W: Write>(planet: &Planet, timestep: f64, out: &mut W) -> io::Result<()> {
+    out.write_all(MAGIC)?;
+    out.write_all(&timestep.to_le_bytes())?;
+    out.write_all(&(planet.trajectory().len() as u64).to_le_bytes())?;
+
+    for point in planet.trajectory() {
+        out.write_all(&point.step.to_le_bytes())?;
+        out.write_all(&point.position.x.to_le_bytes())?;
+        out.write_all(&point.position.y.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+/// Reads back a trajectory written by `write_binary`, as the timestep and
+/// the points. `TRJ1` files are read too.
+pub fn read_binary<R: Read>(input: &mut R) -> io::Result<(f64, Vec<TrailPoint>)> {
+    let mut magic = [0; 4];
+    input.read_exact(&mut magic)?;
+
+    let stepped = match &magic {
+        MAGIC => true,
+        MAGIC_V1 => false,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "not a trajectory file")),
+    };
+
+    let timestep = read_f64(input)?;
+
+    let count = read_u64(input)?;
+    let mut points = vec![];
+
+    for i in 1..=count {
+        let step = if stepped { read_u64(input)? } else { i };
+        let x = read_f64(input)?;
+        let y = read_f64(input)?;
+        points.push(TrailPoint { step, position: Vector::new(x, y) });
+    }
+
+    Ok((timestep, points))
+}
+
+fn read_u64<R: Read>(input: &mut R) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_f64<R: Read>(input: &mut R) -> io::Result<f64> {
+    let mut bytes = [0; 8];
+    input.read_exact(&mut bytes)?;
+
+    Ok(f64::from_le_bytes(bytes))
+}
+
+/// A file name, without extension, for the body called `name`: anything
+/// but ASCII letters, digits, `-` and `_` becomes `_`, so names like
+/// `../sun` or `a/b` stay inside the export directory.
+pub fn file_name(name: &str) -> String {
+    let safe: String = name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+
+    if safe.is_empty() { "_".to_string() } else { safe }
+}
+
+/// Writes the trajectory of `planet` to `path` in `format`.
+pub fn export(planet: &Planet, timestep: f64, path: &Path, format: Format) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+
+    match format {
+        Format::Csv => write_csv(planet, timestep, &mut out)?,
+        Format::Binary => write_binary(planet, timestep, &mut out)?,
+    }
+
+    out.flush()
+}
+
+/// Reads a trajectory file written with `Format::Binary`.
+pub fn import_binary(path: &Path) -> io::Result<(f64, Vec<TrailPoint>)> {
+    read_binary(&mut BufReader::new(File::open(path)?))
+}
@@ -0,0 +1,99 @@
+use forces::Coordinates;
+
+use crate::barnes_hut;
+use crate::space::Space;
+
+/// Gravitational constant plus an optional softening length.
+///
+/// With softening `eps` the distance `r` in Newton's law becomes
+/// `sqrt(r² + eps²)`, so the pull between close bodies stays finite
+/// instead of blowing up.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gravity {
+    pub constant: f64,
+    pub softening: f64,
+}
+
+impl Gravity {
+    pub fn new(constant: f64) -> Self {
+        Self {
+            constant,
+            softening: 0.0,
+        }
+    }
+
+    pub fn with_softening(mut self, softening: f64) -> Self {
+        self.softening = softening;
+        self
+    }
+
+    /// Acceleration at `position` towards `mass` sitting at `source`.
+    pub fn pull<V: Coordinates>(&self, position: &V, source: &V, mass: f64) -> V {
+        let offset = source.clone() - position;
+        let squared = offset.dot_product(&offset);
+
+        if squared == 0.0 {
+            return V::zero();
+        }
+
+        offset * (self.constant * mass / self.cubed(squared))
+    }
+
+    /// Potential energy of two masses `distance` apart.
+    pub fn potential(&self, a: f64, b: f64, distance: f64) -> f64 {
+        let softened = (distance * distance + self.softening * self.softening).sqrt();
+
+        if softened == 0.0 {
+            return 0.0;
+        }
+
+        -self.constant * a * b / softened
+    }
+
+    // (r² + eps²)^(3/2)
+    fn cubed(&self, squared: f64) -> f64 {
+        (squared + self.softening * self.softening).powf(1.5)
+    }
+}
+
+/// How `Planets` sums the gravitational pull between bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Solver {
+    /// Exact pairwise summation, O(n²).
+    #[default]
+    Direct,
+    /// Barnes-Hut quadtree (an octree in space) with opening angle
+    /// `theta`; 0.5 is a common compromise and 0 degenerates into direct
+    /// summation.
+    BarnesHut { theta: f64 },
+}
+
+impl Solver {
+    pub fn accelerations<V: Space>(&self, positions: &[V], masses: &[f64], gravity: &Gravity) -> Vec<V> {
+        match *self {
+            Solver::Direct => direct(positions, masses, gravity),
+            Solver::BarnesHut { theta } => barnes_hut::accelerations(positions, masses, gravity, theta),
+        }
+    }
+}
+
+/// Exact pairwise gravitational accelerations for bodies at `positions`.
+///
+/// Every pair is visited once and the same interaction is applied to both
+/// bodies with opposite signs, so the total force is zero. Bodies sharing
+/// a position do not attract each other. Works in the plane and in space.
+pub fn direct<V: Coordinates>(positions: &[V], masses: &[f64], gravity: &Gravity) -> Vec<V> {
+    let mut accelerations = vec![V::zero(); positions.len()];
+
+    for i in 0..positions.len() {
+        for j in (i + 1)..positions.len() {
+            // forca por unidade de massa de i para j
+            let pull = gravity.pull(&positions[i], &positions[j], 1.0);
+
+            accelerations[i] += pull.clone() * masses[j];
+            accelerations[j] -= pull * masses[i];
+        }
+    }
+
+    accelerations
+}
@@ -0,0 +1,115 @@
+use forces::{Coordinates, Vector};
+
+pub use forces::{Rk4, Rk45};
+
+/// Maps the positions of every body to their accelerations.
+pub type Acceleration<'a, V = Vector> = dyn Fn(&[V]) -> Vec<V> + 'a;
+
+/// Advances a system of bodies by one timestep.
+///
+/// Every integrator here works in the plane and in space alike.
+pub trait Integrator<V: Coordinates = Vector> {
+    fn step(&mut self,
+            positions: &mut [V],
+            velocities: &mut [V],
+            dt: f64,
+            acceleration: &Acceleration<V>);
+}
+
+/// Semi-implicit Euler: the velocity is kicked first and then moves the
+/// body. With `dt = 1.0` this is what the simulation originally did.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Euler;
+
+impl<V: Coordinates> Integrator<V> for Euler {
+    fn step(&mut self,
+            positions: &mut [V],
+            velocities: &mut [V],
+            dt: f64,
+            acceleration: &Acceleration<V>)
+    {
+        let accelerations = acceleration(positions);
+
+        for i in 0..positions.len() {
+            velocities[i] += accelerations[i].clone() * dt;
+            positions[i] += velocities[i].clone() * dt;
+        }
+    }
+}
+
+/// Velocity Verlet, second order and symplectic.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct VelocityVerlet;
+
+impl<V: Coordinates> Integrator<V> for VelocityVerlet {
+    fn step(&mut self,
+            positions: &mut [V],
+            velocities: &mut [V],
+            dt: f64,
+            acceleration: &Acceleration<V>)
+    {
+        let before = acceleration(positions);
+
+        for i in 0..positions.len() {
+            let drift = velocities[i].clone() * dt + before[i].clone() * (0.5 * dt * dt);
+            positions[i] += drift;
+        }
+
+        let after = acceleration(positions);
+
+        for i in 0..positions.len() {
+            let kick = (before[i].clone() + &after[i]) * (0.5 * dt);
+            velocities[i] += kick;
+        }
+    }
+}
+
+/// Drift-kick-drift leapfrog: second order and symplectic like velocity
+/// Verlet, but with a single force evaluation per step.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Leapfrog;
+
+impl<V: Coordinates> Integrator<V> for Leapfrog {
+    fn step(&mut self,
+            positions: &mut [V],
+            velocities: &mut [V],
+            dt: f64,
+            acceleration: &Acceleration<V>)
+    {
+        for i in 0..positions.len() {
+            positions[i] += velocities[i].clone() * (0.5 * dt);
+        }
+
+        let accelerations = acceleration(positions);
+
+        for i in 0..positions.len() {
+            velocities[i] += accelerations[i].clone() * dt;
+            positions[i] += velocities[i].clone() * (0.5 * dt);
+        }
+    }
+}
+
+/// The fourth order Runge-Kutta shared with the pendulums.
+impl<V: Coordinates> Integrator<V> for Rk4 {
+    fn step(&mut self,
+            positions: &mut [V],
+            velocities: &mut [V],
+            dt: f64,
+            acceleration: &Acceleration<V>)
+    {
+        self.advance(0.0, positions, velocities, dt, &|_, x, _| acceleration(x));
+    }
+}
+
+/// The adaptive integrator shared with the pendulums; here the
+/// acceleration only depends on the positions.
+impl<V: Coordinates> Integrator<V> for Rk45 {
+    fn step(&mut self,
+            positions: &mut [V],
+            velocities: &mut [V],
+            dt: f64,
+            acceleration: &Acceleration<V>)
+    {
+        self.advance(0.0, positions, velocities, dt, &|_, x, _| acceleration(x));
+    }
+}
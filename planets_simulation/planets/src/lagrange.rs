@@ -0,0 +1,224 @@
+use forces::Vector;
+
+use crate::camera::RotatingFrame;
+use crate::Planet;
+
+// iteracoes de bissecao para L1, L2 e L3, bem alem da precisao de f64
+const BISECTIONS: usize = 200;
+
+/// Circular restricted three-body problem: a test particle moving under two
+/// massive bodies that circle their barycenter.
+///
+/// Built from the current state of the two bodies, assuming their orbit is
+/// circular at the current separation. The rotating frame has its origin at
+/// the barycenter and `+x` pointing from the primary to the secondary.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RestrictedThreeBody {
+    primary: Vector,
+    secondary: Vector,
+    primary_mass: f64,
+    secondary_mass: f64,
+    barycenter: Vector,
+    barycenter_velocity: Vector,
+    // sentido do movimento do secundario: +1 de +x para +y
+    sense: f64,
+    g: f64,
+}
+
+impl RestrictedThreeBody {
+    pub fn new(primary: &Planet, secondary: &Planet, g: f64) -> Self {
+        let total = primary.mass() + secondary.mass();
+        let barycenter = &(&(&primary.position * primary.mass()) +
+                           &(&secondary.position * secondary.mass())) * (1.0 / total);
+        let barycenter_velocity = &(&(&primary.velocity * primary.mass()) +
+                                    &(&secondary.velocity * secondary.mass())) * (1.0 / total);
+
+        let offset = &secondary.position - &primary.position;
+        let relative = &secondary.velocity - &primary.velocity;
+        let angular_momentum = offset.cross(&relative);
+
+        Self {
+            primary: primary.position.clone(),
+            secondary: secondary.position.clone(),
+            primary_mass: primary.mass(),
+            secondary_mass: secondary.mass(),
+            barycenter,
+            barycenter_velocity,
+            sense: if angular_momentum < 0.0 { -1.0 } else { 1.0 },
+            g,
+        }
+    }
+
+    /// `mu = m2 / (m1 + m2)`.
+    pub fn mass_ratio(&self) -> f64 {
+        self.secondary_mass / (self.primary_mass + self.secondary_mass)
+    }
+
+    pub fn separation(&self) -> f64 {
+        (&self.secondary - &self.primary).magnitude()
+    }
+
+    pub fn barycenter(&self) -> &Vector {
+        &self.barycenter
+    }
+
+    /// Angular velocity of the circular orbit, positive from `+x` towards
+    /// `+y`.
+    pub fn angular_velocity(&self) -> f64 {
+        let total = self.primary_mass + self.secondary_mass;
+
+        self.sense * (self.g * total / self.separation().powi(3)).sqrt()
+    }
+
+    /// The frame turning with the two bodies, for `Camera::set_frame`.
+    pub fn frame(&self, timestep: f64) -> RotatingFrame {
+        let axis = self.axis();
+
+        RotatingFrame {
+            origin: self.barycenter.clone(),
+            velocity: self.barycenter_velocity.clone(),
+            angle: axis.y.atan2(axis.x),
+            angular_velocity: self.angular_velocity(),
+            timestep,
+        }
+    }
+
+    /// L1 to L5. L1 lies between the bodies, L2 beyond the secondary, L3
+    /// beyond the primary, L4 leads the secondary along its orbit and L5
+    /// trails it.
+    pub fn lagrange_points(&self) -> [Vector; 5] {
+        let mu = self.mass_ratio();
+        let (primary, secondary) = (-mu, 1.0 - mu);
+
+        let l1 = bisect(|x| collinear(x, mu), primary, secondary);
+        let l2 = bisect(|x| collinear(x, mu), secondary, secondary + 2.0);
+        let l3 = bisect(|x| collinear(x, mu), primary - 2.0, primary);
+        let height = 3.0_f64.sqrt() / 2.0;
+
+        [(l1, 0.0), (l2, 0.0), (l3, 0.0), (0.5 - mu, height), (0.5 - mu, -height)]
+            .map(|(x, y)| self.normalized_to_world(x, y))
+    }
+
+    /// `Ω = ω²ρ²/2 + G m1 / r1 + G m2 / r2`, with `ρ` the distance to the
+    /// barycenter.
+    pub fn pseudo_potential(&self, point: &Vector) -> f64 {
+        let omega = self.angular_velocity();
+        let rho = (point - &self.barycenter).magnitude();
+
+        omega * omega * rho * rho / 2.0 +
+            self.g * self.primary_mass / (point - &self.primary).magnitude() +
+            self.g * self.secondary_mass / (point - &self.secondary).magnitude()
+    }
+
+    /// `C = 2Ω - v²` with `v` the velocity seen from the rotating frame;
+    /// constant along the path of a test particle.
+    pub fn jacobi_constant(&self, position: &Vector, velocity: &Vector) -> f64 {
+        let omega = self.angular_velocity();
+        let offset = position - &self.barycenter;
+
+        // velocidade no referencial girante: v - v_cm - ω × r
+        let spin = offset.perpendicular() * omega;
+        let rotating = &(velocity - &self.barycenter_velocity) - &spin;
+
+        2.0 * self.pseudo_potential(position) - rotating.dot_product(&rotating)
+    }
+
+    /// Zero-velocity curves `2Ω = jacobi` as line segments.
+    ///
+    /// The curves are traced on a `cells` x `cells` grid that turns with the
+    /// bodies and reaches `extent` separations from the barycenter; a test
+    /// particle with that Jacobi constant never enters the side where
+    /// `2Ω < jacobi`.
+    pub fn zero_velocity_curves(&self, jacobi: f64, extent: f64, cells: usize) -> Vec<(Vector, Vector)> {
+        let size = 2.0 * extent / cells as f64;
+        let corner = |i: usize, j: usize| {
+            let point = self.normalized_to_world(-extent + i as f64 * size, -extent + j as f64 * size);
+            // perto dos corpos Ω cresce sem limite
+            let value = (2.0 * self.pseudo_potential(&point) - jacobi).min(f64::MAX);
+
+            (point, value)
+        };
+
+        let values: Vec<Vec<(Vector, f64)>> = (0..=cells)
+            .map(|i| (0..=cells).map(|j| corner(i, j)).collect())
+            .collect();
+
+        let mut segments = vec![];
+
+        for i in 0..cells {
+            for j in 0..cells {
+                let square = [&values[i][j], &values[i + 1][j], &values[i + 1][j + 1], &values[i][j + 1]];
+                marching_square(square, &mut segments);
+            }
+        }
+
+        segments
+    }
+
+    // +x do referencial girante; com os corpos no mesmo ponto fica no +x do mundo
+    fn axis(&self) -> Vector {
+        self.primary.direction_or(&self.secondary, Vector::new(1.0, 0.0))
+    }
+
+    // coordenadas em unidades da separacao, no referencial girante
+    fn normalized_to_world(&self, x: f64, y: f64) -> Vector {
+        let distance = self.separation();
+        let axis = self.axis();
+        let normal = axis.perpendicular() * self.sense;
+
+        &self.barycenter + &(&(&axis * (x * distance)) + &(&normal * (y * distance)))
+    }
+}
+
+// aceleracao efetiva no eixo x do referencial girante (em unidades normalizadas)
+fn collinear(x: f64, mu: f64) -> f64 {
+    let (r1, r2) = (x + mu, x - 1.0 + mu);
+
+    x - (1.0 - mu) * r1 / r1.abs().powi(3) - mu * r2 / r2.abs().powi(3)
+}
+
+// raiz de uma funcao crescente entre `low` e `high`
+fn bisect<F: Fn(f64) -> f64>(f: F, mut low: f64, mut high: f64) -> f64 {
+    for _ in 0..BISECTIONS {
+        let middle = (low + high) / 2.0;
+
+        if f(middle) < 0.0 {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+// segmentos da curva de nivel zero dentro de uma celula; cantos em ordem
+// anti-horaria a partir de (i, j), arestas 0..3 a partir do canto 0
+fn marching_square(corners: [&(Vector, f64); 4], segments: &mut Vec<(Vector, Vector)>) {
+    let case = corners.iter()
+        .enumerate()
+        .fold(0, |case, (bit, (_, value))| case | ((*value > 0.0) as usize) << bit);
+
+    let pairs: &[(usize, usize)] = match case {
+        0 | 15 => &[],
+        1 | 14 => &[(3, 0)],
+        2 | 13 => &[(0, 1)],
+        3 | 12 => &[(3, 1)],
+        4 | 11 => &[(1, 2)],
+        6 | 9 => &[(0, 2)],
+        7 | 8 => &[(3, 2)],
+        5 => &[(3, 0), (1, 2)],
+        _ => &[(0, 1), (2, 3)],
+    };
+
+    let crossing = |edge: usize| {
+        let (a, b) = (corners[edge], corners[(edge + 1) % 4]);
+        let t = a.1 / (a.1 - b.1);
+
+        &a.0 + &(&(&b.0 - &a.0) * t)
+    };
+
+    for &(from, to) in pairs {
+        segments.push((crossing(from), crossing(to)));
+    }
+}
@@ -1,163 +1,473 @@
 extern crate forces;
 extern crate sdl2;
+extern crate serde;
+extern crate toml;
+
+pub mod barnes_hut;
+pub mod camera;
+pub mod collision;
+pub mod diagnostics;
+pub mod elements;
+pub mod export;
+pub mod gravity;
+pub mod integrator;
+pub mod lagrange;
+pub mod projection;
+pub mod scene;
+pub mod space;
+pub mod trail;
 
 use forces::*;
-
-use sdl2::render::WindowCanvas;
+use camera::Camera;
+use diagnostics::{Diagnostics, Drift};
+use elements::Elements;
+use collision::Collision;
+use gravity::{Gravity, Solver};
+use integrator::{Integrator, VelocityVerlet};
+use lagrange::RestrictedThreeBody;
+use space::Space;
+use trail::Trail;
+
+use sdl2::render::{BlendMode, WindowCanvas};
 use sdl2::pixels::Color;
 use std::f32::consts::PI;
 
+/// Identifies a planet inside a `Planets` system; never reused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlanetId(u64);
+
+/// A body in the plane, or in space with `V = Vec3<f64>` (see `space`).
 #[derive(Debug, PartialEq, Clone)]
-pub struct Planet {
-    pub position: Vector,
-    pub velocity: Vector,
+pub struct Planet<V = Vector> {
+    id: Option<PlanetId>,
+    pub position: V,
+    pub velocity: V,
     radius: f64,
     mass: f64,
     color: Color,
-    trajectory: Vec<Vector>,
+    trail_color: Color,
+    trajectory: Trail<V>,
 }
 
-impl Planet {
-    const G: f64 = 100.0;
-
+impl<V: Space> Planet<V> {
     pub fn new(
-        position: Vector,
-        velocity: Vector,
+        position: V,
+        velocity: V,
         radius: f64,
         mass: f64,
         color: Color,
     ) -> Self
     {
         Self {
+            id: None,
             position,
             velocity,
             radius,
             mass,
             color,
-            trajectory: vec![],
+            trail_color: color,
+            trajectory: Trail::default(),
         }
     }
 
-    pub fn update_position(&mut self) {
-        self.position = &self.position + &self.velocity;
+    /// Set once the planet is added to a `Planets` system.
+    pub fn id(&self) -> Option<PlanetId> {
+        self.id
+    }
+
+    /// Position and velocity are free of NaNs and infinities.
+    pub fn is_finite(&self) -> bool {
+        self.position.is_finite() && self.velocity.is_finite()
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.mass
+    }
+
+    pub fn radius(&self) -> f64 {
+        self.radius
+    }
 
-        self.trajectory.push(self.position.clone());
+    /// Positions recorded after each step.
+    pub fn trajectory(&self) -> &Trail<V> {
+        &self.trajectory
     }
 
+    pub fn trajectory_mut(&mut self) -> &mut Trail<V> {
+        &mut self.trajectory
+    }
+
+    /// Color of the newest part of the trail, the same as the planet unless
+    /// set; older parts fade out.
+    pub fn set_trail_color(&mut self, color: Color) {
+        self.trail_color = color;
+    }
+
+    pub fn trail_color(&self) -> Color {
+        self.trail_color
+    }
+}
+
+impl Planet {
+    pub const G: f64 = 100.0;
+
+    /// Sets the velocity of a circular orbit around `other`. Leaves it
+    /// untouched when both planets are at the same point.
     pub fn orbit_velocity(&mut self, other: &Self) {
+        let direction = match (&other.position - &self.position).try_normalize() {
+            Some(direction) => direction,
+            None => return,
+        };
         let distance = (&self.position - &other.position).magnitude();
         let velocity_magnitude = (Self::G * other.mass / distance).sqrt();
-        let direction = self.position.direction(&other.position);
-        let tangential_velocity = Vector::new(-direction.y, direction.x);
+        let tangential_velocity = direction.perpendicular();
 
         self.velocity = &tangential_velocity * velocity_magnitude;
     }
 
-    pub fn apply_force(&mut self, other: &Self) {
-        let distance = (&other.position - &self.position).magnitude();
+    /// Puts the planet on the orbit described by `elements` around
+    /// `primary`, under gravitational constant `g`.
+    pub fn place_in_orbit(&mut self, primary: &Self, elements: &Elements, g: f64) {
+        let (position, velocity) = elements.to_state(g * (primary.mass + self.mass));
+
+        self.position = &primary.position + &position;
+        self.velocity = &primary.velocity + &velocity;
+    }
+
+    /// Current orbital elements around `primary`.
+    pub fn elements(&self, primary: &Self, g: f64) -> Elements {
+        Elements::from_state(&(&self.position - &primary.position),
+                             &(&self.velocity - &primary.velocity),
+                             g * (primary.mass + self.mass))
+    }
+
+    pub fn draw_trajectory(&self,
+        renderer: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        camera: &Camera) {
+
+        let count = self.trajectory.len();
 
-        if distance == 0.0 {
+        if count < 2 {
             return;
         }
 
-        let magnitude = (Self::G * self.mass * other.mass) /
-                        (distance.powf(2.0));
+        renderer.set_blend_mode(BlendMode::Blend);
 
-        let direction = &self.position.direction(&other.position);
+        let color = self.trail_color;
+        let steps = self.trajectory.steps();
+        let mut points = self.trajectory.iter()
+            .map(|point| camera.to_screen_at(&point.position, steps - point.step));
+        let mut start = points.next().unwrap();
 
-        let force = direction * magnitude;
-        let acceleration = &force * (1.0 / self.mass);
-        self.velocity = &self.velocity + &acceleration;
-    }
+        // o segmento mais antigo quase some, o mais novo fica com o alfa da cor
+        for (i, end) in points.enumerate() {
+            let alpha = color.a as usize * (i + 1) / (count - 1);
 
-    pub fn draw_trajectory(&self, 
-        renderer: &mut sdl2::render::Canvas<sdl2::video::Window>) {
+            renderer.set_draw_color(Color::RGBA(color.r, color.g, color.b, alpha as u8));
+            renderer.draw_line(start, end).unwrap();
 
-        if self.trajectory.len() < 2 {
-            return;
+            start = end;
         }
 
-        for i in 1..self.trajectory.len() {
-            let start = &self.trajectory[i - 1];
-            let end = &self.trajectory[i];
+        renderer.set_blend_mode(BlendMode::None);
+    }
 
-            renderer.set_draw_color(Color::RGBA(255, 255, 255, 255));
-            renderer.draw_line((start.x as i32, start.y as i32),
-                               (end.x as i32, end.y as i32)).unwrap();
-        }
+    pub fn draw(&self, canvas: &mut WindowCanvas, camera: &Camera) {
+        // mesmo longe, continua visivel como um ponto
+        let radius = camera.scale(self.radius).max(1.0);
 
+        fill_circle(canvas, camera.to_screen(&self.position), radius, self.color);
     }
+}
 
-    pub fn draw(&self, canvas: &mut WindowCanvas) {
-        let num_segments = 360;
-        let angle_step = 2.0 * PI / num_segments as f32;
+// um NaN se espalha para todos os corpos em um passo, entao em debug
+// `Planets` confere antes (corpos editados de fora) e depois de integrar
+#[cfg(debug_assertions)]
+fn check_finite<V: Space>(planets: &[Planet<V>], stage: &str) {
+    let broken: Vec<String> = planets.iter()
+        .filter(|planet| !planet.is_finite())
+        .map(|planet| format!("{:?} at {:?} moving {:?}", planet.id.unwrap(), planet.position, planet.velocity))
+        .collect();
+
+    if !broken.is_empty() {
+        panic!("non-finite bodies {} the step: {}", stage, broken.join("; "));
+    }
+}
 
-        let pos_x = self.position.x as i32;
-        let pos_y = self.position.y as i32;
+// disco cheio desenhado com raios a partir do centro
+fn fill_circle(canvas: &mut WindowCanvas, (pos_x, pos_y): (i32, i32), radius: f64, color: Color) {
+    let num_segments = 360;
+    let angle_step = 2.0 * PI / num_segments as f32;
+    let radius = radius as f32;
 
-        let radius = self.radius as f32;
+    canvas.set_draw_color(color);
 
-        for i in 0..num_segments {
-            let angle = angle_step * i as f32;
-            let x = pos_x + (radius * angle.cos()) as i32;
-            let y = pos_y + (radius * angle.sin()) as i32;
+    for i in 0..num_segments {
+        let angle = angle_step * i as f32;
+        let x = pos_x + (radius * angle.cos()) as i32;
+        let y = pos_y + (radius * angle.sin()) as i32;
 
-            canvas.set_draw_color(self.color);
-            canvas.draw_line((pos_x, pos_y),
-                             (x, y)).unwrap();
-        }
+        canvas.draw_line((pos_x, pos_y),
+                         (x, y)).unwrap();
     }
 }
 
-pub struct Planets {
-    planets: Vec<Planet>,
+/// An N-body system in the plane, or in space with `V = Vec3<f64>`.
+pub struct Planets<V: Space = Vector> {
+    planets: Vec<Planet<V>>,
+    next_id: u64,
+    integrator: Box<dyn Integrator<V>>,
+    solver: Solver,
+    gravity: Gravity,
+    collision: Collision,
+    timestep: f64,
+    // aplicado a cada planeta adicionado
+    trail: (Option<usize>, f64),
+    // passos dados desde a criacao; os rastros contam a partir dele
+    steps: u64,
+    // (inicio, ultimo passo) quando o acompanhamento esta ligado
+    tracking: Option<(Diagnostics<V>, Diagnostics<V>)>,
 }
 
-impl Planets {
+impl<V: Space> Planets<V> {
     pub fn new() -> Self {
         Self {
             planets: vec![],
+            next_id: 0,
+            integrator: Box::new(VelocityVerlet),
+            solver: Solver::Direct,
+            gravity: Gravity::new(Planet::G),
+            collision: Collision::Ignore,
+            timestep: 1.0,
+            trail: (Some(Trail::DEFAULT_LENGTH), 0.0),
+            steps: 0,
+            tracking: None,
         }
     }
 
-    pub fn add_planet(&mut self, planet: Planet) {
+    pub fn add_planet(&mut self, mut planet: Planet<V>) -> PlanetId {
+        let id = PlanetId(self.next_id);
+        self.next_id += 1;
+
+        planet.id = Some(id);
+        planet.trajectory.set_length(self.trail.0);
+        planet.trajectory.set_spacing(self.trail.1);
+        planet.trajectory.start_at(self.steps);
         self.planets.push(planet);
+
+        id
     }
 
-    pub fn draw(&self, canvas: &mut WindowCanvas){
-        for planet in &self.planets {
-            planet.draw(canvas);
-        }
+    pub fn remove_planet(&mut self, id: PlanetId) -> Option<Planet<V>> {
+        let index = self.planets.iter().position(|planet| planet.id == Some(id))?;
+        let mut planet = self.planets.remove(index);
+        planet.id = None;
+
+        Some(planet)
+    }
+
+    pub fn get(&self, id: PlanetId) -> Option<&Planet<V>> {
+        self.planets.iter().find(|planet| planet.id == Some(id))
+    }
+
+    pub fn get_mut(&mut self, id: PlanetId) -> Option<&mut Planet<V>> {
+        self.planets.iter_mut().find(|planet| planet.id == Some(id))
+    }
+
+    pub fn planets(&self) -> &[Planet<V>] {
+        &self.planets
+    }
+
+    pub fn set_integrator<I: Integrator<V> + 'static>(&mut self, integrator: I) {
+        self.integrator = Box::new(integrator);
+    }
+
+    pub fn set_solver(&mut self, solver: Solver) {
+        self.solver = solver;
+    }
+
+    pub fn solver(&self) -> Solver {
+        self.solver
+    }
+
+    pub fn set_gravity(&mut self, gravity: Gravity) {
+        self.gravity = gravity;
+    }
+
+    pub fn gravity(&self) -> Gravity {
+        self.gravity
+    }
+
+    pub fn set_collision(&mut self, collision: Collision) {
+        self.collision = collision;
+    }
+
+    pub fn collision(&self) -> Collision {
+        self.collision
     }
 
-    pub fn apply_force_sun(&mut self, sun: &Planet) {
+    /// Simulated time covered by each call to `step`.
+    pub fn set_timestep(&mut self, timestep: f64) {
+        self.timestep = timestep;
+    }
+
+    pub fn timestep(&self) -> f64 {
+        self.timestep
+    }
+
+    /// Steps taken so far; trail points are numbered with it.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Trail length and spacing (see `Trail`) for every planet, current and
+    /// added later.
+    pub fn set_trails(&mut self, length: Option<usize>, spacing: f64) {
+        self.trail = (length, spacing);
+
         for planet in &mut self.planets {
-            planet.apply_force(sun);
+            planet.trajectory.set_length(length);
+            planet.trajectory.set_spacing(spacing);
         }
     }
 
-    pub fn apply_force_others(&mut self) {
-        let mut copy = self.planets.clone();
+    pub fn trails(&self) -> (Option<usize>, f64) {
+        self.trail
+    }
 
+    pub fn clear_trails(&mut self) {
         for planet in &mut self.planets {
-            for p in &mut copy {
-                if *planet != *p {
-                    planet.apply_force(&p);
-                }
+            planet.trajectory.clear();
+        }
+    }
+
+    pub fn diagnostics(&self) -> Diagnostics<V> {
+        Diagnostics::measure(&self.planets, &self.gravity)
+    }
+
+    /// Measures the system after every `step` and keeps the current state
+    /// as the baseline for `drift`. Costs an extra O(n²) pass per step.
+    pub fn set_tracking(&mut self, enabled: bool) {
+        self.tracking = if enabled {
+            let now = self.diagnostics();
+            Some((now.clone(), now))
+        } else {
+            None
+        };
+    }
+
+    /// Diagnostics measured after the last step, when tracking.
+    pub fn latest_diagnostics(&self) -> Option<&Diagnostics<V>> {
+        self.tracking.as_ref().map(|(_, latest)| latest)
+    }
+
+    /// Drift since tracking started.
+    pub fn drift(&self) -> Option<Drift> {
+        self.tracking.as_ref().map(|(baseline, latest)| latest.drift_since(baseline))
+    }
+
+    /// Gravitational acceleration of every planet, in `planets()` order.
+    pub fn accelerations(&self) -> Vec<V> {
+        let positions: Vec<V> = self.planets.iter()
+            .map(|planet| planet.position.clone())
+            .collect();
+
+        self.solver.accelerations(&positions, &self.masses(), &self.gravity)
+    }
+
+    /// Advances every planet by one timestep and records the new positions.
+    ///
+    /// All forces are evaluated from the same snapshot of positions, so the
+    /// result does not depend on the order the planets were added in.
+    /// Overlapping planets are then resolved according to `collision`.
+    pub fn step(&mut self) {
+        #[cfg(debug_assertions)]
+        check_finite(&self.planets, "before");
+
+        let masses = self.masses();
+        let (solver, gravity) = (self.solver, self.gravity);
+        let mut positions: Vec<V> = self.planets.iter()
+            .map(|planet| planet.position.clone())
+            .collect();
+        let mut velocities: Vec<V> = self.planets.iter()
+            .map(|planet| planet.velocity.clone())
+            .collect();
+
+        let acceleration = |positions: &[V]| solver.accelerations(positions, &masses, &gravity);
+
+        self.integrator.step(&mut positions, &mut velocities, self.timestep, &acceleration);
+        self.steps += 1;
+
+        for (planet, (position, velocity)) in self.planets.iter_mut()
+            .zip(positions.into_iter().zip(velocities))
+        {
+            planet.position = position;
+            planet.velocity = velocity;
+            planet.trajectory.push(planet.position.clone());
+        }
+
+        #[cfg(debug_assertions)]
+        check_finite(&self.planets, "after");
+
+        match self.collision {
+            Collision::Ignore => {},
+            Collision::Merge => collision::merge(&mut self.planets),
+            Collision::Bounce => collision::bounce(&mut self.planets),
+        }
+
+        if self.tracking.is_some() {
+            let now = self.diagnostics();
+
+            if let Some((_, latest)) = &mut self.tracking {
+                *latest = now;
             }
         }
     }
 
-    pub fn update_position(&mut self) {
-        for planet in &mut self.planets {
-            planet.update_position();
+    /// Advances `steps` timesteps without drawing anything.
+    pub fn run(&mut self, steps: usize) {
+        for _ in 0..steps {
+            self.step();
+        }
+    }
+
+    fn masses(&self) -> Vec<f64> {
+        self.planets.iter().map(|planet| planet.mass).collect()
+    }
+}
+
+impl Planets {
+    /// Orbital elements of `body` around `primary`.
+    pub fn elements(&self, body: PlanetId, primary: PlanetId) -> Option<Elements> {
+        let (body, primary) = (self.get(body)?, self.get(primary)?);
+
+        Some(body.elements(primary, self.gravity.constant))
+    }
+
+    /// Restricted three-body view of `primary` and `secondary`.
+    pub fn restricted(&self, primary: PlanetId, secondary: PlanetId) -> Option<RestrictedThreeBody> {
+        let (primary, secondary) = (self.get(primary)?, self.get(secondary)?);
+
+        Some(RestrictedThreeBody::new(primary, secondary, self.gravity.constant))
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas, camera: &Camera){
+        for planet in &self.planets {
+            planet.draw(canvas, camera);
         }
     }
 
     pub fn draw_trajectory(&self,
-        renderer: &mut sdl2::render::Canvas<sdl2::video::Window>) {
+        renderer: &mut sdl2::render::Canvas<sdl2::video::Window>,
+        camera: &Camera) {
         for planet in &self.planets {
-            planet.draw_trajectory(renderer);
+            planet.draw_trajectory(renderer, camera);
         }
     }
-}
\ No newline at end of file
+}
+
+impl<V: Space> Default for Planets<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
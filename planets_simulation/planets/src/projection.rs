@@ -0,0 +1,146 @@
+use std::f64::consts::FRAC_PI_2;
+
+use forces::Vec3;
+
+use crate::camera::Camera;
+
+// com perspectiva, pontos mais perto do olho que isso (em fracao de
+// `distance`) nao sao desenhados
+const NEAR: f64 = 0.01;
+
+/// How `Camera3` flattens space onto the window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Projection {
+    /// Parallel rays; sizes do not change with depth.
+    #[default]
+    Orthographic,
+    /// Rays meet at an eye `distance` units in front of the target, so
+    /// nearer things look bigger.
+    Perspective,
+}
+
+/// A point of space as seen by a `Camera3`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projected {
+    pub x: i32,
+    pub y: i32,
+    /// Distance behind the plane through the target facing the camera;
+    /// negative in front of it.
+    pub depth: f64,
+    /// Pixels per simulation unit at that depth.
+    pub scale: f64,
+}
+
+/// Maps space coordinates to window pixels, looking at `target` from a
+/// direction set by `yaw` and `pitch`.
+///
+/// With both angles at zero the camera looks down the `z` axis and a new
+/// camera over an 800x600 viewport shows the `xy` plane exactly like
+/// `Camera` does. `yaw` turns the view about `z` and `pitch` tilts it
+/// about the horizontal screen axis, up to edge-on at ±π/2.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Camera3 {
+    pub target: Vec3<f64>,
+    yaw: f64,
+    pitch: f64,
+    zoom: f64,
+    distance: f64,
+    projection: Projection,
+    viewport: (u32, u32),
+}
+
+impl Camera3 {
+    pub const DEFAULT_DISTANCE: f64 = 2000.0;
+
+    pub fn new(viewport: (u32, u32)) -> Self {
+        Self {
+            target: Vec3::new(viewport.0 as f64 / 2.0, viewport.1 as f64 / 2.0, 0.0),
+            yaw: 0.0,
+            pitch: 0.0,
+            zoom: 1.0,
+            distance: Self::DEFAULT_DISTANCE,
+            projection: Projection::Orthographic,
+            viewport,
+        }
+    }
+
+    pub fn projection(&self) -> Projection {
+        self.projection
+    }
+
+    pub fn set_projection(&mut self, projection: Projection) {
+        self.projection = projection;
+    }
+
+    pub fn yaw(&self) -> f64 {
+        self.yaw
+    }
+
+    pub fn pitch(&self) -> f64 {
+        self.pitch
+    }
+
+    /// Turns the view; the pitch stops at edge-on.
+    pub fn rotate(&mut self, yaw: f64, pitch: f64) {
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-FRAC_PI_2, FRAC_PI_2);
+    }
+
+    /// Pixels per simulation unit at the target.
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Multiplies the zoom by `factor`, within the limits of `Camera`.
+    pub fn zoom_by(&mut self, factor: f64) {
+        self.zoom = (self.zoom * factor).clamp(Camera::MIN_ZOOM, Camera::MAX_ZOOM);
+    }
+
+    /// Distance from the eye to the target, only used by the perspective
+    /// projection.
+    pub fn distance(&self) -> f64 {
+        self.distance
+    }
+
+    pub fn set_distance(&mut self, distance: f64) {
+        self.distance = distance;
+    }
+
+    pub fn viewport(&self) -> (u32, u32) {
+        self.viewport
+    }
+
+    pub fn set_viewport(&mut self, viewport: (u32, u32)) {
+        self.viewport = viewport;
+    }
+
+    /// Where `point` lands on the window, `None` when it is behind the eye.
+    pub fn project(&self, point: &Vec3<f64>) -> Option<Projected> {
+        let (x, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+
+        // x para a direita, y para baixo e z para dentro da tela
+        let view = (point - &self.target)
+            .rotate(&z, -self.yaw)
+            .rotate(&x, -self.pitch);
+
+        let scale = match self.projection {
+            Projection::Orthographic => self.zoom,
+            Projection::Perspective => {
+                let eye = self.distance + view.z;
+
+                if eye <= self.distance * NEAR {
+                    return None;
+                }
+
+                self.zoom * self.distance / eye
+            },
+        };
+
+        Some(Projected {
+            x: (self.viewport.0 as f64 / 2.0 + view.x * scale).round() as i32,
+            y: (self.viewport.1 as f64 / 2.0 + view.y * scale).round() as i32,
+            depth: view.z,
+            scale,
+        })
+    }
+}
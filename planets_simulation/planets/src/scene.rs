@@ -0,0 +1,602 @@
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use forces::{Vec3, Vector};
+use sdl2::pixels::Color;
+use serde::Deserialize;
+
+use crate::collision::Collision;
+use crate::elements::{Elements, Orientation};
+use crate::gravity::{Gravity, Solver};
+use crate::integrator::{Euler, Leapfrog, Rk4, Rk45, VelocityVerlet};
+use crate::trail::Trail;
+use crate::space::{Planets3, Space};
+use crate::{Planet, Planets};
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io(PathBuf, io::Error),
+    Parse(String),
+    Invalid {
+        field: String,
+        message: String,
+    },
+}
+
+impl SceneError {
+    fn invalid(field: String, message: String) -> Self {
+        SceneError::Invalid { field, message }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io(path, error) => write!(f, "could not read {}: {}", path.display(), error),
+            SceneError::Parse(message) => write!(f, "{}", message),
+            SceneError::Invalid { field, message } => write!(f, "{}: {}", field, message),
+        }
+    }
+}
+
+impl error::Error for SceneError {}
+
+/// An orbital system read from a TOML file.
+///
+/// ```toml
+/// gravity = 100.0
+/// collision = "merge"
+///
+/// [integrator]
+/// method = "rk45"
+/// timestep = 1.0
+///
+/// [trails]
+/// length = 500
+/// spacing = 2.0
+///
+/// [[bodies]]
+/// name = "sun"
+/// mass = 1.0
+/// radius = 50.0
+/// color = [255, 255, 0]
+/// position = [400.0, 300.0]
+///
+/// [[bodies]]
+/// name = "earth"
+/// mass = 1.0e-5
+/// radius = 10.0
+/// position = [300.0, 250.0]
+/// orbit = { around = "sun" }
+///
+/// [[bodies]]
+/// name = "comet"
+/// mass = 1.0e-9
+/// radius = 2.0
+/// orbit = { around = "sun", semi_major_axis = 250.0, eccentricity = 0.8 }
+/// ```
+///
+/// With `dimensions = 3` positions and velocities may have a `z`
+/// component and orbits given by their elements may be inclined; such
+/// scenes are built with `build_3d`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scene {
+    /// Gravitational constant.
+    #[serde(default = "default_gravity")]
+    pub gravity: f64,
+    /// 2 for the plane, 3 for space.
+    #[serde(default = "default_dimensions")]
+    pub dimensions: usize,
+    #[serde(default)]
+    pub softening: f64,
+    #[serde(default)]
+    pub collision: Collision,
+    #[serde(default)]
+    pub integrator: IntegratorSettings,
+    #[serde(default)]
+    pub solver: SolverSettings,
+    #[serde(default)]
+    pub trails: TrailSettings,
+    pub bodies: Vec<Body>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IntegratorSettings {
+    #[serde(default)]
+    pub method: Method,
+    #[serde(default = "default_timestep")]
+    pub timestep: f64,
+    /// Local error tolerance, only used by `rk45`.
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Method {
+    Euler,
+    #[default]
+    VelocityVerlet,
+    Leapfrog,
+    Rk4,
+    Rk45,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SolverSettings {
+    #[serde(default)]
+    pub method: SolverMethod,
+    /// Opening angle, only used by `barnes-hut`.
+    #[serde(default = "default_theta")]
+    pub theta: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SolverMethod {
+    #[default]
+    Direct,
+    BarnesHut,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TrailSettings {
+    /// Points kept per body, `0` keeps all of them.
+    #[serde(default = "default_trail_length")]
+    pub length: usize,
+    /// Minimum distance between kept points.
+    #[serde(default)]
+    pub spacing: f64,
+}
+
+impl TrailSettings {
+    fn length(&self) -> Option<usize> {
+        if self.length == 0 { None } else { Some(self.length) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Body {
+    pub name: String,
+    pub mass: f64,
+    pub radius: f64,
+    #[serde(default = "default_color")]
+    pub color: [u8; 3],
+    /// Defaults to `color`.
+    pub trail_color: Option<[u8; 3]>,
+    /// Required unless the orbit sets `semi_major_axis`. `[x, y]`, or
+    /// `[x, y, z]` in 3D scenes.
+    pub position: Option<Vec<f64>>,
+    /// Initial velocity; exclusive with `orbit`.
+    pub velocity: Option<Vec<f64>>,
+    pub orbit: Option<Orbit>,
+}
+
+/// Starts the body on an orbit around another body of the scene.
+///
+/// Without `semi_major_axis` the orbit is circular through the body's
+/// `position`, starting with a velocity parallel to the `xy` plane.
+/// Otherwise the position comes from the elements, with the angles in
+/// degrees (see `Elements` and `Orientation` for the conventions); only 3D
+/// scenes may incline the orbit.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Orbit {
+    pub around: String,
+    pub semi_major_axis: Option<f64>,
+    #[serde(default)]
+    pub eccentricity: f64,
+    #[serde(default)]
+    pub argument_of_periapsis: f64,
+    #[serde(default)]
+    pub true_anomaly: f64,
+    #[serde(default)]
+    pub clockwise: bool,
+    #[serde(default)]
+    pub inclination: f64,
+    #[serde(default)]
+    pub longitude_of_ascending_node: f64,
+}
+
+impl Orbit {
+    fn elements(&self) -> Option<Elements> {
+        self.semi_major_axis.map(|semi_major_axis| Elements {
+            semi_major_axis,
+            eccentricity: self.eccentricity,
+            argument_of_periapsis: self.argument_of_periapsis.to_radians(),
+            true_anomaly: self.true_anomaly.to_radians(),
+            clockwise: self.clockwise,
+        })
+    }
+
+    fn orientation(&self) -> Orientation {
+        Orientation {
+            inclination: self.inclination.to_radians(),
+            longitude_of_ascending_node: self.longitude_of_ascending_node.to_radians(),
+        }
+    }
+
+    fn is_inclined(&self) -> bool {
+        self.inclination != 0.0 || self.longitude_of_ascending_node != 0.0
+    }
+}
+
+fn default_gravity() -> f64 {
+    Planet::G
+}
+
+fn default_dimensions() -> usize {
+    2
+}
+
+fn default_timestep() -> f64 {
+    1.0
+}
+
+fn default_tolerance() -> f64 {
+    1.0e-9
+}
+
+fn default_theta() -> f64 {
+    0.5
+}
+
+fn default_trail_length() -> usize {
+    Trail::DEFAULT_LENGTH
+}
+
+fn default_color() -> [u8; 3] {
+    [255, 255, 255]
+}
+
+impl Default for IntegratorSettings {
+    fn default() -> Self {
+        Self {
+            method: Method::default(),
+            timestep: default_timestep(),
+            tolerance: default_tolerance(),
+        }
+    }
+}
+
+impl Default for SolverSettings {
+    fn default() -> Self {
+        Self {
+            method: SolverMethod::default(),
+            theta: default_theta(),
+        }
+    }
+}
+
+impl Default for TrailSettings {
+    fn default() -> Self {
+        Self {
+            length: default_trail_length(),
+            spacing: 0.0,
+        }
+    }
+}
+
+impl Scene {
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, SceneError> {
+        let path = path.as_ref();
+
+        let text = fs::read_to_string(path)
+            .map_err(|e| SceneError::Io(path.to_path_buf(), e))?;
+
+        let scene: Scene = toml::from_str(&text)
+            .map_err(|e| SceneError::Parse(format!("{}: {}", path.display(), e)))?;
+
+        scene.validate()?;
+
+        Ok(scene)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, SceneError> {
+        let scene: Scene = toml::from_str(text)
+            .map_err(|e| SceneError::Parse(e.to_string()))?;
+
+        scene.validate()?;
+
+        Ok(scene)
+    }
+
+    pub fn validate(&self) -> Result<(), SceneError> {
+        check_finite("gravity", self.gravity, false)?;
+        check_finite("softening", self.softening, false)?;
+        check_finite("integrator.timestep", self.integrator.timestep, true)?;
+        check_finite("integrator.tolerance", self.integrator.tolerance, true)?;
+        check_finite("solver.theta", self.solver.theta, false)?;
+        check_finite("trails.spacing", self.trails.spacing, false)?;
+
+        if self.dimensions != 2 && self.dimensions != 3 {
+            return Err(SceneError::invalid("dimensions".to_string(), "must be 2 or 3".to_string()));
+        }
+
+        if self.bodies.is_empty() {
+            return Err(SceneError::invalid("bodies".to_string(),
+                                           "at least one body is required".to_string()));
+        }
+
+        for (i, body) in self.bodies.iter().enumerate() {
+            let field = format!("bodies[{}]", i);
+
+            if body.name.is_empty() {
+                return Err(SceneError::invalid(format!("{}.name", field),
+                                               "must not be empty".to_string()));
+            }
+
+            if let Some(first) = self.bodies[..i].iter().position(|other| other.name == body.name) {
+                return Err(SceneError::invalid(format!("{}.name", field),
+                                               format!("`{}` is already used by bodies[{}]",
+                                                       body.name, first)));
+            }
+
+            check_finite(&format!("{}.mass", field), body.mass, true)?;
+            check_finite(&format!("{}.radius", field), body.radius, false)?;
+
+            for (name, value) in [("position", &body.position), ("velocity", &body.velocity)] {
+                let value = match value {
+                    Some(value) => value,
+                    None => continue,
+                };
+
+                let message = match value.len() {
+                    2 => None,
+                    3 if self.dimensions == 3 => None,
+                    3 => Some("has a `z` component, which needs `dimensions = 3`"),
+                    _ if self.dimensions == 3 => Some("must have 2 or 3 components"),
+                    _ => Some("must have 2 components"),
+                }
+                .or_else(|| (!value.iter().all(|v| v.is_finite())).then_some("must be finite"));
+
+                if let Some(message) = message {
+                    return Err(SceneError::invalid(format!("{}.{}", field, name), message.to_string()));
+                }
+            }
+
+            match &body.orbit {
+                Some(orbit) => self.check_orbit(i, orbit)?,
+                None if body.position.is_none() => {
+                    return Err(SceneError::invalid(format!("{}.position", field),
+                                                   "is required without an orbit".to_string()));
+                },
+                None => {},
+            }
+        }
+
+        // so da para calcular as posicoes depois de descartar ciclos
+        for (i, body) in self.bodies.iter().enumerate() {
+            if let Some(orbit) = &body.orbit {
+                let primary = self.index_of(&orbit.around).unwrap();
+                let (position, center) = (self.state(i).0, self.state(primary).0);
+
+                if position == center {
+                    return Err(SceneError::invalid(format!("bodies[{}].orbit", i),
+                                                   format!("`{}` sits on top of `{}`",
+                                                           body.name, orbit.around)));
+                }
+
+                // a velocidade da orbita circular fica paralela ao plano xy
+                if orbit.semi_major_axis.is_none() && position.xy() == center.xy() {
+                    return Err(SceneError::invalid(format!("bodies[{}].position", i),
+                                                   format!("must not be straight above or below `{}` \
+                                                            for a circular orbit", orbit.around)));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_orbit(&self, index: usize, orbit: &Orbit) -> Result<(), SceneError> {
+        let field = format!("bodies[{}].orbit", index);
+        let body = &self.bodies[index];
+
+        if body.velocity.is_some() {
+            return Err(SceneError::invalid(format!("bodies[{}]", index),
+                                           "set either `velocity` or `orbit`, not both".to_string()));
+        }
+
+        let primary = self.index_of(&orbit.around).ok_or_else(|| {
+            SceneError::invalid(format!("{}.around", field),
+                                format!("no body is called `{}`", orbit.around))
+        })?;
+
+        if primary == index {
+            return Err(SceneError::invalid(format!("{}.around", field),
+                                           "a body cannot orbit itself".to_string()));
+        }
+
+        match (orbit.semi_major_axis, &body.position) {
+            (Some(_), Some(_)) => {
+                return Err(SceneError::invalid(format!("bodies[{}]", index),
+                                               "set either `position` or `orbit.semi_major_axis`, not both"
+                                                   .to_string()));
+            },
+            (Some(semi_major_axis), None) => {
+                check_finite(&format!("{}.semi_major_axis", field), semi_major_axis, true)?;
+                check_finite(&format!("{}.eccentricity", field), orbit.eccentricity, false)?;
+
+                if orbit.eccentricity >= 1.0 {
+                    return Err(SceneError::invalid(format!("{}.eccentricity", field),
+                                                   "must be below 1 for a closed orbit".to_string()));
+                }
+
+                for (name, angle) in [("argument_of_periapsis", orbit.argument_of_periapsis),
+                                      ("true_anomaly", orbit.true_anomaly),
+                                      ("inclination", orbit.inclination),
+                                      ("longitude_of_ascending_node", orbit.longitude_of_ascending_node)] {
+                    if !angle.is_finite() {
+                        return Err(SceneError::invalid(format!("{}.{}", field, name),
+                                                       "must be finite".to_string()));
+                    }
+                }
+
+                if orbit.is_inclined() && self.dimensions != 3 {
+                    return Err(SceneError::invalid(format!("{}.inclination", field),
+                                                   "an inclined orbit needs `dimensions = 3`".to_string()));
+                }
+            },
+            (None, Some(_)) => {
+                if orbit.eccentricity != 0.0 || orbit.argument_of_periapsis != 0.0 ||
+                   orbit.true_anomaly != 0.0 || orbit.is_inclined() {
+                    return Err(SceneError::invalid(format!("{}.semi_major_axis", field),
+                                                   "is required to set the other elements".to_string()));
+                }
+            },
+            (None, None) => {
+                return Err(SceneError::invalid(format!("bodies[{}].position", index),
+                                               "is required unless `orbit.semi_major_axis` is set"
+                                                   .to_string()));
+            },
+        }
+
+        // seguir a cadeia de `around` tem que terminar em algum corpo livre
+        let mut current = primary;
+
+        for _ in 0..self.bodies.len() {
+            // um nome desconhecido e reportado no proprio corpo
+            match self.bodies[current].orbit.as_ref().and_then(|next| self.index_of(&next.around)) {
+                Some(next) => current = next,
+                None => return Ok(()),
+            }
+
+            if current == index {
+                break;
+            }
+        }
+
+        Err(SceneError::invalid(format!("{}.around", field),
+                                format!("`{}` orbits around itself through other bodies", body.name)))
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.bodies.iter().position(|body| body.name == name)
+    }
+
+    /// Builds the system. Planets are added in `bodies` order, so
+    /// `planets()[i]` is `bodies[i]`. A 3D scene is flattened onto the
+    /// `xy` plane; see `build_3d`.
+    ///
+    /// The scene is validated first, since its fields may have been changed
+    /// after loading.
+    pub fn build(&self) -> Result<Planets, SceneError> {
+        self.build_in(|vector| vector.xy())
+    }
+
+    /// Builds the system in space, in `bodies` order and validated like
+    /// `build`. A 2D scene lies on the `z = 0` plane.
+    pub fn build_3d(&self) -> Result<Planets3, SceneError> {
+        self.build_in(|vector| vector)
+    }
+
+    // os estados sao calculados em 3D e `project` os leva para `V`
+    fn build_in<V: Space>(&self, project: impl Fn(Vec3<f64>) -> V) -> Result<Planets<V>, SceneError> {
+        self.validate()?;
+
+        let gravity = Gravity::new(self.gravity).with_softening(self.softening);
+        let mut planets = Planets::new();
+
+        planets.set_gravity(gravity);
+        planets.set_collision(self.collision);
+        planets.set_timestep(self.integrator.timestep);
+
+        match self.integrator.method {
+            Method::Euler => planets.set_integrator(Euler),
+            Method::VelocityVerlet => planets.set_integrator(VelocityVerlet),
+            Method::Leapfrog => planets.set_integrator(Leapfrog),
+            Method::Rk4 => planets.set_integrator(Rk4),
+            Method::Rk45 => planets.set_integrator(Rk45::new(self.integrator.tolerance)),
+        }
+
+        planets.set_solver(match self.solver.method {
+            SolverMethod::Direct => Solver::Direct,
+            SolverMethod::BarnesHut => Solver::BarnesHut { theta: self.solver.theta },
+        });
+
+        planets.set_trails(self.trails.length(), self.trails.spacing);
+
+        for (i, body) in self.bodies.iter().enumerate() {
+            let [r, g, b] = body.color;
+            let (position, velocity) = self.state(i);
+
+            let mut planet = Planet::new(project(position), project(velocity), body.radius, body.mass,
+                                         Color::RGB(r, g, b));
+
+            if let Some([r, g, b]) = body.trail_color {
+                planet.set_trail_color(Color::RGB(r, g, b));
+            }
+
+            planets.add_planet(planet);
+        }
+
+        Ok(planets)
+    }
+
+    // posicao e velocidade iniciais, relativas ao corpo central quando em orbita
+    fn state(&self, index: usize) -> (Vec3<f64>, Vec3<f64>) {
+        let body = &self.bodies[index];
+
+        let orbit = match &body.orbit {
+            Some(orbit) => orbit,
+            None => {
+                let velocity = body.velocity.as_deref().map(vector).unwrap_or_default();
+                return (body.position.as_deref().map(vector).unwrap_or_default(), velocity);
+            },
+        };
+
+        let primary = self.index_of(&orbit.around).unwrap();
+        let (center, center_velocity) = self.state(primary);
+
+        let (position, velocity) = match orbit.elements() {
+            Some(elements) => {
+                let mu = self.gravity * (self.bodies[primary].mass + body.mass);
+                let (position, velocity) = elements.to_state_in_space(&orbit.orientation(), mu);
+
+                (&center + &position, velocity)
+            },
+            None => {
+                // mesma orbita circular de `Planet::orbit_velocity`
+                let position = body.position.as_deref().map(vector).unwrap_or_default();
+                let offset = &center - &position;
+                let distance = offset.magnitude();
+
+                let speed = (self.gravity * self.bodies[primary].mass / distance).sqrt();
+                let sign = if orbit.clockwise { -1.0 } else { 1.0 };
+                let tangent = Vector::new(-offset.y, offset.x).normalize();
+
+                (position, Vec3::from(&tangent * (sign * speed)))
+            },
+        };
+
+        (position, &center_velocity + &velocity)
+    }
+}
+
+// `[x, y]` ou `[x, y, z]`, ja validados
+fn vector(components: &[f64]) -> Vec3<f64> {
+    Vec3::new(components[0], components[1], components.get(2).copied().unwrap_or(0.0))
+}
+
+fn check_finite(field: &str, value: f64, positive: bool) -> Result<(), SceneError> {
+    let message = if !value.is_finite() {
+        "must be finite"
+    } else if positive && value <= 0.0 {
+        "must be greater than zero"
+    } else if value < 0.0 {
+        "must not be negative"
+    } else {
+        return Ok(());
+    };
+
+    Err(SceneError::invalid(field.to_string(), message.to_string()))
+}
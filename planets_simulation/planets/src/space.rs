@@ -0,0 +1,152 @@
+use forces::{Coordinates, Vec3, Vector};
+use sdl2::pixels::Color;
+use sdl2::render::{BlendMode, WindowCanvas};
+
+use crate::elements::{Elements, Orientation};
+use crate::projection::Camera3;
+use crate::{fill_circle, Planet, Planets};
+
+/// The plane or space: what `Planets` needs from its coordinates besides
+/// `Coordinates`.
+pub trait Space: Coordinates {
+    /// Angular momentum: a scalar in the plane, a vector in space.
+    type Angular: Coordinates;
+
+    /// 2 or 3; each Barnes-Hut node splits in `2^AXES`.
+    const AXES: usize;
+
+    fn axis(&self, index: usize) -> f64;
+    fn from_axes(axes: &[f64]) -> Self;
+
+    /// Angular momentum per unit mass of a body here moving at `velocity`.
+    fn moment(&self, velocity: &Self) -> Self::Angular;
+
+    fn is_finite(&self) -> bool;
+}
+
+impl Space for Vector {
+    type Angular = f64;
+
+    const AXES: usize = 2;
+
+    fn axis(&self, index: usize) -> f64 {
+        [self.x, self.y][index]
+    }
+
+    fn from_axes(axes: &[f64]) -> Self {
+        Vector::new(axes[0], axes[1])
+    }
+
+    fn moment(&self, velocity: &Self) -> f64 {
+        self.cross(velocity)
+    }
+
+    fn is_finite(&self) -> bool {
+        Vector::is_finite(self)
+    }
+}
+
+impl Space for Vec3<f64> {
+    type Angular = Self;
+
+    const AXES: usize = 3;
+
+    fn axis(&self, index: usize) -> f64 {
+        [self.x, self.y, self.z][index]
+    }
+
+    fn from_axes(axes: &[f64]) -> Self {
+        Vec3::new(axes[0], axes[1], axes[2])
+    }
+
+    fn moment(&self, velocity: &Self) -> Self {
+        self.cross(velocity)
+    }
+
+    fn is_finite(&self) -> bool {
+        Vec3::is_finite(self)
+    }
+}
+
+/// A planet free to move in space.
+pub type Planet3 = Planet<Vec3<f64>>;
+
+/// An N-body system in space, drawn through a `Camera3`.
+pub type Planets3 = Planets<Vec3<f64>>;
+
+impl Planet3 {
+    /// Puts the planet on the orbit described by `elements`, in the plane
+    /// given by `orientation`, around `primary`.
+    pub fn place_in_orbit(&mut self, primary: &Self, elements: &Elements, orientation: &Orientation, g: f64) {
+        let (position, velocity) = elements.to_state_in_space(orientation, g * (primary.mass + self.mass));
+
+        self.position = &primary.position + &position;
+        self.velocity = &primary.velocity + &velocity;
+    }
+
+    pub fn draw_trajectory(&self, canvas: &mut WindowCanvas, camera: &Camera3) {
+        let count = self.trajectory.len();
+
+        if count < 2 {
+            return;
+        }
+
+        canvas.set_blend_mode(BlendMode::Blend);
+
+        let color = self.trail_color;
+        let points: Vec<_> = self.trajectory.iter()
+            .map(|point| camera.project(&point.position))
+            .collect();
+
+        // mesmo desbotamento do rastro no plano; trechos atras do olho somem
+        for (i, pair) in points.windows(2).enumerate() {
+            if let (Some(start), Some(end)) = (pair[0], pair[1]) {
+                let alpha = color.a as usize * (i + 1) / (count - 1);
+
+                canvas.set_draw_color(Color::RGBA(color.r, color.g, color.b, alpha as u8));
+                canvas.draw_line((start.x, start.y), (end.x, end.y)).unwrap();
+            }
+        }
+
+        canvas.set_blend_mode(BlendMode::None);
+    }
+
+    pub fn draw(&self, canvas: &mut WindowCanvas, camera: &Camera3) {
+        if let Some(projected) = camera.project(&self.position) {
+            let radius = (self.radius * projected.scale).max(1.0);
+
+            fill_circle(canvas, (projected.x, projected.y), radius, self.color);
+        }
+    }
+}
+
+impl From<Planet> for Planet3 {
+    /// The same planet on the `z = 0` plane, without its id or trail.
+    fn from(planet: Planet) -> Self {
+        let mut planet3 = Self::new(planet.position.into(), planet.velocity.into(),
+                                    planet.radius, planet.mass, planet.color);
+        planet3.trail_color = planet.trail_color;
+
+        planet3
+    }
+}
+
+impl Planets3 {
+    /// Draws the farthest planets first so nearer ones cover them.
+    pub fn draw(&self, canvas: &mut WindowCanvas, camera: &Camera3) {
+        let mut planets: Vec<(f64, &Planet3)> = self.planets.iter()
+            .filter_map(|planet| Some((camera.project(&planet.position)?.depth, planet)))
+            .collect();
+        planets.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        for (_, planet) in planets {
+            planet.draw(canvas, camera);
+        }
+    }
+
+    pub fn draw_trajectory(&self, canvas: &mut WindowCanvas, camera: &Camera3) {
+        for planet in &self.planets {
+            planet.draw_trajectory(canvas, camera);
+        }
+    }
+}
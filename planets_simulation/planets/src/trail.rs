@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use std::collections::vec_deque;
+
+use forces::{Coordinates, Vector};
+
+/// A recorded position and the step of the system, counted from 1, it was
+/// recorded after.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrailPoint<V = Vector> {
+    pub step: u64,
+    pub position: V,
+}
+
+/// The recent path of a planet.
+///
+/// Keeps at most `length` points, dropping the oldest, and skips positions
+/// closer than `spacing` to the last kept one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trail<V = Vector> {
+    points: VecDeque<TrailPoint<V>>,
+    length: Option<usize>,
+    spacing: f64,
+    steps: u64,
+}
+
+impl Trail {
+    pub const DEFAULT_LENGTH: usize = 2000;
+}
+
+impl<V: Coordinates> Trail<V> {
+    /// `length: None` keeps every point.
+    pub fn new(length: Option<usize>, spacing: f64) -> Self {
+        Self {
+            points: VecDeque::new(),
+            length,
+            spacing,
+            steps: 0,
+        }
+    }
+
+    pub fn length(&self) -> Option<usize> {
+        self.length
+    }
+
+    pub fn set_length(&mut self, length: Option<usize>) {
+        self.length = length;
+        self.trim();
+    }
+
+    pub fn spacing(&self) -> f64 {
+        self.spacing
+    }
+
+    pub fn set_spacing(&mut self, spacing: f64) {
+        self.spacing = spacing;
+    }
+
+    /// Forgets the points and counts steps from `step`, the step of the
+    /// system the trail joins at, so its points line up with the other
+    /// bodies' points.
+    pub fn start_at(&mut self, step: u64) {
+        self.points.clear();
+        self.steps = step;
+    }
+
+    /// Counts a step and records `position` unless it is too close to the
+    /// last point.
+    pub fn push(&mut self, position: V) {
+        self.steps += 1;
+
+        if let Some(last) = self.points.back() {
+            if (position.clone() - &last.position).magnitude() < self.spacing {
+                return;
+            }
+        }
+
+        self.points.push_back(TrailPoint { step: self.steps, position });
+        self.trim();
+    }
+
+    /// Steps counted so far, the `step` the next point would get minus one.
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// Forgets the points; steps keep counting.
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Oldest point first.
+    pub fn iter(&self) -> vec_deque::Iter<'_, TrailPoint<V>> {
+        self.points.iter()
+    }
+
+    pub fn last(&self) -> Option<&TrailPoint<V>> {
+        self.points.back()
+    }
+
+    fn trim(&mut self) {
+        if let Some(length) = self.length {
+            while self.points.len() > length {
+                self.points.pop_front();
+            }
+        }
+    }
+}
+
+impl<V: Coordinates> Default for Trail<V> {
+    fn default() -> Self {
+        Self::new(Some(Trail::DEFAULT_LENGTH), 0.0)
+    }
+}
+
+impl<'a, V> IntoIterator for &'a Trail<V> {
+    type Item = &'a TrailPoint<V>;
+    type IntoIter = vec_deque::Iter<'a, TrailPoint<V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.points.iter()
+    }
+}
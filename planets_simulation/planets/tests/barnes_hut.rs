@@ -0,0 +1,131 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::barnes_hut::Quadtree;
+use planets::gravity::{Gravity, Solver};
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+const G: Gravity = Gravity { constant: 100.0, softening: 0.0 };
+
+// gerador congruencial: os mesmos corpos em toda execucao
+fn bodies(count: usize) -> (Vec<Vector>, Vec<f64>) {
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = move || {
+        state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1_442_695_040_888_963_407);
+        (state >> 11) as f64 / (1u64 << 53) as f64
+    };
+
+    (0..count)
+        .map(|_| {
+            let radius = 1000.0 * next().sqrt();
+            let angle = 2.0 * std::f64::consts::PI * next();
+            let mass = 1.0e-3 + next();
+
+            (Vector::new(radius * angle.cos(), radius * angle.sin()), mass)
+        })
+        .unzip()
+}
+
+fn errors(theta: f64, count: usize) -> Vec<f64> {
+    let (positions, masses) = bodies(count);
+    let exact = Solver::Direct.accelerations(&positions, &masses, &G);
+    let approximate = Solver::BarnesHut { theta }.accelerations(&positions, &masses, &G);
+
+    approximate.iter()
+        .zip(&exact)
+        .map(|(a, e)| (a - e).magnitude() / e.magnitude())
+        .collect()
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[test]
+fn zero_theta_matches_direct_summation() {
+    let worst = errors(0.0, 500).into_iter().fold(0.0, f64::max);
+
+    assert!(worst < 1.0e-12, "{:e}", worst);
+}
+
+#[test]
+fn half_theta_is_within_a_few_percent() {
+    let errors = errors(0.5, 2_000);
+
+    assert!(mean(&errors) < 0.02, "mean {:e}", mean(&errors));
+}
+
+#[test]
+fn worst_error_is_small_next_to_typical_force() {
+    // o erro relativo por corpo explode onde as forcas quase se cancelam,
+    // entao o pior caso e comparado com a aceleracao media
+    let (positions, masses) = bodies(2_000);
+    let exact = Solver::Direct.accelerations(&positions, &masses, &G);
+    let approximate = Solver::BarnesHut { theta: 0.5 }.accelerations(&positions, &masses, &G);
+
+    let typical = mean(&exact.iter().map(Vector::magnitude).collect::<Vec<_>>());
+    let worst = approximate.iter()
+        .zip(&exact)
+        .map(|(a, e)| (a - e).magnitude())
+        .fold(0.0, f64::max);
+
+    assert!(worst < 0.1 * typical, "worst {:e} typical {:e}", worst, typical);
+}
+
+#[test]
+fn larger_theta_is_less_accurate() {
+    let fine = mean(&errors(0.3, 1_000));
+    let coarse = mean(&errors(1.0, 1_000));
+
+    assert!(fine < coarse, "{:e} {:e}", fine, coarse);
+}
+
+#[test]
+fn tree_tracks_total_mass_and_center() {
+    let positions = vec![Vector::new(-10.0, 0.0), Vector::new(10.0, 0.0), Vector::new(10.0, 30.0)];
+    let masses = vec![2.0, 1.0, 1.0];
+    let tree = Quadtree::new(&positions, &masses);
+
+    assert_eq!(tree.mass(), 4.0);
+    assert!((tree.center_of_mass() - &Vector::new(0.0, 7.5)).magnitude() < 1.0e-12);
+}
+
+#[test]
+fn handles_coincident_and_missing_bodies() {
+    assert!(Solver::BarnesHut { theta: 0.5 }.accelerations::<Vector>(&[], &[], &G).is_empty());
+
+    let positions = vec![Vector::new(1.0, 1.0); 3];
+    let accelerations = Solver::BarnesHut { theta: 0.5 }.accelerations(&positions, &[1.0; 3], &G);
+
+    assert!(accelerations.iter().all(|a| *a == Vector::new(0.0, 0.0)));
+}
+
+#[test]
+fn planets_can_step_with_barnes_hut() {
+    let (positions, masses) = bodies(200);
+    let mut direct = Planets::new();
+
+    for (position, mass) in positions.into_iter().zip(masses) {
+        direct.add_planet(Planet::new(position, Vector::new(0.0, 0.0), 1.0, mass, Color::RGB(255, 255, 255)));
+    }
+
+    let mut tree = Planets::new();
+    tree.set_solver(Solver::BarnesHut { theta: 0.3 });
+
+    for planet in direct.planets() {
+        tree.add_planet(Planet::new(planet.position.clone(), Vector::new(0.0, 0.0), 1.0, planet.mass(),
+                                    Color::RGB(255, 255, 255)));
+    }
+
+    for _ in 0..10 {
+        direct.step();
+        tree.step();
+    }
+
+    for (a, b) in direct.planets().iter().zip(tree.planets()) {
+        assert!((&a.position - &b.position).magnitude() < 1.0, "{:?} {:?}", a.position, b.position);
+    }
+}
@@ -0,0 +1,90 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::camera::Camera;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn close(a: &Vector, b: &Vector) -> bool {
+    (a - b).magnitude() < 1.0e-9
+}
+
+fn planet(x: f64, y: f64, radius: f64) -> Planet {
+    Planet::new(Vector::new(x, y), Vector::new(0.0, 0.0), radius, 1.0, Color::RGB(255, 255, 255))
+}
+
+#[test]
+fn starts_as_identity() {
+    let camera = Camera::new((800, 600));
+
+    assert_eq!(camera.to_screen(&Vector::new(0.0, 0.0)), (0, 0));
+    assert_eq!(camera.to_screen(&Vector::new(123.0, 456.0)), (123, 456));
+    assert_eq!(camera.to_world(800, 600), Vector::new(800.0, 600.0));
+    assert_eq!(camera.scale(7.0), 7.0);
+}
+
+#[test]
+fn zoom_keeps_point_under_cursor() {
+    let mut camera = Camera::new((800, 600));
+    let before = camera.to_world(100, 500);
+
+    camera.zoom_at(2.5, 100, 500);
+
+    assert_eq!(camera.zoom(), 2.5);
+    assert!(close(&camera.to_world(100, 500), &before));
+    assert_eq!(camera.scale(2.0), 5.0);
+
+    camera.zoom_at(1.0e12, 0, 0);
+    assert_eq!(camera.zoom(), Camera::MAX_ZOOM);
+}
+
+#[test]
+fn pan_moves_view_with_drag() {
+    let mut camera = Camera::new((800, 600));
+    camera.zoom_at(2.0, 400, 300);
+
+    let grabbed = camera.to_world(300, 200);
+    camera.pan(50, -20);
+
+    assert!(close(&camera.to_world(350, 180), &grabbed));
+}
+
+#[test]
+fn follows_planet_until_it_is_gone() {
+    let mut planets = Planets::new();
+    let sun = planets.add_planet(planet(0.0, 0.0, 10.0));
+    let comet = planets.add_planet(planet(1000.0, -50.0, 2.0));
+
+    let mut camera = Camera::new((800, 600));
+    camera.follow(Some(comet));
+    camera.update(&planets);
+
+    assert_eq!(camera.to_screen(&Vector::new(1000.0, -50.0)), (400, 300));
+
+    // aproximar mantem o corpo no centro
+    camera.zoom_at(3.0, 10, 10);
+    assert_eq!(camera.to_screen(&Vector::new(1000.0, -50.0)), (400, 300));
+
+    planets.remove_planet(comet);
+    camera.update(&planets);
+    assert_eq!(camera.following(), None);
+
+    camera.follow(Some(sun));
+    camera.pan(1, 1);
+    assert_eq!(camera.following(), None);
+}
+
+#[test]
+fn picks_closest_planet_under_cursor() {
+    let mut planets = Planets::new();
+    let big = planets.add_planet(planet(100.0, 100.0, 20.0));
+    let small = planets.add_planet(planet(125.0, 100.0, 2.0));
+
+    let camera = Camera::new((800, 600));
+
+    assert_eq!(camera.pick(&planets, 105, 100), Some(big));
+    assert_eq!(camera.pick(&planets, 124, 100), Some(small));
+    assert_eq!(camera.pick(&planets, 300, 300), None);
+}
@@ -0,0 +1,157 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::collision::Collision;
+use planets::gravity::Gravity;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn body(x: f64, vx: f64, radius: f64, mass: f64) -> Planet {
+    Planet::new(Vector::new(x, 0.0), Vector::new(vx, 0.0), radius, mass, Color::RGB(255, 255, 255))
+}
+
+// sem gravidade, para isolar a colisao
+fn system(collision: Collision, bodies: Vec<Planet>) -> Planets {
+    let mut planets = Planets::new();
+    planets.set_gravity(Gravity::new(0.0));
+    planets.set_collision(collision);
+
+    for planet in bodies {
+        planets.add_planet(planet);
+    }
+
+    planets
+}
+
+fn momentum(planets: &Planets) -> Vector {
+    planets.planets()
+        .iter()
+        .fold(Vector::new(0.0, 0.0), |sum, planet| &sum + &(&planet.velocity * planet.mass()))
+}
+
+#[test]
+fn ignore_lets_bodies_pass_through() {
+    let mut planets = system(Collision::Ignore, vec![body(0.0, 1.0, 5.0, 1.0), body(8.0, -1.0, 5.0, 1.0)]);
+
+    planets.step();
+
+    assert_eq!(planets.planets().len(), 2);
+    assert_eq!(planets.planets()[0].velocity, Vector::new(1.0, 0.0));
+}
+
+#[test]
+fn merge_conserves_mass_and_momentum() {
+    let mut planets = system(Collision::Merge, vec![body(0.0, 2.0, 4.0, 1.0), body(8.0, -1.0, 3.0, 3.0)]);
+    let heavy = planets.planets()[1].id();
+    let before = momentum(&planets);
+
+    planets.step();
+
+    assert_eq!(planets.planets().len(), 1);
+
+    let merged = &planets.planets()[0];
+    assert_eq!(merged.id(), heavy);
+    assert_eq!(merged.mass(), 4.0);
+    assert!((merged.radius() - 91.0_f64.cbrt()).abs() < 1.0e-12);
+    assert!((&momentum(&planets) - &before).magnitude() < 1.0e-12);
+    assert!((merged.position.x - (2.0 + 3.0 * 7.0) / 4.0).abs() < 1.0e-12);
+}
+
+#[test]
+fn merge_keeps_the_heavier_body_trail_color() {
+    let light = body(0.0, 0.0, 4.0, 1.0);
+    let mut heavy = body(5.0, 0.0, 4.0, 3.0);
+    heavy.set_trail_color(Color::RGB(10, 20, 30));
+
+    let mut planets = system(Collision::Merge, vec![light, heavy]);
+    planets.step();
+
+    assert_eq!(planets.planets().len(), 1);
+    assert_eq!(planets.planets()[0].trail_color(), Color::RGB(10, 20, 30));
+}
+
+#[test]
+fn merges_chain_until_nothing_overlaps() {
+    let big = body(0.0, 0.0, 10.0, 100.0);
+    let small = body(12.0, 0.0, 3.0, 1.0);
+
+    // nao toca nenhum dos dois, so o corpo que sai da fusao
+    let mut top = body(0.0, 0.0, 1.0, 1.0);
+    top.position = Vector::new(12.0 / 101.0, 11.08);
+
+    let mut planets = system(Collision::Merge, vec![top, big, small]);
+    planets.step();
+
+    assert_eq!(planets.planets().len(), 1);
+    assert_eq!(planets.planets()[0].mass(), 102.0);
+}
+
+#[test]
+fn equal_masses_swap_velocities_on_bounce() {
+    let mut planets = system(Collision::Bounce, vec![body(0.0, 1.0, 5.0, 2.0), body(9.0, -3.0, 5.0, 2.0)]);
+    let before = momentum(&planets);
+
+    planets.step();
+
+    let (a, b) = (&planets.planets()[0], &planets.planets()[1]);
+    assert_eq!(a.velocity, Vector::new(-3.0, 0.0));
+    assert_eq!(b.velocity, Vector::new(1.0, 0.0));
+    assert!((&b.position - &a.position).magnitude() >= 10.0 - 1.0e-12);
+    assert_eq!(momentum(&planets), before);
+}
+
+#[test]
+fn bounce_conserves_kinetic_energy() {
+    let mut a = body(0.0, 0.5, 4.0, 1.0);
+    a.velocity = Vector::new(0.5, 0.7);
+    let mut planets = system(Collision::Bounce, vec![a, body(6.0, -0.2, 3.0, 5.0)]);
+
+    let kinetic = |planets: &Planets| planets.diagnostics().kinetic_energy;
+    let before = kinetic(&planets);
+
+    planets.step();
+
+    assert!(planets.planets()[0].velocity.x < 0.0);
+    assert!((kinetic(&planets) - before).abs() < 1.0e-12);
+}
+
+#[test]
+fn separating_bodies_do_not_bounce_back() {
+    let mut planets = system(Collision::Bounce, vec![body(0.0, -1.0, 5.0, 1.0), body(8.0, 1.0, 5.0, 1.0)]);
+
+    planets.step();
+
+    assert_eq!(planets.planets()[0].velocity, Vector::new(-1.0, 0.0));
+    assert_eq!(planets.planets()[1].velocity, Vector::new(1.0, 0.0));
+}
+
+#[test]
+fn softening_keeps_close_pull_finite() {
+    let plain = Gravity::new(100.0);
+    let soft = Gravity::new(100.0).with_softening(2.0);
+    let origin = Vector::new(0.0, 0.0);
+    let close = Vector::new(1.0e-6, 0.0);
+
+    assert!(plain.pull(&origin, &close, 1.0).x > 1.0e12);
+    assert!(soft.pull(&origin, &close, 1.0).x < 1.0e-4);
+    assert_eq!(soft.pull(&origin, &origin, 1.0), origin);
+
+    // longe o amortecimento some
+    let far = Vector::new(1000.0, 0.0);
+    let ratio = soft.pull(&origin, &far, 1.0).x / plain.pull(&origin, &far, 1.0).x;
+    assert!((ratio - 1.0).abs() < 1.0e-5);
+}
+
+#[test]
+fn softened_potential_matches_softened_force() {
+    let gravity = Gravity::new(100.0).with_softening(3.0);
+    let (r, h) = (2.0, 1.0e-6);
+
+    // F = -dU/dr
+    let force = -(gravity.potential(1.0, 1.0, r + h) - gravity.potential(1.0, 1.0, r - h)) / (2.0 * h);
+    let pull = gravity.pull(&Vector::new(r, 0.0), &Vector::new(0.0, 0.0), 1.0);
+
+    assert!((force - pull.x).abs() < 1.0e-6, "{} {}", force, pull.x);
+}
@@ -0,0 +1,88 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::diagnostics::Diagnostics;
+use planets::gravity::Gravity;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn body(position: Vector, velocity: Vector, mass: f64) -> Planet {
+    Planet::new(position, velocity, 5.0, mass, Color::RGB(255, 255, 255))
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1.0e-12 * a.abs().max(b.abs()).max(1.0)
+}
+
+#[test]
+fn measures_two_bodies() {
+    let planets = [body(Vector::new(0.0, 0.0), Vector::new(0.0, -1.0), 3.0),
+                   body(Vector::new(10.0, 0.0), Vector::new(0.0, 2.0), 1.0)];
+    let diagnostics = Diagnostics::measure(&planets, &Gravity::new(100.0));
+
+    assert!(close(diagnostics.kinetic_energy, 0.5 * 3.0 * 1.0 + 0.5 * 1.0 * 4.0));
+    assert!(close(diagnostics.potential_energy, -100.0 * 3.0 / 10.0));
+    assert!(close(diagnostics.total_energy(), 3.5 - 30.0));
+    assert_eq!(diagnostics.momentum, Vector::new(0.0, -1.0));
+    assert!(close(diagnostics.angular_momentum, 10.0 * 2.0));
+    assert_eq!(diagnostics.center_of_mass, Vector::new(2.5, 0.0));
+}
+
+#[test]
+fn empty_system_is_all_zero() {
+    let diagnostics: Diagnostics = Diagnostics::measure(&[], &Gravity::new(100.0));
+
+    assert_eq!(diagnostics.total_energy(), 0.0);
+    assert_eq!(diagnostics.center_of_mass, Vector::new(0.0, 0.0));
+}
+
+#[test]
+fn drift_is_relative_or_absolute_for_zero_baseline() {
+    let baseline = Diagnostics::measure(&[body(Vector::new(0.0, 0.0), Vector::new(0.0, 0.0), 1.0),
+                                          body(Vector::new(1.0, 0.0), Vector::new(0.0, 0.0), 1.0)],
+                                        &Gravity::new(1.0));
+    let mut later = baseline.clone();
+    later.kinetic_energy = 0.1;
+    later.momentum = Vector::new(0.5, 0.0);
+
+    let drift = later.drift_since(&baseline);
+
+    assert!(close(drift.energy, 0.1));
+    assert_eq!(drift.momentum, 0.5);
+    assert_eq!(drift.angular_momentum, 0.0);
+}
+
+#[test]
+fn tracking_reports_drift_after_each_step() {
+    let sun = body(Vector::new(0.0, 0.0), Vector::new(0.0, 0.0), 1.0);
+    let mut earth = body(Vector::new(100.0, 0.0), Vector::new(0.0, 0.0), 1.0e-3);
+    earth.orbit_velocity(&sun);
+
+    let mut planets = Planets::new();
+    planets.add_planet(sun);
+    planets.add_planet(earth);
+    planets.set_timestep(0.1);
+
+    assert!(planets.drift().is_none());
+
+    planets.set_tracking(true);
+    let start = planets.latest_diagnostics().unwrap().clone();
+
+    for _ in 0..5_000 {
+        planets.step();
+    }
+
+    let latest = planets.latest_diagnostics().unwrap();
+    let drift = planets.drift().unwrap();
+
+    assert_ne!(latest, &start);
+    assert_eq!(latest, &planets.diagnostics());
+    assert!(drift.energy < 1.0e-5, "{:?}", drift);
+    assert!(drift.momentum < 1.0e-12, "{:?}", drift);
+    assert!(drift.angular_momentum < 1.0e-10, "{:?}", drift);
+
+    planets.set_tracking(false);
+    assert!(planets.latest_diagnostics().is_none());
+}
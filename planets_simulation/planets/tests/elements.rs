@@ -0,0 +1,138 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use std::f64::consts::PI;
+
+use forces::Vector;
+use planets::elements::Elements;
+use planets::integrator::Rk45;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+const MU: f64 = 100.0;
+
+fn close(a: f64, b: f64, tolerance: f64) -> bool {
+    (a - b).abs() <= tolerance * a.abs().max(b.abs()).max(1.0)
+}
+
+fn same_angle(a: f64, b: f64) -> bool {
+    let difference = (a - b).rem_euclid(2.0 * PI);
+    difference < 1.0e-9 || 2.0 * PI - difference < 1.0e-9
+}
+
+fn body(x: f64, y: f64, mass: f64) -> Planet {
+    Planet::new(Vector::new(x, y), Vector::new(0.0, 0.0), 1.0, mass, Color::RGB(255, 255, 255))
+}
+
+#[test]
+fn state_round_trips_through_elements() {
+    let cases = [(Vector::new(100.0, 0.0), Vector::new(0.0, 0.8)),
+                 (Vector::new(-30.0, 70.0), Vector::new(0.5, 0.9)),
+                 (Vector::new(50.0, -20.0), Vector::new(-0.7, -1.1)),
+                 (Vector::new(10.0, 5.0), Vector::new(4.0, 1.0))];
+
+    for (position, velocity) in cases {
+        let elements = Elements::from_state(&position, &velocity, MU);
+        let (p, v) = elements.to_state(MU);
+
+        assert!((&p - &position).magnitude() < 1.0e-9, "{:?} {:?}", elements, p);
+        assert!((&v - &velocity).magnitude() < 1.0e-9, "{:?} {:?}", elements, v);
+    }
+}
+
+#[test]
+fn elements_round_trip_through_state() {
+    for clockwise in [false, true] {
+        let elements = Elements {
+            semi_major_axis: 150.0,
+            eccentricity: 0.4,
+            argument_of_periapsis: 1.2,
+            true_anomaly: 2.5,
+            clockwise,
+        };
+
+        let (position, velocity) = elements.to_state(MU);
+        let back = Elements::from_state(&position, &velocity, MU);
+
+        assert!(close(back.semi_major_axis, 150.0, 1.0e-12));
+        assert!(close(back.eccentricity, 0.4, 1.0e-12));
+        assert!(same_angle(back.argument_of_periapsis, 1.2));
+        assert!(same_angle(back.true_anomaly, 2.5));
+        assert_eq!(back.clockwise, clockwise);
+    }
+}
+
+#[test]
+fn orbit_velocity_is_circular_and_counterclockwise_on_screen() {
+    let sun = body(400.0, 300.0, 1.0);
+    let mut earth = body(300.0, 250.0, 0.0);
+    earth.orbit_velocity(&sun);
+
+    let elements = earth.elements(&sun, Planet::G);
+    let radius = (&earth.position - &sun.position).magnitude();
+
+    assert!(elements.eccentricity < 1.0e-12);
+    assert!(close(elements.semi_major_axis, radius, 1.0e-12));
+    assert!(!elements.clockwise);
+}
+
+#[test]
+fn periapsis_apoapsis_and_period() {
+    let elements = Elements { semi_major_axis: 100.0, eccentricity: 0.5, ..Elements::circular(0.0, 0.0) };
+
+    assert_eq!(elements.periapsis(), 50.0);
+    assert_eq!(elements.apoapsis(), Some(150.0));
+    assert!(close(elements.period(MU).unwrap(), 2.0 * PI * 100.0, 1.0e-12));
+
+    let escaping = Elements::from_state(&Vector::new(10.0, 0.0), &Vector::new(0.0, 10.0), MU);
+    assert!(escaping.eccentricity > 1.0);
+    assert!(escaping.period(MU).is_none());
+    assert!(escaping.apoapsis().is_none());
+}
+
+#[test]
+fn body_returns_after_one_period() {
+    let mut sun = body(0.0, 0.0, 1.0);
+    let mut comet = body(0.0, 0.0, 1.0e-3);
+    let elements = Elements { semi_major_axis: 120.0, eccentricity: 0.6, ..Elements::circular(0.0, 0.5) };
+
+    comet.place_in_orbit(&sun, &elements, Planet::G);
+
+    // sol com o momento oposto para o centro de massa ficar parado
+    sun.velocity = &comet.velocity * -1.0e-3;
+    comet.velocity = &comet.velocity * (1.0 - 1.0e-3);
+
+    let mut planets = Planets::new();
+    let sun = planets.add_planet(sun);
+    let comet = planets.add_planet(comet);
+    planets.set_integrator(Rk45::new(1.0e-12));
+
+    let start = planets.elements(comet, sun).unwrap();
+    let period = start.period(Planet::G * 1.001).unwrap();
+    let steps = 1000;
+    planets.set_timestep(period / steps as f64);
+
+    for _ in 0..steps {
+        planets.step();
+    }
+
+    let end = planets.elements(comet, sun).unwrap();
+
+    assert!(close(end.semi_major_axis, start.semi_major_axis, 1.0e-8), "{:?} {:?}", start, end);
+    assert!(close(end.eccentricity, start.eccentricity, 1.0e-8));
+    assert!(same_angle(end.true_anomaly, start.true_anomaly) ||
+            (end.true_anomaly - start.true_anomaly).abs() < 1.0e-6, "{:?} {:?}", start, end);
+}
+
+#[test]
+fn place_in_orbit_is_relative_to_primary() {
+    let mut sun = body(400.0, 300.0, 1.0);
+    sun.velocity = Vector::new(1.0, 0.0);
+
+    let mut planet = body(0.0, 0.0, 0.0);
+    planet.place_in_orbit(&sun, &Elements::circular(100.0, 0.0), 1.0);
+
+    assert!((&planet.position - &Vector::new(500.0, 300.0)).magnitude() < 1.0e-12);
+    assert!((&planet.velocity - &Vector::new(1.0, -0.1)).magnitude() < 1.0e-12, "{:?}", planet.velocity);
+}
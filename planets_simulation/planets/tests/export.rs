@@ -0,0 +1,166 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use std::env;
+use std::fs;
+
+use forces::Vector;
+use planets::export::{self, Format};
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn system() -> Planets {
+    let sun = Planet::new(Vector::new(0.0, 0.0), Vector::new(0.0, 0.0), 10.0, 1.0, Color::RGB(255, 255, 0));
+    let mut earth = Planet::new(Vector::new(100.0, 0.0), Vector::new(0.0, 0.0), 2.0, 1.0e-3,
+                                Color::RGB(0, 0, 255));
+    earth.orbit_velocity(&sun);
+
+    let mut planets = Planets::new();
+    planets.add_planet(sun);
+    planets.add_planet(earth);
+    planets.set_timestep(0.5);
+    planets
+}
+
+#[test]
+fn run_matches_stepping_by_hand() {
+    let mut run = system();
+    let mut stepped = system();
+
+    run.run(100);
+
+    for _ in 0..100 {
+        stepped.step();
+    }
+
+    assert_eq!(run.planets(), stepped.planets());
+    assert_eq!(run.planets()[1].trajectory().len(), 100);
+}
+
+#[test]
+fn csv_has_one_row_per_step() {
+    let mut planets = system();
+    planets.run(3);
+
+    let earth = &planets.planets()[1];
+    let mut out = vec![];
+    export::write_csv(earth, planets.timestep(), &mut out).unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+
+    assert_eq!(lines.len(), 4);
+    assert!(lines[1].starts_with("1,0.5,"));
+    assert_eq!(lines[0], "step,time,x,y");
+
+    let last: Vec<f64> = lines[3].split(',').map(|value| value.parse().unwrap()).collect();
+    assert_eq!(last, vec![3.0, 1.5, earth.position.x, earth.position.y]);
+}
+
+#[test]
+fn binary_round_trips_exactly() {
+    let mut planets = system();
+    planets.run(50);
+
+    let earth = &planets.planets()[1];
+    let mut out = vec![];
+    export::write_binary(earth, planets.timestep(), &mut out).unwrap();
+
+    assert_eq!(out.len(), 4 + 8 + 8 + 50 * 24);
+
+    let (timestep, points) = export::read_binary(&mut out.as_slice()).unwrap();
+
+    assert_eq!(timestep, 0.5);
+    assert!(points.iter().eq(earth.trajectory().iter()));
+}
+
+#[test]
+fn binary_still_reads_the_first_format() {
+    let mut planets = system();
+    planets.run(50);
+
+    // TRJ1: so x e y de cada ponto, o passo e a posicao no arquivo
+    let earth = &planets.planets()[1];
+    let mut out = b"TRJ1".to_vec();
+    out.extend_from_slice(&planets.timestep().to_le_bytes());
+    out.extend_from_slice(&(earth.trajectory().len() as u64).to_le_bytes());
+
+    for point in earth.trajectory() {
+        out.extend_from_slice(&point.position.x.to_le_bytes());
+        out.extend_from_slice(&point.position.y.to_le_bytes());
+    }
+
+    assert_eq!(out.len(), 4 + 8 + 8 + 50 * 16);
+
+    let (timestep, points) = export::read_binary(&mut out.as_slice()).unwrap();
+
+    assert_eq!(timestep, 0.5);
+    assert!(points.iter().eq(earth.trajectory().iter()));
+}
+
+#[test]
+fn late_bodies_are_exported_on_the_system_clock() {
+    let mut planets = system();
+    planets.run(10);
+
+    let moon = Planet::new(Vector::new(120.0, 0.0), Vector::new(0.0, 0.0), 1.0, 1.0e-6, Color::RGB(200, 200, 200));
+    let moon = planets.add_planet(moon);
+    planets.run(2);
+
+    let mut out = vec![];
+    export::write_csv(planets.get(moon).unwrap(), planets.timestep(), &mut out).unwrap();
+
+    let text = String::from_utf8(out).unwrap();
+    let rows: Vec<&str> = text.lines().skip(1).map(|line| line.split(',').next().unwrap()).collect();
+
+    assert_eq!(planets.steps(), 12);
+    assert_eq!(rows, vec!["11", "12"]);
+    assert!(text.lines().nth(1).unwrap().starts_with("11,5.5,"));
+}
+
+#[test]
+fn binary_rejects_other_files() {
+    let error = export::read_binary(&mut &b"step,time,x,y\n"[..]).unwrap_err();
+
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn exports_to_files() {
+    let mut planets = system();
+    planets.run(10);
+
+    let directory = env::temp_dir().join(format!("planets-export-{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+
+    let earth = &planets.planets()[1];
+    let csv = directory.join("earth.csv");
+    let binary = directory.join("earth.bin");
+
+    export::export(earth, planets.timestep(), &csv, Format::Csv).unwrap();
+    export::export(earth, planets.timestep(), &binary, Format::Binary).unwrap();
+
+    assert_eq!(fs::read_to_string(&csv).unwrap().lines().count(), 11);
+    assert!(export::import_binary(&binary).unwrap().1.iter().eq(earth.trajectory().iter()));
+
+    fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn file_names_stay_in_the_directory() {
+    assert_eq!(export::file_name("earth"), "earth");
+    assert_eq!(export::file_name("Alpha-Centauri_B"), "Alpha-Centauri_B");
+    assert_eq!(export::file_name("../sun"), "___sun");
+    assert_eq!(export::file_name("a/b\\c"), "a_b_c");
+    assert_eq!(export::file_name(".."), "__");
+    assert_eq!(export::file_name(""), "_");
+}
+
+#[test]
+fn parses_format_names() {
+    assert_eq!("csv".parse::<Format>(), Ok(Format::Csv));
+    assert_eq!("binary".parse::<Format>(), Ok(Format::Binary));
+    assert!("json".parse::<Format>().is_err());
+    assert_eq!(Format::Binary.extension(), "bin");
+}
@@ -0,0 +1,137 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::integrator::*;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+const STEPS: usize = 100_000;
+const SUN_MASS: f64 = 1.0;
+
+fn sun() -> Planet {
+    Planet::new(Vector::new(0.0, 0.0), Vector::new(0.0, 0.0), 50.0, SUN_MASS, Color::RGB(255, 255, 0))
+}
+
+// orbita eliptica (e ~ 0.36) com periodo ~ 430 unidades de tempo
+fn two_body<I: Integrator + 'static>(integrator: I) -> Planets {
+    let mut planet = Planet::new(Vector::new(100.0, 0.0), Vector::new(0.0, 0.0),
+                                 10.0, 1.0e-5, Color::RGB(0, 255, 0));
+    planet.orbit_velocity(&sun());
+    planet.velocity = &planet.velocity * 0.8;
+
+    let mut planets = Planets::new();
+    planets.add_planet(sun());
+    planets.add_planet(planet);
+    planets.set_integrator(integrator);
+    planets.set_timestep(0.1);
+    planets
+}
+
+// energia total do par: o sol tambem se move
+fn energy(planets: &Planets) -> f64 {
+    let (sun, planet) = (&planets.planets()[0], &planets.planets()[1]);
+    let kinetic = |body: &Planet| 0.5 * body.mass() * body.velocity.magnitude().powi(2);
+    let distance = (&planet.position - &sun.position).magnitude();
+
+    kinetic(sun) + kinetic(planet) - Planet::G * sun.mass() * planet.mass() / distance
+}
+
+// maior variacao relativa de energia ao longo da simulacao
+fn drift<I: Integrator + 'static>(integrator: I, dt: f64, steps: usize) -> f64 {
+    let mut planets = two_body(integrator);
+    planets.set_timestep(dt);
+
+    let initial = energy(&planets);
+    let mut worst: f64 = 0.0;
+
+    for _ in 0..steps {
+        planets.step();
+        worst = worst.max(((energy(&planets) - initial) / initial).abs());
+    }
+
+    worst
+}
+
+#[test]
+fn euler_drifts_the_most() {
+    let euler = drift(Euler, 0.1, STEPS);
+
+    assert!(euler < 5.0e-3, "{:e}", euler);
+    assert!(euler > 100.0 * drift(VelocityVerlet, 0.1, STEPS));
+}
+
+#[test]
+fn velocity_verlet_energy_stays_bounded() {
+    let drift = drift(VelocityVerlet, 0.1, STEPS);
+
+    assert!(drift < 1.0e-5, "{:e}", drift);
+}
+
+#[test]
+fn leapfrog_energy_stays_bounded() {
+    let drift = drift(Leapfrog, 0.1, STEPS);
+
+    assert!(drift < 1.0e-5, "{:e}", drift);
+}
+
+#[test]
+fn rk4_energy_drift() {
+    let drift = drift(Rk4, 0.1, STEPS);
+
+    assert!(drift < 1.0e-10, "{:e}", drift);
+}
+
+#[test]
+fn rk45_energy_drift() {
+    let drift = drift(Rk45::new(1.0e-10), 0.1, STEPS);
+
+    assert!(drift < 1.0e-11, "{:e}", drift);
+}
+
+#[test]
+fn rk45_subdivides_large_timesteps() {
+    let rk4 = drift(Rk4, 5.0, 2_000);
+    let rk45 = drift(Rk45::new(1.0e-10), 5.0, 2_000);
+
+    assert!(rk45 < 1.0e-7, "{:e}", rk45);
+    assert!(rk45 * 1000.0 < rk4, "rk45 {:e} rk4 {:e}", rk45, rk4);
+}
+
+#[test]
+fn rk45_finishes_when_the_error_is_not_finite() {
+    let (sender, receiver) = mpsc::channel();
+
+    // aceleracoes como as de dois corpos no mesmo lugar sem softening
+    thread::spawn(move || {
+        for value in [f64::NAN, f64::INFINITY] {
+            let mut positions = [Vector::new(0.0, 0.0)];
+            let mut velocities = [Vector::new(1.0, 0.0)];
+            let acceleration = |x: &[Vector]| vec![Vector::new(value, 0.0); x.len()];
+
+            let mut rk45 = Rk45::new(1.0e-9);
+            rk45.step(&mut positions, &mut velocities, 0.1, &acceleration);
+            rk45.step(&mut positions, &mut velocities, 0.1, &acceleration);
+        }
+
+        sender.send(()).unwrap();
+    });
+
+    assert!(receiver.recv_timeout(Duration::from_secs(10)).is_ok(), "Rk45 never finished the step");
+}
+
+#[test]
+fn step_records_trajectory_and_keeps_timestep() {
+    let mut planets = two_body(Leapfrog);
+    let start = planets.planets()[1].position.clone();
+
+    planets.set_timestep(0.5);
+    planets.step();
+
+    assert_eq!(planets.timestep(), 0.5);
+    assert_ne!(planets.planets()[1].position, start);
+}
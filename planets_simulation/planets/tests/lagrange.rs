@@ -0,0 +1,186 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::camera::Camera;
+use planets::integrator::Rk45;
+use planets::lagrange::RestrictedThreeBody;
+use planets::{Planet, PlanetId, Planets};
+use sdl2::pixels::Color;
+
+const G: f64 = 100.0;
+
+fn body(x: f64, y: f64, mass: f64) -> Planet {
+    Planet::new(Vector::new(x, y), Vector::new(0.0, 0.0), 1.0, mass, Color::RGB(255, 255, 255))
+}
+
+// par em orbita circular em volta do centro de massa, na origem e parado
+fn binary(mass_ratio: f64, clockwise: bool) -> (Planets, PlanetId, PlanetId) {
+    let separation: f64 = 100.0;
+    let omega = (G / separation.powi(3)).sqrt() * if clockwise { 1.0 } else { -1.0 };
+
+    let mut primary = body(-mass_ratio * separation, 0.0, 1.0 - mass_ratio);
+    let mut secondary = body((1.0 - mass_ratio) * separation, 0.0, mass_ratio);
+    primary.velocity = Vector::new(0.0, omega * primary.position.x);
+    secondary.velocity = Vector::new(0.0, omega * secondary.position.x);
+
+    let mut planets = Planets::new();
+    planets.set_integrator(Rk45::new(1.0e-12));
+    let primary = planets.add_planet(primary);
+    let secondary = planets.add_planet(secondary);
+
+    (planets, primary, secondary)
+}
+
+// aceleracao no referencial girante de uma particula parada nele
+fn effective_acceleration(restricted: &RestrictedThreeBody, planets: &Planets, point: &Vector) -> Vector {
+    let omega = restricted.angular_velocity();
+    let mut acceleration = &(point - restricted.barycenter()) * (omega * omega);
+
+    for planet in planets.planets() {
+        let offset = &planet.position - point;
+        let distance = offset.magnitude();
+        acceleration = &acceleration + &(&offset * (G * planet.mass() / distance.powi(3)));
+    }
+
+    acceleration
+}
+
+#[test]
+fn lagrange_points_are_equilibria() {
+    for mass_ratio in [1.0e-3, 0.01, 0.1, 0.5] {
+        let (planets, primary, secondary) = binary(mass_ratio, false);
+        let restricted = planets.restricted(primary, secondary).unwrap();
+        let scale = G / 100.0_f64.powi(2);
+
+        for (i, point) in restricted.lagrange_points().iter().enumerate() {
+            let residual = effective_acceleration(&restricted, &planets, point).magnitude();
+            assert!(residual < 1.0e-9 * scale, "mu {} L{} {:e}", mass_ratio, i + 1, residual);
+        }
+    }
+}
+
+#[test]
+fn lagrange_points_sit_where_expected() {
+    let mass_ratio = 3.0e-6;
+    let (planets, primary, secondary) = binary(mass_ratio, false);
+    let restricted = planets.restricted(primary, secondary).unwrap();
+    let [l1, l2, l3, l4, l5] = restricted.lagrange_points();
+
+    let secondary = &planets.get(secondary).unwrap().position;
+    let primary = &planets.get(primary).unwrap().position;
+    let hill = 100.0 * (mass_ratio / 3.0).cbrt();
+
+    assert!(((secondary.x - l1.x) / hill - 1.0).abs() < 0.01, "{:?}", l1);
+    assert!(((l2.x - secondary.x) / hill - 1.0).abs() < 0.01, "{:?}", l2);
+    assert!((l3.x + 100.0).abs() < 0.01, "{:?}", l3);
+    assert!(l1.y.abs() < 1.0e-9 && l2.y.abs() < 1.0e-9 && l3.y.abs() < 1.0e-9);
+
+    for point in [&l4, &l5] {
+        assert!(((point - primary).magnitude() - 100.0).abs() < 1.0e-9);
+        assert!(((point - secondary).magnitude() - 100.0).abs() < 1.0e-9);
+    }
+}
+
+#[test]
+fn l4_leads_the_secondary() {
+    for clockwise in [false, true] {
+        let (planets, primary, secondary) = binary(0.01, clockwise);
+        let restricted = planets.restricted(primary, secondary).unwrap();
+        let [_, _, _, l4, l5] = restricted.lagrange_points();
+
+        let velocity = &planets.get(secondary).unwrap().velocity;
+
+        assert!(l4.dot_product(velocity) > 0.0, "clockwise {}", clockwise);
+        assert!(l5.dot_product(velocity) < 0.0, "clockwise {}", clockwise);
+    }
+}
+
+#[test]
+fn jacobi_constant_is_conserved_by_a_test_particle() {
+    let (mut planets, primary, secondary) = binary(0.01, false);
+
+    let mut particle = body(0.0, 0.0, 0.0);
+    let restricted = planets.restricted(primary, secondary).unwrap();
+    particle.position = Vector::new(30.0, 40.0);
+    particle.velocity = Vector::new(0.6, -0.5);
+    let particle = planets.add_planet(particle);
+
+    let jacobi = |planets: &Planets| {
+        let particle = planets.get(particle).unwrap();
+        planets.restricted(primary, secondary).unwrap()
+            .jacobi_constant(&particle.position, &particle.velocity)
+    };
+
+    let start = jacobi(&planets);
+    assert_eq!(start, restricted.jacobi_constant(&Vector::new(30.0, 40.0), &Vector::new(0.6, -0.5)));
+
+    planets.set_timestep(0.5);
+    planets.run(2_000);
+
+    let end = jacobi(&planets);
+    assert!(((end - start) / start).abs() < 1.0e-6, "{} {}", start, end);
+}
+
+#[test]
+fn zero_velocity_curves_follow_the_level() {
+    let (planets, primary, secondary) = binary(0.1, false);
+    let restricted = planets.restricted(primary, secondary).unwrap();
+    let l1 = &restricted.lagrange_points()[0];
+    let jacobi = 2.0 * restricted.pseudo_potential(l1);
+
+    let segments = restricted.zero_velocity_curves(jacobi, 1.5, 200);
+
+    assert!(!segments.is_empty());
+
+    for (start, end) in &segments {
+        for point in [start, end] {
+            let level = 2.0 * restricted.pseudo_potential(point);
+            assert!((level - jacobi).abs() < 0.01 * jacobi, "{:?} {} {}", point, level, jacobi);
+        }
+    }
+
+    // com C <= 0 nenhuma regiao e proibida
+    assert!(restricted.zero_velocity_curves(0.0, 1.5, 50).is_empty());
+}
+
+#[test]
+fn rotating_frame_keeps_the_pair_still() {
+    let (mut planets, primary, secondary) = binary(0.2, true);
+    let mut camera = Camera::new((800, 600));
+    camera.center = Vector::new(0.0, 0.0);
+
+    let frame = planets.restricted(primary, secondary).unwrap().frame(planets.timestep());
+    camera.set_frame(Some(frame));
+    let start = camera.to_screen(&planets.get(secondary).unwrap().position);
+
+    planets.run(500);
+
+    let frame = planets.restricted(primary, secondary).unwrap().frame(planets.timestep());
+    camera.set_frame(Some(frame.clone()));
+
+    assert_eq!(start, (480, 300));
+    assert_eq!(camera.to_screen(&planets.get(secondary).unwrap().position), start);
+    assert_eq!(camera.to_screen(&planets.get(primary).unwrap().position), (380, 300));
+
+    // pontos antigos do rastro caem onde o corpo estava no referencial girante
+    let trail = planets.get(secondary).unwrap().trajectory();
+    let steps = trail.steps();
+    for point in trail.iter().step_by(50) {
+        let (x, y) = camera.to_screen_at(&point.position, steps - point.step);
+        assert!((x - 480).abs() <= 1 && (y - 300).abs() <= 1, "{:?}", (x, y));
+    }
+
+    let world = camera.to_world(123, 456);
+    assert!((&frame.to_frame(&world, 0) - &Vector::new(123.0 - 400.0, 456.0 - 300.0)).magnitude() < 1.0e-9);
+}
+
+#[test]
+fn frame_of_coincident_bodies_stays_finite() {
+    let restricted = RestrictedThreeBody::new(&body(5.0, 5.0, 1.0), &body(5.0, 5.0, 0.1), G);
+    let frame = restricted.frame(1.0);
+
+    assert_eq!(frame.angle, 0.0);
+    assert_eq!(frame.origin, Vector::new(5.0, 5.0));
+}
@@ -0,0 +1,164 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn body(x: f64, y: f64, mass: f64) -> Planet {
+    Planet::new(Vector::new(x, y), Vector::new(0.0, 0.0), 5.0, mass, Color::RGB(255, 255, 255))
+}
+
+fn scattered() -> Vec<Planet> {
+    vec![body(0.0, 0.0, 1.0),
+         body(120.0, 10.0, 1.0e-3),
+         body(-40.0, 75.0, 2.5e-2),
+         body(15.0, -200.0, 4.0e-4),
+         body(300.0, 300.0, 0.3)]
+}
+
+fn momentum(planets: &Planets) -> Vector {
+    planets.planets()
+        .iter()
+        .fold(Vector::new(0.0, 0.0), |sum, planet| &sum + &(&planet.velocity * planet.mass()))
+}
+
+#[test]
+fn forces_obey_third_law() {
+    let mut planets = Planets::new();
+
+    for planet in scattered() {
+        planets.add_planet(planet);
+    }
+
+    let net = planets.planets()
+        .iter()
+        .zip(planets.accelerations())
+        .fold(Vector::new(0.0, 0.0), |sum, (planet, acceleration)| {
+            &sum + &(&acceleration * planet.mass())
+        });
+
+    assert!(net.magnitude() < 1.0e-15, "{:?}", net);
+}
+
+#[test]
+fn result_does_not_depend_on_insertion_order() {
+    let mut forward = Planets::new();
+    let mut backward = Planets::new();
+
+    for planet in scattered() {
+        forward.add_planet(planet);
+    }
+
+    for planet in scattered().into_iter().rev() {
+        backward.add_planet(planet);
+    }
+
+    for _ in 0..100 {
+        forward.step();
+        backward.step();
+    }
+
+    for (a, b) in forward.planets().iter().zip(backward.planets().iter().rev()) {
+        assert!((&a.position - &b.position).magnitude() < 1.0e-9);
+        assert!((&a.velocity - &b.velocity).magnitude() < 1.0e-9);
+    }
+}
+
+#[test]
+fn identical_planets_attract_each_other() {
+    let mut planets = Planets::new();
+    planets.add_planet(body(0.0, 0.0, 1.0));
+    planets.add_planet(body(0.0, 0.0, 1.0));
+    planets.get_mut(planets.planets()[1].id().unwrap()).unwrap().position = Vector::new(50.0, 0.0);
+
+    let accelerations = planets.accelerations();
+
+    assert!(accelerations[0].x > 0.0);
+    assert_eq!(accelerations[0].x, -accelerations[1].x);
+}
+
+#[test]
+fn sun_moves_and_momentum_is_conserved() {
+    let sun = body(0.0, 0.0, 1.0);
+    let mut earth = body(100.0, 0.0, 0.1);
+    earth.orbit_velocity(&sun);
+
+    let mut planets = Planets::new();
+    let sun = planets.add_planet(sun);
+    planets.add_planet(earth);
+
+    let start = momentum(&planets);
+
+    for _ in 0..1000 {
+        planets.step();
+    }
+
+    assert_ne!(planets.get(sun).unwrap().position, Vector::new(0.0, 0.0));
+    assert!((&momentum(&planets) - &start).magnitude() < 1.0e-12);
+}
+
+#[test]
+fn ids_are_stable_and_never_reused() {
+    let mut planets = Planets::new();
+    let ids: Vec<_> = scattered().into_iter().map(|planet| planets.add_planet(planet)).collect();
+
+    let removed = planets.remove_planet(ids[1]).unwrap();
+    assert_eq!(removed.id(), None);
+    assert_eq!(removed.mass(), 1.0e-3);
+    assert!(planets.get(ids[1]).is_none());
+    assert!(planets.remove_planet(ids[1]).is_none());
+
+    assert_eq!(planets.get(ids[4]).unwrap().mass(), 0.3);
+
+    let new = planets.add_planet(body(1.0, 1.0, 1.0));
+    assert!(!ids.contains(&new));
+    assert_eq!(planets.get(new).unwrap().id(), Some(new));
+}
+
+#[test]
+fn is_finite_spots_nan_and_infinity() {
+    let mut planet = body(1.0, 2.0, 1.0);
+    assert!(planet.is_finite());
+
+    planet.velocity = Vector::new(f64::INFINITY, 0.0);
+    assert!(!planet.is_finite());
+}
+
+#[test]
+fn orbit_velocity_around_the_same_point_keeps_the_velocity() {
+    let sun = body(10.0, 10.0, 1.0);
+    let mut planet = body(10.0, 10.0, 1.0e-3);
+    planet.velocity = Vector::new(1.0, 2.0);
+
+    planet.orbit_velocity(&sun);
+
+    assert_eq!(planet.velocity, Vector::new(1.0, 2.0));
+}
+
+// a verificacao so existe em debug
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "non-finite bodies before the step: PlanetId(1) at")]
+fn step_reports_the_body_that_went_non_finite() {
+    let mut planets = Planets::new();
+    planets.add_planet(body(0.0, 0.0, 1.0));
+
+    let mut broken = body(100.0, 0.0, 1.0e-3);
+    broken.velocity = Vector::new(f64::NAN, 0.0);
+    planets.add_planet(broken);
+
+    planets.step();
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "non-finite bodies after the step: PlanetId(0) at")]
+fn step_reports_bodies_that_overflow() {
+    let mut planets = Planets::new();
+    planets.add_planet(body(0.0, 0.0, 1.0));
+    planets.add_planet(body(1.0e-160, 0.0, 1.0));
+
+    planets.step();
+}
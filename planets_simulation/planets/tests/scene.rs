@@ -0,0 +1,307 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::{Vec3, Vector};
+use planets::collision::Collision;
+use planets::gravity::Solver;
+use planets::scene::{Method, Scene};
+use planets::Planet;
+use sdl2::pixels::Color;
+
+const SUN: &str = r#"
+[[bodies]]
+name = "sun"
+mass = 1.0
+radius = 50.0
+position = [0.0, 0.0]
+"#;
+
+fn error(text: &str) -> String {
+    Scene::parse(text).unwrap_err().to_string()
+}
+
+fn with_body(body: &str) -> String {
+    format!("{}\n[[bodies]]\n{}\n", SUN, body)
+}
+
+#[test]
+fn loads_bundled_solar_system() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../scenes/solar_system.toml");
+    let scene = Scene::from_file(path).unwrap();
+    let planets = scene.build().unwrap();
+
+    assert_eq!(scene.bodies.len(), 5);
+    assert_eq!(planets.planets().len(), 5);
+    assert_eq!(planets.collision(), Collision::Merge);
+    assert_eq!(planets.gravity().constant, Planet::G);
+
+    // mesmo resultado que `orbit_velocity` dava no main antigo
+    let sun = Planet::new(Vector::new(400.0, 300.0), Vector::new(0.0, 0.0), 50.0, 1.0, Color::RGB(255, 255, 0));
+    let mut earth = Planet::new(Vector::new(300.0, 250.0), Vector::new(0.0, 0.0), 10.0, 1.0e-5, Color::RGB(0, 255, 0));
+    earth.orbit_velocity(&sun);
+
+    assert!((&planets.planets()[1].velocity - &earth.velocity).magnitude() < 1.0e-12);
+}
+
+#[test]
+fn applies_settings() {
+    let scene = Scene::parse(&format!(r#"
+        gravity = 2.5
+        softening = 0.1
+        collision = "bounce"
+
+        [integrator]
+        method = "rk45"
+        timestep = 0.25
+        tolerance = 1e-6
+
+        [solver]
+        method = "barnes-hut"
+        theta = 0.7
+        {}"#, SUN)).unwrap();
+
+    assert_eq!(scene.integrator.method, Method::Rk45);
+
+    let planets = scene.build().unwrap();
+    assert_eq!(planets.gravity().constant, 2.5);
+    assert_eq!(planets.gravity().softening, 0.1);
+    assert_eq!(planets.collision(), Collision::Bounce);
+    assert_eq!(planets.timestep(), 0.25);
+    assert_eq!(planets.solver(), Solver::BarnesHut { theta: 0.7 });
+}
+
+#[test]
+fn defaults_match_planets_defaults() {
+    let scene = Scene::parse(SUN).unwrap();
+    let planets = scene.build().unwrap();
+
+    assert_eq!(scene.integrator.method, Method::VelocityVerlet);
+    assert_eq!(planets.gravity().constant, Planet::G);
+    assert_eq!(planets.solver(), Solver::Direct);
+    assert_eq!(planets.collision(), Collision::Ignore);
+    assert_eq!(planets.planets()[0].velocity, Vector::new(0.0, 0.0));
+}
+
+#[test]
+fn moons_orbit_a_moving_primary() {
+    let scene = Scene::parse(&format!(r#"{}
+        [[bodies]]
+        name = "moon"
+        mass = 1e-8
+        radius = 1.0
+        position = [110.0, 0.0]
+        orbit = {{ around = "earth" }}
+
+        [[bodies]]
+        name = "earth"
+        mass = 1e-3
+        radius = 2.0
+        position = [100.0, 0.0]
+        orbit = {{ around = "sun" }}
+    "#, SUN)).unwrap();
+
+    let planets = scene.build().unwrap();
+    let (moon, earth) = (&planets.planets()[1], &planets.planets()[2]);
+
+    assert!((earth.velocity.y - (-1.0)).abs() < 1.0e-12, "{:?}", earth.velocity);
+
+    let relative = &moon.velocity - &earth.velocity;
+    let expected = (100.0 * 1.0e-3 / 10.0_f64).sqrt();
+    assert!((relative.magnitude() - expected).abs() < 1.0e-12);
+}
+
+#[test]
+fn reports_syntax_errors_with_location() {
+    assert!(error("gravity = \n").contains("line 1"));
+}
+
+#[test]
+fn reports_unknown_fields_and_methods() {
+    assert!(error(&format!("gravty = 1.0\n{}", SUN)).contains("unknown field `gravty`"));
+    assert!(error(&format!("[integrator]\nmethod = \"midpoint\"\n{}", SUN)).contains("unknown variant `midpoint`"));
+}
+
+#[test]
+fn rejects_empty_scene() {
+    assert_eq!(error("bodies = []\n"), "bodies: at least one body is required");
+}
+
+#[test]
+fn rejects_bad_numbers() {
+    assert_eq!(error(&format!("[integrator]\ntimestep = 0.0\n{}", SUN)),
+               "integrator.timestep: must be greater than zero");
+    assert_eq!(error(&format!("softening = -1.0\n{}", SUN)), "softening: must not be negative");
+    assert_eq!(error(&with_body("name = \"x\"\nmass = nan\nradius = 1.0\nposition = [1.0, 0.0]")),
+               "bodies[1].mass: must be finite");
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [inf, 0.0]")),
+               "bodies[1].position: must be finite");
+}
+
+#[test]
+fn build_rejects_a_scene_changed_after_loading() {
+    let mut scene = Scene::parse(SUN).unwrap();
+    scene.integrator.timestep = 0.0;
+
+    assert!(scene.build().is_err());
+    assert!(scene.build_3d().is_err());
+}
+
+#[test]
+fn rejects_duplicate_names() {
+    assert_eq!(error(&with_body("name = \"sun\"\nmass = 1.0\nradius = 1.0\nposition = [1.0, 0.0]")),
+               "bodies[1].name: `sun` is already used by bodies[0]");
+}
+
+#[test]
+fn rejects_bad_orbits() {
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [1.0, 0.0]\n\
+                                 orbit = { around = \"moon\" }")),
+               "bodies[1].orbit.around: no body is called `moon`");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [1.0, 0.0]\n\
+                                 velocity = [0.0, 1.0]\norbit = { around = \"sun\" }")),
+               "bodies[1]: set either `velocity` or `orbit`, not both");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [0.0, 0.0]\n\
+                                 orbit = { around = \"sun\" }")),
+               "bodies[1].orbit: `x` sits on top of `sun`");
+}
+
+#[test]
+fn rejects_orbit_cycles() {
+    let message = error(r#"
+        [[bodies]]
+        name = "a"
+        mass = 1.0
+        radius = 1.0
+        position = [0.0, 0.0]
+        orbit = { around = "b" }
+
+        [[bodies]]
+        name = "b"
+        mass = 1.0
+        radius = 1.0
+        position = [10.0, 0.0]
+        orbit = { around = "a" }
+    "#);
+
+    assert_eq!(message, "bodies[0].orbit.around: `a` orbits around itself through other bodies");
+}
+
+#[test]
+fn places_bodies_from_orbital_elements() {
+    let scene = Scene::parse(&with_body(r#"
+        name = "comet"
+        mass = 1e-6
+        radius = 1.0
+        orbit = { around = "sun", semi_major_axis = 200.0, eccentricity = 0.5, argument_of_periapsis = 90.0 }
+    "#)).unwrap();
+
+    let planets = scene.build().unwrap();
+    let comet = &planets.planets()[1];
+
+    // periapsis a 100 unidades, em +y
+    assert!((&comet.position - &Vector::new(0.0, 100.0)).magnitude() < 1.0e-9, "{:?}", comet.position);
+
+    let elements = comet.elements(&planets.planets()[0], scene.gravity);
+    assert!((elements.eccentricity - 0.5).abs() < 1.0e-12);
+    assert!((elements.semi_major_axis - 200.0).abs() < 1.0e-9);
+}
+
+#[test]
+fn rejects_bad_elements() {
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [5.0, 0.0]\n\
+                                 orbit = { around = \"sun\", semi_major_axis = 10.0 }")),
+               "bodies[1]: set either `position` or `orbit.semi_major_axis`, not both");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\n\
+                                 orbit = { around = \"sun\", semi_major_axis = 10.0, eccentricity = 1.0 }")),
+               "bodies[1].orbit.eccentricity: must be below 1 for a closed orbit");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [5.0, 0.0]\n\
+                                 orbit = { around = \"sun\", eccentricity = 0.2 }")),
+               "bodies[1].orbit.semi_major_axis: is required to set the other elements");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\norbit = { around = \"sun\" }")),
+               "bodies[1].position: is required unless `orbit.semi_major_axis` is set");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0")),
+               "bodies[1].position: is required without an orbit");
+}
+
+#[test]
+fn loads_bundled_inclined_scene() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../scenes/inclined.toml");
+    let scene = Scene::from_file(path).unwrap();
+    let planets = scene.build_3d().unwrap();
+
+    assert_eq!(scene.dimensions, 3);
+    assert_eq!(planets.planets().len(), 5);
+    assert_eq!(planets.planets()[4].position, Vec3::new(400.0, 100.0, 150.0));
+
+    // a orbita polar fica num plano vertical
+    let (sun, polar) = (&planets.planets()[0], &planets.planets()[3]);
+    let normal = (&polar.position - &sun.position).cross(&(&polar.velocity - &sun.velocity));
+    assert!(normal.z.abs() < 1.0e-9 * normal.magnitude());
+}
+
+#[test]
+fn builds_inclined_orbits_in_space() {
+    let scene = Scene::parse(&format!("dimensions = 3\n{}", with_body(
+        "name = \"moon\"\nmass = 1.0e-6\nradius = 1.0\n\
+         orbit = { around = \"sun\", semi_major_axis = 100.0, inclination = 30.0, \
+                   longitude_of_ascending_node = 90.0 }"))).unwrap();
+    let planets = scene.build_3d().unwrap();
+    let moon = &planets.planets()[1];
+
+    // comeca no no ascendente, em +y
+    assert!((&moon.position - &Vec3::new(0.0, 100.0, 0.0)).magnitude() < 1.0e-9, "{:?}", moon.position);
+    assert!(moon.velocity.z > 0.0);
+
+    // no plano fica so a projecao
+    let flat = scene.build().unwrap();
+    assert_eq!(flat.planets()[1].position, moon.position.xy());
+
+    // cenas 2D viram o plano z = 0
+    let planets = Scene::parse(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [5.0, 0.0]\n\
+                                           velocity = [0.0, 1.0]")).unwrap().build_3d().unwrap();
+    assert_eq!(planets.planets()[1].velocity, Vec3::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn rejects_space_settings_in_the_plane() {
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [5.0, 0.0, 1.0]")),
+               "bodies[1].position: has a `z` component, which needs `dimensions = 3`");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [5.0]")),
+               "bodies[1].position: must have 2 components");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\n\
+                                 orbit = { around = \"sun\", semi_major_axis = 10.0, inclination = 5.0 }")),
+               "bodies[1].orbit.inclination: an inclined orbit needs `dimensions = 3`");
+
+    assert_eq!(error(&with_body("name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [5.0, 0.0]\n\
+                                 orbit = { around = \"sun\", inclination = 5.0 }")),
+               "bodies[1].orbit.semi_major_axis: is required to set the other elements");
+
+    assert_eq!(error(&format!("dimensions = 4\n{}", SUN)), "dimensions: must be 2 or 3");
+}
+
+#[test]
+fn space_scenes_keep_collisions_and_solver() {
+    let scene = Scene::parse(&format!("dimensions = 3\ncollision = \"merge\"\n\
+                                       [solver]\nmethod = \"barnes-hut\"\n{}", SUN)).unwrap();
+    let planets = scene.build_3d().unwrap();
+
+    assert_eq!(planets.collision(), Collision::Merge);
+    assert_eq!(planets.solver(), Solver::BarnesHut { theta: 0.5 });
+}
+
+#[test]
+fn rejects_vertical_circular_orbits_in_space() {
+    assert_eq!(error(&format!("dimensions = 3\n{}", with_body(
+                   "name = \"x\"\nmass = 1.0\nradius = 1.0\nposition = [0.0, 0.0, 5.0]\n\
+                    orbit = { around = \"sun\" }"))),
+               "bodies[1].position: must not be straight above or below `sun` for a circular orbit");
+}
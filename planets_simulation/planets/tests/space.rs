@@ -0,0 +1,203 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use std::f64::consts::{FRAC_PI_2, PI};
+
+use forces::{Vec3, Vector};
+use planets::camera::Camera;
+use planets::collision::Collision;
+use planets::elements::{Elements, Orientation};
+use planets::gravity::{Gravity, Solver};
+use planets::integrator::Rk4;
+use planets::projection::{Camera3, Projection};
+use planets::space::{Planet3, Planets3};
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn body(position: Vec3<f64>, velocity: Vec3<f64>, mass: f64) -> Planet3 {
+    Planet3::new(position, velocity, 1.0, mass, Color::RGB(255, 255, 255))
+}
+
+// sol parado na origem e um planeta na orbita dada
+fn sun_and_planet(elements: &Elements, orientation: &Orientation) -> Planets3 {
+    let sun = body(Vec3::zero(), Vec3::zero(), 1.0);
+    let mut planet = body(Vec3::zero(), Vec3::zero(), 1.0e-6);
+    planet.place_in_orbit(&sun, elements, orientation, Planet::G);
+
+    let mut planets = Planets3::new();
+    planets.add_planet(sun);
+    planets.add_planet(planet);
+    planets
+}
+
+#[test]
+fn planar_system_matches_the_2d_simulation() {
+    let mut flat = Planets::new();
+    let mut space = Planets3::new();
+
+    for (x, y, vx, vy, mass) in [(0.0, 0.0, 0.0, -0.01, 1.0),
+                                 (100.0, 0.0, 0.0, 1.0, 1.0e-2),
+                                 (-60.0, 20.0, 0.3, -1.1, 1.0e-3)] {
+        let planet = Planet::new(Vector::new(x, y), Vector::new(vx, vy), 1.0, mass, Color::RGB(0, 0, 0));
+        space.add_planet(Planet3::from(planet.clone()));
+        flat.add_planet(planet);
+    }
+
+    flat.run(500);
+    space.run(500);
+
+    for (a, b) in flat.planets().iter().zip(space.planets()) {
+        assert_eq!(b.position.z, 0.0);
+        assert!((&b.position.xy() - &a.position).magnitude() < 1.0e-9, "{:?} {:?}", a, b);
+    }
+}
+
+#[test]
+fn inclined_orbit_keeps_its_plane_and_energy() {
+    let elements = Elements { semi_major_axis: 120.0, eccentricity: 0.3, ..Elements::circular(0.0, 0.4) };
+    let orientation = Orientation { inclination: 0.6, longitude_of_ascending_node: 1.1 };
+    let mut planets = sun_and_planet(&elements, &orientation);
+    planets.set_integrator(Rk4);
+    planets.set_timestep(0.5);
+
+    let before = planets.diagnostics();
+    planets.run(4000);
+
+    let after = planets.diagnostics();
+    let drift = after.drift_since(&before);
+    assert!(drift.energy < 1.0e-6, "{:?}", drift);
+    assert!(drift.angular_momentum < 1.0e-6, "{:?}", drift);
+    assert!((&after.momentum - &before.momentum).magnitude() < 1.0e-12);
+
+    // o plano da orbita forma o angulo da inclinacao com o plano xy
+    let normal = after.angular_momentum;
+    let tilt = normal.angle(&Vec3::new(0.0, 0.0, -1.0));
+    assert!((tilt - 0.6).abs() < 1.0e-6, "{}", tilt);
+
+    let planet = &planets.planets()[1];
+    assert!(planet.trajectory().iter().all(|point| point.position.dot_product(&normal).abs() < 1.0e-6 * normal.magnitude()));
+}
+
+#[test]
+fn bodies_merge_in_space() {
+    let mut planets = Planets3::new();
+    planets.set_gravity(Gravity::new(0.0));
+    planets.set_collision(Collision::Merge);
+    planets.add_planet(body(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0), 3.0));
+    planets.add_planet(body(Vec3::new(0.0, 0.0, 1.5), Vec3::new(1.0, 0.0, 0.0), 1.0));
+
+    planets.step();
+
+    assert_eq!(planets.planets().len(), 1);
+
+    let merged = &planets.planets()[0];
+    assert_eq!(merged.mass(), 4.0);
+    assert_eq!(merged.velocity, Vec3::new(0.25, 0.0, 0.75));
+}
+
+#[test]
+fn octree_approaches_direct_summation() {
+    // espiral em volta do eixo z, subindo
+    let positions: Vec<Vec3<f64>> = (0..100)
+        .map(|i| {
+            let angle = i as f64 * 0.7;
+            Vec3::new(angle.cos() * (10.0 + i as f64), angle.sin() * (10.0 + i as f64), i as f64 * 3.0 - 150.0)
+        })
+        .collect();
+    let masses: Vec<f64> = (0..100).map(|i| 1.0 + (i % 7) as f64).collect();
+    let gravity = Gravity::new(Planet::G);
+
+    let direct = Solver::Direct.accelerations(&positions, &masses, &gravity);
+    let exact = Solver::BarnesHut { theta: 0.0 }.accelerations(&positions, &masses, &gravity);
+    let approximate = Solver::BarnesHut { theta: 0.5 }.accelerations(&positions, &masses, &gravity);
+
+    for ((direct, exact), approximate) in direct.iter().zip(&exact).zip(&approximate) {
+        assert!((direct - exact).magnitude() < 1.0e-9 * direct.magnitude(), "{:?} {:?}", direct, exact);
+        assert!((direct - approximate).magnitude() < 0.05 * direct.magnitude(), "{:?} {:?}", direct, approximate);
+    }
+}
+
+#[test]
+fn body_climbs_through_the_ascending_node() {
+    let node = 2.0;
+
+    for clockwise in [false, true] {
+        // o corpo comeca no no ascendente
+        let elements = Elements { clockwise, ..Elements::circular(80.0, 0.0) };
+        let orientation = Orientation { inclination: 0.3, longitude_of_ascending_node: node };
+        let (position, velocity) = elements.to_state_in_space(&orientation, 100.0);
+
+        assert!(position.z.abs() < 1.0e-12);
+        assert!((position.y.atan2(position.x) - node).abs() < 1.0e-12);
+        assert!(velocity.z > 0.0);
+        assert!((velocity.z / velocity.magnitude() - 0.3f64.sin()).abs() < 1.0e-12);
+    }
+
+    // sem inclinacao e o mesmo estado do plano
+    let elements = Elements { eccentricity: 0.4, ..Elements::circular(90.0, 1.0) };
+    let (position, velocity) = elements.to_state(100.0);
+    let in_space = elements.to_state_in_space(&Orientation::default(), 100.0);
+
+    assert_eq!(in_space, (Vec3::from(position), Vec3::from(velocity)));
+}
+
+#[test]
+fn untilted_camera_matches_the_2d_camera() {
+    let flat = Camera::new((800, 600));
+    let camera = Camera3::new((800, 600));
+
+    for (x, y) in [(0.0, 0.0), (400.0, 300.0), (123.4, -56.7)] {
+        let projected = camera.project(&Vec3::new(x, y, 25.0)).unwrap();
+
+        assert_eq!((projected.x, projected.y), flat.to_screen(&Vector::new(x, y)));
+        assert_eq!(projected.scale, 1.0);
+    }
+}
+
+#[test]
+fn camera_turns_and_tilts() {
+    let mut camera = Camera3::new((800, 600));
+    camera.target = Vec3::zero();
+
+    // de lado, o lado do plano voltado para a camera (z negativo) fica em cima
+    camera.rotate(0.0, FRAC_PI_2);
+    let up = camera.project(&Vec3::new(0.0, 0.0, -100.0)).unwrap();
+    assert_eq!((up.x, up.y), (400, 200));
+
+    // o angulo fica preso em ±90 graus
+    camera.rotate(0.0, 1.0);
+    assert_eq!(camera.pitch(), FRAC_PI_2);
+
+    let mut camera = Camera3::new((800, 600));
+    camera.target = Vec3::zero();
+    camera.rotate(PI / 2.0, 0.0);
+
+    // girar a camera gira a cena para o outro lado
+    let x = camera.project(&Vec3::new(100.0, 0.0, 0.0)).unwrap();
+    assert_eq!((x.x, x.y), (400, 200));
+}
+
+#[test]
+fn perspective_shrinks_distant_bodies() {
+    let mut camera = Camera3::new((800, 600));
+    camera.target = Vec3::zero();
+    camera.set_projection(Projection::Perspective);
+    camera.set_distance(1000.0);
+
+    let near = camera.project(&Vec3::new(100.0, 0.0, -500.0)).unwrap();
+    let middle = camera.project(&Vec3::new(100.0, 0.0, 0.0)).unwrap();
+    let far = camera.project(&Vec3::new(100.0, 0.0, 1000.0)).unwrap();
+
+    assert_eq!(middle.scale, 1.0);
+    assert_eq!(near.scale, 2.0);
+    assert_eq!(far.scale, 0.5);
+    assert_eq!((near.x, middle.x, far.x), (600, 500, 450));
+    assert!(near.depth < middle.depth && middle.depth < far.depth);
+
+    // atras do olho nao aparece
+    assert!(camera.project(&Vec3::new(0.0, 0.0, -1000.0)).is_none());
+
+    camera.set_projection(Projection::Orthographic);
+    assert_eq!(camera.project(&Vec3::new(100.0, 0.0, -500.0)).unwrap().scale, 1.0);
+}
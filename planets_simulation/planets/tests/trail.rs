@@ -0,0 +1,120 @@
+extern crate forces;
+extern crate planets;
+extern crate sdl2;
+
+use forces::Vector;
+use planets::scene::Scene;
+use planets::trail::Trail;
+use planets::{Planet, Planets};
+use sdl2::pixels::Color;
+
+fn steps(trail: &Trail) -> Vec<u64> {
+    trail.iter().map(|point| point.step).collect()
+}
+
+#[test]
+fn keeps_only_the_newest_points() {
+    let mut trail = Trail::new(Some(3), 0.0);
+
+    for i in 0..10 {
+        trail.push(Vector::new(i as f64, 0.0));
+    }
+
+    assert_eq!(trail.len(), 3);
+    assert_eq!(steps(&trail), vec![8, 9, 10]);
+    assert_eq!(trail.last().unwrap().position, Vector::new(9.0, 0.0));
+
+    trail.set_length(Some(1));
+    assert_eq!(steps(&trail), vec![10]);
+}
+
+#[test]
+fn skips_points_closer_than_spacing() {
+    let mut trail = Trail::new(None, 1.0);
+
+    for i in 0..10 {
+        trail.push(Vector::new(i as f64 * 0.4, 0.0));
+    }
+
+    // 0.0, 1.2, 2.4, 3.6 ficam; os outros estao a menos de 1 do anterior
+    assert_eq!(steps(&trail), vec![1, 4, 7, 10]);
+}
+
+#[test]
+fn clear_keeps_counting_steps() {
+    let mut trail = Trail::default();
+    trail.push(Vector::new(0.0, 0.0));
+    trail.push(Vector::new(1.0, 0.0));
+    trail.clear();
+
+    assert!(trail.is_empty());
+
+    trail.push(Vector::new(2.0, 0.0));
+    assert_eq!(steps(&trail), vec![3]);
+}
+
+#[test]
+fn start_at_numbers_points_from_the_given_step() {
+    let mut trail = Trail::default();
+    trail.push(Vector::new(0.0, 0.0));
+    trail.start_at(40);
+
+    assert!(trail.is_empty());
+
+    trail.push(Vector::new(1.0, 0.0));
+    assert_eq!(steps(&trail), vec![41]);
+}
+
+#[test]
+fn planets_apply_trail_settings_to_every_body() {
+    let sun = Planet::new(Vector::new(0.0, 0.0), Vector::new(0.0, 0.0), 10.0, 1.0, Color::RGB(255, 255, 0));
+    let mut earth = Planet::new(Vector::new(100.0, 0.0), Vector::new(0.0, 0.0), 2.0, 1.0e-3,
+                                Color::RGB(0, 0, 255));
+    earth.orbit_velocity(&sun);
+
+    assert_eq!(earth.trail_color(), Color::RGB(0, 0, 255));
+
+    let mut planets = Planets::new();
+    planets.set_trails(Some(50), 0.0);
+    planets.add_planet(sun);
+    let earth = planets.add_planet(earth);
+
+    planets.run(200);
+    assert_eq!(planets.get(earth).unwrap().trajectory().len(), 50);
+
+    planets.set_trails(Some(10), 0.0);
+    assert_eq!(planets.get(earth).unwrap().trajectory().len(), 10);
+
+    planets.clear_trails();
+    assert!(planets.planets().iter().all(|planet| planet.trajectory().is_empty()));
+}
+
+#[test]
+fn scene_sets_trails_and_colors() {
+    let scene = Scene::parse(r#"
+        [trails]
+        length = 0
+        spacing = 5.0
+
+        [[bodies]]
+        name = "sun"
+        mass = 1.0
+        radius = 10.0
+        color = [255, 255, 0]
+        position = [0.0, 0.0]
+
+        [[bodies]]
+        name = "earth"
+        mass = 1.0e-5
+        radius = 2.0
+        trail_color = [10, 20, 30]
+        position = [100.0, 0.0]
+        orbit = { around = "sun" }
+    "#).unwrap();
+
+    let planets = scene.build().unwrap();
+
+    assert_eq!(planets.trails(), (None, 5.0));
+    assert_eq!(planets.planets()[0].trail_color(), Color::RGB(255, 255, 0));
+    assert_eq!(planets.planets()[1].trail_color(), Color::RGB(10, 20, 30));
+}
@@ -0,0 +1,183 @@
+use game_loop::{controls, GameLoop};
+use planets::camera::Camera;
+use planets::gravity::Gravity;
+use planets::{Planet, Planets};
+
+use sdl2::event::Event;
+use sdl2::keyboard::{Keycode, Mod};
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+
+// velocidade dada por unidade de distancia arrastada
+static VELOCITY_PER_UNIT: f64 = 0.01;
+// quanto o mouse anda antes de um clique virar arrasto
+static DRAG_THRESHOLD: i32 = 3;
+static SPAWN_MASS: f64 = 1.0e-5;
+static SPAWN_RADIUS: f64 = 8.0;
+static TIME_STEP: f64 = 2.0;
+static GRAVITY_STEP: f64 = 1.25;
+static PALETTE: [Color; 6] = [
+    Color::RGB(100, 200, 255),
+    Color::RGB(255, 150, 50),
+    Color::RGB(150, 255, 150),
+    Color::RGB(255, 100, 200),
+    Color::RGB(200, 200, 100),
+    Color::RGB(180, 130, 255),
+];
+
+/// Mouse and keyboard editing of a running system:
+///
+/// - left drag pans the view; left click on a planet follows it and on
+///   empty space stops following
+/// - shift + left drag from empty space spawns a planet, the drag giving
+///   its velocity; a click without dragging spawns nothing
+/// - right click deletes a planet
+/// - `=` / `-` double and halve the timestep
+/// - G / shift+G scale the gravitational constant up and down
+///
+/// Pausing, stepping and the time scale belong to the loop; see
+/// `game_loop::controls`.
+pub struct Editor {
+    drag: Option<Drag>,
+    shift: bool,
+    spawned: usize,
+}
+
+// botao esquerdo apertado: vira clique, arrasto da vista ou novo corpo
+struct Drag {
+    // onde comecou e onde o mouse estava no ultimo movimento, em pixels
+    start: (i32, i32),
+    end: (i32, i32),
+    // ja passou de DRAG_THRESHOLD
+    moved: bool,
+    spawning: bool,
+}
+
+impl Editor {
+    pub fn new() -> Self {
+        Self {
+            drag: None,
+            shift: false,
+            spawned: 0,
+        }
+    }
+
+    /// Returns false for events the editor does not use.
+    pub fn handle(&mut self, event: &Event, planets: &mut Planets, camera: &mut Camera) -> bool {
+        match *event {
+            Event::KeyDown { keycode: Some(Keycode::Equals | Keycode::KpPlus), .. } => {
+                planets.set_timestep(planets.timestep() * TIME_STEP);
+            },
+            Event::KeyDown { keycode: Some(Keycode::Minus | Keycode::KpMinus), .. } => {
+                planets.set_timestep(planets.timestep() / TIME_STEP);
+            },
+            Event::KeyDown { keycode: Some(Keycode::G), keymod, .. } => {
+                let factor = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    1.0 / GRAVITY_STEP
+                } else {
+                    GRAVITY_STEP
+                };
+
+                let gravity = planets.gravity();
+                planets.set_gravity(Gravity { constant: gravity.constant * factor, ..gravity });
+                restart_tracking(planets);
+            },
+            Event::KeyDown { keycode: Some(Keycode::LShift | Keycode::RShift), .. } => {
+                self.shift = true;
+                return false;
+            },
+            Event::KeyUp { keycode: Some(Keycode::LShift | Keycode::RShift), .. } => {
+                self.shift = false;
+                return false;
+            },
+            Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+                self.drag = Some(Drag {
+                    start: (x, y),
+                    end: (x, y),
+                    moved: false,
+                    spawning: self.shift && camera.pick(planets, x, y).is_none(),
+                });
+            },
+            Event::MouseMotion { x, y, .. } => {
+                let drag = match &mut self.drag {
+                    Some(drag) => drag,
+                    None => return false,
+                };
+
+                if !drag.moved && ((x - drag.start.0).abs() > DRAG_THRESHOLD ||
+                                   (y - drag.start.1).abs() > DRAG_THRESHOLD) {
+                    // a vista alcanca o que o mouse andou antes do limite
+                    drag.moved = true;
+                    drag.end = drag.start;
+                }
+
+                if drag.moved && !drag.spawning {
+                    camera.pan(x - drag.end.0, y - drag.end.1);
+                }
+
+                drag.end = (x, y);
+            },
+            Event::MouseButtonUp { mouse_btn: MouseButton::Left, x, y, .. } => {
+                match self.drag.take() {
+                    Some(Drag { moved: false, .. }) => camera.follow(camera.pick(planets, x, y)),
+                    Some(Drag { start, spawning: true, .. }) => {
+                        self.spawn_planet(start, (x, y), planets, camera);
+                    },
+                    _ => {},
+                }
+            },
+            Event::MouseButtonDown { mouse_btn: MouseButton::Right, x, y, .. } => {
+                if let Some(id) = camera.pick(planets, x, y) {
+                    planets.remove_planet(id);
+                    restart_tracking(planets);
+                }
+            },
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn spawn_planet(&mut self, start: (i32, i32), end: (i32, i32), planets: &mut Planets, camera: &Camera) {
+        let position = camera.to_world(start.0, start.1);
+        let drag = &camera.to_world(end.0, end.1) - &position;
+        let mut velocity = &drag * VELOCITY_PER_UNIT;
+
+        // seguindo um corpo, a velocidade e relativa a ele
+        if let Some(followed) = camera.following().and_then(|id| planets.get(id)) {
+            velocity = &velocity + &followed.velocity;
+        }
+
+        let color = PALETTE[self.spawned % PALETTE.len()];
+        self.spawned += 1;
+
+        planets.add_planet(Planet::new(position, velocity, SPAWN_RADIUS, SPAWN_MASS, color));
+        restart_tracking(planets);
+    }
+
+    /// Draws the planet being placed and its velocity.
+    pub fn draw(&self, canvas: &mut WindowCanvas, camera: &Camera) {
+        if let Some(Drag { start, end, moved: true, spawning: true }) = self.drag {
+            let color = PALETTE[self.spawned % PALETTE.len()];
+            let radius = camera.scale(SPAWN_RADIUS).max(1.0) as i32;
+
+            canvas.set_draw_color(color);
+            canvas.draw_line(start, end).unwrap();
+            canvas.draw_rect(sdl2::rect::Rect::new(start.0 - radius, start.1 - radius,
+                                                   2 * radius as u32, 2 * radius as u32)).unwrap();
+        }
+    }
+
+    pub fn status(&self, planets: &Planets, game_loop: &GameLoop) -> String {
+        format!("{}  DT {}  G {}  BODIES {}", controls::status(game_loop),
+                planets.timestep(), planets.gravity().constant, planets.planets().len())
+    }
+}
+
+// editar o sistema muda a energia; a deriva volta a contar daqui
+fn restart_tracking(planets: &mut Planets) {
+    if planets.latest_diagnostics().is_some() {
+        planets.set_tracking(true);
+    }
+}
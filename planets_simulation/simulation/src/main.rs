@@ -1,17 +1,251 @@
+extern crate game_loop;
 extern crate planets;
-extern crate forces;
 extern crate sdl2;
 
+mod editor;
+mod options;
+mod space;
+mod text;
+
 use planets::*;
-use forces::*;
+use planets::camera::{Camera, RotatingFrame};
+use planets::export;
+use planets::lagrange::RestrictedThreeBody;
+use planets::scene::{Scene, SceneError};
+
+use editor::Editor;
+use game_loop::{controls, GameLoop};
+use options::{Headless, Options};
 
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
 use sdl2::pixels::Color;
-use std::time::Duration;
+use sdl2::render::WindowCanvas;
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::process;
 
 static CANVAS_COLOR: Color = Color::RGB(0, 0, 0);
 static CANVAS_SIZE: (u32, u32) =  (800, 600);
+static OVERLAY_COLOR: Color = Color::RGB(200, 200, 200);
+static ZOOM_STEP: f64 = 1.1;
+static UPDATES_PER_SECOND: f64 = 120.0;
+static LAGRANGE_COLOR: Color = Color::RGB(255, 80, 80);
+// tons das curvas de velocidade zero de L1, L2 e L3
+static CONTOUR_COLORS: [Color; 3] = [
+    Color::RGB(90, 90, 200),
+    Color::RGB(70, 140, 200),
+    Color::RGB(60, 180, 160),
+];
+
+// energia, momento e deriva desde o inicio, no canto da janela
+fn draw_diagnostics(canvas: &mut WindowCanvas, planets: &Planets) {
+    let (now, drift) = match (planets.latest_diagnostics(), planets.drift()) {
+        (Some(now), Some(drift)) => (now, drift),
+        _ => return,
+    };
+
+    let lines = [
+        format!("E  {:+.4e}  DRIFT {:.2e}", now.total_energy(), drift.energy),
+        format!("KE {:+.4e}  PE {:+.4e}", now.kinetic_energy, now.potential_energy),
+        format!("P  ({:+.3e}, {:+.3e})  DRIFT {:.2e}", now.momentum.x, now.momentum.y, drift.momentum),
+        format!("L  {:+.4e}  DRIFT {:.2e}", now.angular_momentum, drift.angular_momentum),
+        format!("COM ({:.1}, {:.1})", now.center_of_mass.x, now.center_of_mass.y),
+    ];
+
+    text::draw_lines(canvas, &lines, 10, 10, 2, OVERLAY_COLOR);
+}
+
+// elementos orbitais de cada corpo em volta do mais pesado, ao lado dele
+fn draw_elements(canvas: &mut WindowCanvas, planets: &Planets, camera: &Camera) {
+    let primary = match planets.planets()
+        .iter()
+        .max_by(|a, b| a.mass().total_cmp(&b.mass()))
+    {
+        Some(primary) => primary,
+        None => return,
+    };
+
+    let g = planets.gravity().constant;
+
+    for planet in planets.planets() {
+        if planet.id() == primary.id() {
+            continue;
+        }
+
+        let elements = planet.elements(primary, g);
+        let period = elements.period(g * (primary.mass() + planet.mass()))
+            .map(|period| format!("{:.0}", period))
+            .unwrap_or_else(|| "-".to_string());
+
+        let lines = [
+            format!("A {:.1} E {:.3}", elements.semi_major_axis, elements.eccentricity),
+            format!("W {:.0} V {:.0} T {}", elements.argument_of_periapsis.to_degrees(),
+                    elements.true_anomaly.to_degrees(), period),
+        ];
+
+        let (x, y) = camera.to_screen(&planet.position);
+        let radius = camera.scale(planet.radius()) as i32;
+        let (x, y) = (x + radius + 4, y - radius);
+
+        text::draw_lines(canvas, &lines, x, y, 1, OVERLAY_COLOR);
+    }
+}
+
+// em 3D so mede a deriva, sem exportar
+fn run_headless_3d(scene: &Scene, options: &Headless) -> Result<(), String> {
+    if options.export.is_some() {
+        return Err("--export only works with 2D scenes".to_string());
+    }
+
+    let mut planets = scene.build_3d().map_err(|err| err.to_string())?;
+
+    planets.set_tracking(true);
+    planets.run(options.steps);
+
+    let drift = planets.drift().unwrap();
+    println!("{} steps, energy drift {:.3e}, momentum drift {:.3e}, angular momentum drift {:.3e}",
+             options.steps, drift.energy, drift.momentum, drift.angular_momentum);
+
+    Ok(())
+}
+
+// roda sem janela e grava a trajetoria de cada corpo em `export`
+fn run_headless(scene: &Scene, options: &Headless) -> Result<(), String> {
+    let mut planets = scene.build().map_err(|err| err.to_string())?;
+
+    // exporta a trajetoria inteira, nao so o rastro visivel
+    planets.set_trails(None, planets.trails().1);
+
+    // `build` adiciona os corpos na ordem da cena
+    let bodies: Vec<_> = scene.bodies.iter()
+        .zip(planets.planets().iter().map(|planet| planet.id()))
+        .map(|(body, id)| (body.name.clone(), id.unwrap()))
+        .collect();
+
+    planets.set_tracking(true);
+    planets.run(options.steps);
+
+    let drift = planets.drift().unwrap();
+    println!("{} steps, energy drift {:.3e}, momentum drift {:.3e}, angular momentum drift {:.3e}",
+             options.steps, drift.energy, drift.momentum, drift.angular_momentum);
+
+    let directory = match &options.export {
+        Some(directory) => directory,
+        None => return Ok(()),
+    };
+
+    fs::create_dir_all(directory)
+        .map_err(|err| format!("could not create {}: {}", directory.display(), err))?;
+
+    let mut files = HashSet::new();
+
+    for (name, id) in bodies {
+        let planet = match planets.get(id) {
+            Some(planet) => planet,
+            None => {
+                println!("{} merged into another body, not exported", name);
+                continue;
+            },
+        };
+
+        // nomes diferentes podem virar o mesmo arquivo depois de limpos
+        let mut file = export::file_name(&name);
+
+        while !files.insert(file.clone()) {
+            file.push('_');
+        }
+
+        let path = directory.join(format!("{}.{}", file, options.format.extension()));
+
+        export::export(planet, planets.timestep(), &path, options.format)
+            .map_err(|err| format!("could not write {}: {}", path.display(), err))?;
+    }
+
+    Ok(())
+}
+
+// o mais pesado e o corpo seguido, ou o segundo mais pesado
+fn restricted_pair(planets: &Planets, camera: &Camera) -> Option<RestrictedThreeBody> {
+    let mut bodies: Vec<_> = planets.planets().iter().collect();
+    bodies.sort_by(|a, b| b.mass().total_cmp(&a.mass()));
+
+    let primary = bodies.first()?.id()?;
+    let secondary = camera.following()
+        .filter(|&id| id != primary)
+        .or_else(|| bodies.get(1)?.id())?;
+
+    planets.restricted(primary, secondary)
+}
+
+// pontos de Lagrange e curvas de velocidade zero com a constante de Jacobi
+// de L1, L2 e L3
+fn draw_lagrange(canvas: &mut WindowCanvas, restricted: &RestrictedThreeBody, camera: &Camera) {
+    let points = restricted.lagrange_points();
+
+    for (point, color) in points.iter().zip(CONTOUR_COLORS) {
+        let jacobi = 2.0 * restricted.pseudo_potential(point);
+        canvas.set_draw_color(color);
+
+        for (start, end) in restricted.zero_velocity_curves(jacobi, 2.0, 150) {
+            canvas.draw_line(camera.to_screen(&start), camera.to_screen(&end)).unwrap();
+        }
+    }
+
+    canvas.set_draw_color(LAGRANGE_COLOR);
+
+    for (i, point) in points.iter().enumerate() {
+        let (x, y) = camera.to_screen(point);
+
+        canvas.draw_line((x - 4, y), (x + 4, y)).unwrap();
+        canvas.draw_line((x, y - 4), (x, y + 4)).unwrap();
+        text::draw(canvas, &format!("L{}", i + 1), x + 6, y - 6, 1, LAGRANGE_COLOR);
+    }
+}
+
+// troca o referencial sem mudar o ponto no meio da tela
+fn set_frame(camera: &mut Camera, frame: Option<RotatingFrame>) {
+    let (width, height) = camera.viewport();
+    let middle = camera.to_world(width as i32 / 2, height as i32 / 2);
+
+    camera.center = match &frame {
+        Some(frame) => frame.to_frame(&middle, 0),
+        None => middle,
+    };
+    camera.set_frame(frame);
+}
+
+fn invalid_scene(err: SceneError) -> ! {
+    eprintln!("Invalid scene: {}", err);
+    process::exit(1);
+}
 
 fn main() {
+    let options = Options::new(env::args()).unwrap_or_else(|err| {
+        eprintln!("{}\n{}", err, options::USAGE);
+        process::exit(1);
+    });
+
+    let scene = Scene::from_file(&options.scene).unwrap_or_else(|err| invalid_scene(err));
+
+    let in_space = options.space || scene.dimensions == 3;
+
+    if let Some(headless) = &options.headless {
+        let result = if in_space {
+            run_headless_3d(&scene, headless)
+        } else {
+            run_headless(&scene, headless)
+        };
+
+        if let Err(err) = result {
+            eprintln!("Application error: {}", err);
+            process::exit(1);
+        }
+
+        return;
+    }
+
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
     let window = video_subsystem
@@ -20,79 +254,121 @@ fn main() {
             .build()
             .unwrap();
 
+    let mut canvas = window.into_canvas().build().unwrap();
+    let mut event_pump = sdl_context.event_pump().unwrap();
 
-    let sun = Planet::new(
-              Vector::new(CANVAS_SIZE.0 as f64 / 2.0,
-                          CANVAS_SIZE.1 as f64 / 2.0),
-              Vector::new(0.0, 0.0),
-              50.0,
-              1.0,
-              Color::RGB(255, 255, 0));
-
-
-    let mut earth = Planet::new(
-              Vector::new(300.0, CANVAS_SIZE.1 as f64 / 2.0 + -50.0),
-              Vector::new(0.0, 0.0),
-              10.0,
-              1.0e-5,
-              Color::RGB(0, 255, 0));
-    earth.orbit_velocity(&sun);
-
-    let mut mars = Planet::new(
-              Vector::new(300.0, CANVAS_SIZE.1 as f64 / 2.0 + -80.0),
-              Vector::new(0.0, 0.0),
-              12.0,
-              1.5e-5,
-              Color::RGB(255, 0, 0));
-    mars.orbit_velocity(&sun);
-
-    let mut jupiter = Planet::new(
-              Vector::new(100.0, CANVAS_SIZE.1 as f64 / 2.0 + 20.0),
-              Vector::new(0.0, 0.0),
-              30.0,
-              2.0e-4,
-              Color::RGB(255, 255, 100));
-    jupiter.orbit_velocity(&sun);
-
-
-    let mut neptune = Planet::new(
-              Vector::new(700.0, CANVAS_SIZE.1 as f64 / 2.0 - 10.0),
-              Vector::new(0.0, 0.0),
-              18.0,
-              3.0e-5,
-              Color::RGB(255, 0, 0));
-    neptune.orbit_velocity(&sun);
-
-    let mut planets = Planets::new();
-    planets.add_planet(earth);
-    planets.add_planet(mars);
-    planets.add_planet(jupiter);
-    planets.add_planet(neptune);
+    if in_space {
+        let planets = scene.build_3d().unwrap_or_else(|err| invalid_scene(err));
+        space::run(planets, &mut canvas, &mut event_pump);
+        return;
+    }
 
-    let mut canvas = window.into_canvas().build().unwrap();
+    let mut planets = scene.build().unwrap_or_else(|err| invalid_scene(err));
+    planets.set_tracking(true);
+
+    // D liga e desliga o painel de diagnostico, O os elementos orbitais
+    let mut show_diagnostics = false;
+    let mut show_elements = false;
+
+    // T esconde os rastros, C apaga
+    let mut show_trails = true;
+
+    // F gira a vista junto com o par mais pesado (ou com o corpo seguido),
+    // L mostra os pontos de Lagrange do par
+    let mut rotating = false;
+    let mut show_lagrange = false;
+
+    // roda do mouse aproxima e R volta para a vista inicial; o resto do
+    // mouse fica com o editor
+    let mut camera = Camera::new(CANVAS_SIZE);
+    let mut editor = Editor::new();
+
+    // espaco pausa, N avanca um passo, [ e ] mudam a escala de tempo
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
 
-    let mut event_pump = sdl_context.event_pump().unwrap();
     'running: loop {
         for event in event_pump.poll_iter() {
+            if controls::handle(&mut game_loop, &event) || editor.handle(&event, &mut planets, &mut camera) {
+                continue;
+            }
+
             match event {
-                sdl2::event::Event::Quit { .. } => break 'running,
+                Event::Quit { .. } => break 'running,
+                Event::KeyDown { keycode: Some(Keycode::D), .. } => {
+                    show_diagnostics = !show_diagnostics;
+                },
+                Event::KeyDown { keycode: Some(Keycode::O), .. } => {
+                    show_elements = !show_elements;
+                },
+                Event::KeyDown { keycode: Some(Keycode::T), .. } => {
+                    show_trails = !show_trails;
+                },
+                Event::KeyDown { keycode: Some(Keycode::C), .. } => {
+                    planets.clear_trails();
+                },
+                Event::KeyDown { keycode: Some(Keycode::R), .. } => {
+                    camera = Camera::new(CANVAS_SIZE);
+                    rotating = false;
+                },
+                Event::KeyDown { keycode: Some(Keycode::F), .. } => {
+                    rotating = !rotating;
+
+                    let frame = restricted_pair(&planets, &camera)
+                        .filter(|_| rotating)
+                        .map(|restricted| restricted.frame(planets.timestep()));
+                    set_frame(&mut camera, frame);
+                },
+                Event::KeyDown { keycode: Some(Keycode::L), .. } => {
+                    show_lagrange = !show_lagrange;
+                },
+                Event::MouseWheel { precise_y, mouse_x, mouse_y, .. } => {
+                    camera.zoom_at(ZOOM_STEP.powf(precise_y as f64), mouse_x, mouse_y);
+                },
                 _ => {}
             }
         }
 
-        planets.apply_force_sun(&sun);
-        planets.apply_force_others();
-        planets.update_position();
+        for _ in 0..game_loop.tick().updates {
+            planets.step();
+        }
+
+        let restricted = restricted_pair(&planets, &camera);
+
+        if rotating {
+            camera.set_frame(restricted.as_ref().map(|restricted| restricted.frame(planets.timestep())));
+        }
+
+        camera.update(&planets);
 
         canvas.set_draw_color(CANVAS_COLOR);
         canvas.clear();
 
-        planets.draw_trajectory(&mut canvas);
-        planets.draw(&mut canvas);
-        sun.draw(&mut canvas);
+        if show_lagrange {
+            if let Some(restricted) = &restricted {
+                draw_lagrange(&mut canvas, restricted, &camera);
+            }
+        }
+
+        if show_trails {
+            planets.draw_trajectory(&mut canvas, &camera);
+        }
+
+        planets.draw(&mut canvas, &camera);
+        editor.draw(&mut canvas, &camera);
+
+        if show_diagnostics {
+            draw_diagnostics(&mut canvas, &planets);
+        }
+
+        if show_elements {
+            draw_elements(&mut canvas, &planets, &camera);
+        }
+
+        text::draw(&mut canvas, &editor.status(&planets, &game_loop), 10,
+                   CANVAS_SIZE.1 as i32 - 10 - text::line_height(2), 2, OVERLAY_COLOR);
 
         canvas.present();
-        ::std::thread::sleep(Duration::new(0, 1_000_000u32 / 60));
+        game_loop.wait();
     }
 
 }
@@ -0,0 +1,73 @@
+use std::env;
+use std::path::{Path, PathBuf};
+
+use planets::export::Format;
+
+pub static USAGE: &str = "usage: simulation [SCENE] [--3d] [--steps N [--export DIR] [--format csv|binary]]";
+
+/// Runs a fixed number of steps without opening a window.
+pub struct Headless {
+    pub steps: usize,
+    pub export: Option<PathBuf>,
+    pub format: Format,
+}
+
+pub struct Options {
+    pub scene: PathBuf,
+    /// Simulates in space even when the scene is 2D, without collisions.
+    pub space: bool,
+    pub headless: Option<Headless>,
+}
+
+impl Options {
+    pub fn new<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+        args.next();
+
+        let mut scene = None;
+        let mut steps = None;
+        let mut export = None;
+        let mut format = None;
+        let mut space = false;
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| args.next().ok_or_else(|| format!("{} needs a value", name));
+
+            match arg.as_str() {
+                "--steps" => {
+                    let count = value("--steps")?;
+                    steps = Some(count.parse().map_err(|_| format!("invalid step count `{}`", count))?);
+                },
+                "--export" => export = Some(PathBuf::from(value("--export")?)),
+                "--format" => format = Some(value("--format")?.parse()?),
+                "--3d" => space = true,
+                _ if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg)),
+                _ if scene.is_none() => scene = Some(PathBuf::from(arg)),
+                _ => return Err(format!("unexpected argument `{}`", arg)),
+            }
+        }
+
+        let headless = match steps {
+            Some(steps) => Some(Headless { steps, export, format: format.unwrap_or_default() }),
+            None if export.is_some() || format.is_some() => {
+                return Err("--export and --format need --steps".to_string());
+            },
+            None => None,
+        };
+
+        Ok(Options {
+            scene: scene.unwrap_or_else(default_scene),
+            space,
+            headless,
+        })
+    }
+}
+
+// `scenes/` ao lado do executavel ou acima dele (como em `target/debug`), para
+// nao depender de onde o programa foi chamado
+fn default_scene() -> PathBuf {
+    let scene = Path::new("scenes").join("solar_system.toml");
+
+    env::current_exe().ok()
+        .and_then(|exe| exe.ancestors().skip(1).map(|dir| dir.join(&scene)).find(|path| path.is_file()))
+        .unwrap_or(scene)
+}
@@ -0,0 +1,133 @@
+use forces::Vec3;
+use game_loop::{controls, GameLoop};
+use planets::projection::{Camera3, Projection};
+use planets::space::Planets3;
+
+use sdl2::EventPump;
+use sdl2::event::Event;
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::render::WindowCanvas;
+
+use crate::text;
+use crate::{CANVAS_COLOR, CANVAS_SIZE, OVERLAY_COLOR, UPDATES_PER_SECOND, ZOOM_STEP};
+
+// radianos por pixel arrastado
+static ROTATE_SPEED: f64 = 0.01;
+static AXIS_LENGTH: f64 = 100.0;
+static AXIS_COLORS: [Color; 3] = [
+    Color::RGB(160, 60, 60),
+    Color::RGB(60, 160, 60),
+    Color::RGB(60, 60, 160),
+];
+
+// eixos x, y e z saindo do alvo da camera, para ver a orientacao
+fn draw_axes(canvas: &mut WindowCanvas, camera: &Camera3) {
+    let axes = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)];
+
+    for (axis, color) in axes.iter().zip(AXIS_COLORS) {
+        let end = &camera.target + &(axis * AXIS_LENGTH);
+
+        if let (Some(start), Some(end)) = (camera.project(&camera.target), camera.project(&end)) {
+            canvas.set_draw_color(color);
+            canvas.draw_line((start.x, start.y), (end.x, end.y)).unwrap();
+        }
+    }
+}
+
+fn status(planets: &Planets3, camera: &Camera3, game_loop: &GameLoop) -> String {
+    let projection = match camera.projection() {
+        Projection::Orthographic => "ORTHO",
+        Projection::Perspective => "PERSP",
+    };
+
+    format!("{}  {}  YAW {:.0}  PITCH {:.0}  DT {}  BODIES {}",
+            controls::status(game_loop), projection,
+            camera.yaw().to_degrees().rem_euclid(360.0), camera.pitch().to_degrees(),
+            planets.timestep(), planets.planets().len())
+}
+
+/// Shows `planets` in space until the window is closed.
+///
+/// Dragging with the left button turns the camera and the wheel zooms. P
+/// switches between orthographic and perspective, R resets the view, T
+/// hides the trails and C clears them, D shows the energy drift; the loop
+/// keys of `game_loop::controls` work as in the plane.
+pub fn run(mut planets: Planets3, canvas: &mut WindowCanvas, event_pump: &mut EventPump) {
+    let initial_energy = planets.diagnostics().total_energy();
+
+    let mut camera = Camera3::new(CANVAS_SIZE);
+    let mut show_trails = true;
+    let mut show_energy = false;
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
+
+    'running: loop {
+        for event in event_pump.poll_iter() {
+            if controls::handle(&mut game_loop, &event) {
+                continue;
+            }
+
+            match event {
+                Event::Quit { .. } => break 'running,
+                Event::KeyDown { keycode: Some(Keycode::P), .. } => {
+                    camera.set_projection(match camera.projection() {
+                        Projection::Orthographic => Projection::Perspective,
+                        Projection::Perspective => Projection::Orthographic,
+                    });
+                },
+                Event::KeyDown { keycode: Some(Keycode::R), .. } => {
+                    let projection = camera.projection();
+                    camera = Camera3::new(CANVAS_SIZE);
+                    camera.set_projection(projection);
+                },
+                Event::KeyDown { keycode: Some(Keycode::T), .. } => {
+                    show_trails = !show_trails;
+                },
+                Event::KeyDown { keycode: Some(Keycode::C), .. } => {
+                    planets.clear_trails();
+                },
+                Event::KeyDown { keycode: Some(Keycode::D), .. } => {
+                    show_energy = !show_energy;
+                },
+                Event::MouseMotion { mousestate, xrel, yrel, .. }
+                    if mousestate.is_mouse_button_pressed(MouseButton::Left) =>
+                {
+                    camera.rotate(xrel as f64 * ROTATE_SPEED, yrel as f64 * ROTATE_SPEED);
+                },
+                Event::MouseWheel { precise_y, .. } => {
+                    camera.zoom_by(ZOOM_STEP.powf(precise_y as f64));
+                },
+                _ => {}
+            }
+        }
+
+        for _ in 0..game_loop.tick().updates {
+            planets.step();
+        }
+
+        canvas.set_draw_color(CANVAS_COLOR);
+        canvas.clear();
+
+        draw_axes(canvas, &camera);
+
+        if show_trails {
+            planets.draw_trajectory(canvas, &camera);
+        }
+
+        planets.draw(canvas, &camera);
+
+        if show_energy {
+            let energy = planets.diagnostics().total_energy();
+            let drift = ((energy - initial_energy) / initial_energy).abs();
+
+            text::draw(canvas, &format!("E  {:+.4e}  DRIFT {:.2e}", energy, drift), 10, 10, 2, OVERLAY_COLOR);
+        }
+
+        text::draw(canvas, &status(&planets, &camera, &game_loop), 10,
+                   CANVAS_SIZE.1 as i32 - 10 - text::line_height(2), 2, OVERLAY_COLOR);
+
+        canvas.present();
+        game_loop.wait();
+    }
+}
@@ -1,15 +1,16 @@
 #![allow(dead_code, unused_imports)]
 
 use features::*;
+use game_loop::{controls, GameLoop};
 use vectors::*;
 use std::ptr;
 use sdl2::render::Canvas;
 use sdl2::pixels::Color;
 use sdl2::keyboard::Keycode;
 use sdl2::event::Event;
-use std::time::Duration;
 use rand::Rng;
 
+static TITLE: &str = "Pong game";
 static CANVAS_SIZE: (u32, u32) = (800, 600);
 static CANVAS_COLOR: Color = Color::RGB(0, 0, 0);
 static BALL_POSITION: (f64, f64) = ((CANVAS_SIZE.0 / 2) as f64,
@@ -20,6 +21,7 @@ static BAR_L_POSITION: (f64, f64) = (0 as f64,
 static BAR_R_POSITION: (f64, f64) = ((CANVAS_SIZE.0 - 20) as f64,
                                     (CANVAS_SIZE.1 / 2 -40) as f64);
 static BAR_COLOR: Color = Color::RGB(0, 0, 255);
+static UPDATES_PER_SECOND: f64 = 240.0;
 static mut GAME_STATUS: GameStatus = Start;
 
 #[derive(PartialEq)]
@@ -35,7 +37,7 @@ fn create_context_and_canvas() -> (sdl2::Sdl, Canvas<sdl2::video::Window>) {
     let sdl_context = sdl2::init().unwrap();
     let video_subystem = sdl_context.video().unwrap();
     let window = video_subystem
-            .window("Pong game", CANVAS_SIZE.0, CANVAS_SIZE.1)
+            .window(TITLE, CANVAS_SIZE.0, CANVAS_SIZE.1)
             .position_centered()
             .build()
             .unwrap();
@@ -85,9 +87,15 @@ fn main() {
         vec![&mut l_bar as *mut Bar, &mut r_bar as *mut Bar],
     );
 
+    // espaco pausa, N avanca um passo, [ e ] mudam a escala de tempo
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
 
     'running: loop {
         for event in event_pump.poll_iter() {
+            if controls::handle(&mut game_loop, &event) {
+                continue;
+            }
+
             match event {
                 Event::Quit {..} => break 'running,
                 Event::KeyDown { keycode: Some(Keycode::Return), .. } => {
@@ -122,6 +130,13 @@ fn main() {
                 _ => {},
             }
         }
+
+        let frame = game_loop.tick();
+
+        if frame.measured {
+            controls::show_status(canvas.window_mut(), TITLE, &game_loop);
+        }
+
         let content1 = format!("SCORE: {}", l_bar.get_score());
         let content2 = format!("SCORE: {}", r_bar.get_score());
 
@@ -135,8 +150,15 @@ fn main() {
             match GAME_STATUS {
                 Start => start_game(&mut ball, &mut [&mut l_bar,
                                                      &mut r_bar]),
-                Running => run_game(&mut ball, &mut [&mut l_bar,
-                                                 &mut r_bar]),
+                Running => {
+                    for _ in 0..frame.updates {
+                        run_game(&mut ball, &mut [&mut l_bar, &mut r_bar]);
+
+                        if GAME_STATUS != Running {
+                            break;
+                        }
+                    }
+                },
                 End => {
                     end_game();
                 },
@@ -144,6 +166,7 @@ fn main() {
         }
 
         canvas.present();
+        game_loop.wait();
     }
 }
 
@@ -186,8 +209,6 @@ fn run_game
             }
         }
     }
-
-    ::std::thread::sleep(Duration::new(0, 1_000u32));
 }
 
 fn end_game() {
@@ -1,19 +1,21 @@
-use vectors::*;
+use forces::*;
+use game_loop::{controls, Frame, GameLoop};
 use spring::*;
 use rand::Rng;
 use sdl2::pixels::Color;
-use std::time::Duration;
 
+static TITLE: &str = "Spring Simulation";
 static CANVAS_SIZE: (u32, u32) = (800, 600);
 static CANVAS_COLOR: Color = Color::RGB(255, 255, 255);
 static SPRING_COLOR: Color = Color::RGB(0, 0, 0);
+static UPDATES_PER_SECOND: f64 = 240.0;
 
 fn main() {
     let sdl_context = sdl2::init().unwrap();
     let video_subsystem = sdl_context.video().unwrap();
 
     let window = video_subsystem
-        .window("Spring Simulation", CANVAS_SIZE.0, CANVAS_SIZE.1)
+        .window(TITLE, CANVAS_SIZE.0, CANVAS_SIZE.1)
         .position_centered()
         .build()
         .unwrap();
@@ -21,28 +23,64 @@ fn main() {
     let mut canvas = window.into_canvas().build().unwrap();
 
     let mut springsystem = create_random_springs(10);
+    // molas antes do ultimo passo, para desenhar entre ele e o atual
+    let mut previous = springsystem.springs.clone();
 
     let mut event_pump = sdl_context.event_pump().unwrap();
 
+    // espaco pausa, N avanca um passo, [ e ] mudam a escala de tempo
+    let mut game_loop = GameLoop::new(UPDATES_PER_SECOND);
+
     'running: loop {
         for event in event_pump.poll_iter() {
-            match event {
-                sdl2::event::Event::Quit {..} => break 'running,
-                _ => {}
+            if controls::handle(&mut game_loop, &event) {
+                continue;
+            }
+
+            if let sdl2::event::Event::Quit {..} = event {
+                break 'running;
             }
         }
 
+        let frame = game_loop.tick();
+
+        for _ in 0..frame.updates {
+            previous.clone_from(&springsystem.springs);
+            springsystem.update();
+        }
+
+        if frame.measured {
+            controls::show_status(canvas.window_mut(), TITLE, &game_loop);
+        }
+
         canvas.set_draw_color(CANVAS_COLOR);
         canvas.clear();
 
-        springsystem.update();
-        springsystem.draw(&mut canvas, SPRING_COLOR);
+        interpolated(&previous, &springsystem, &frame).draw(&mut canvas, SPRING_COLOR);
 
         canvas.present();
-        ::std::thread::sleep(Duration::new(0, 1_000_000u32));
+        game_loop.wait();
     }
 }
 
+// posicoes a `frame.alpha` do caminho entre o passo anterior e o atual, para
+// o movimento nao tremer quando os quadros nao coincidem com os passos
+fn interpolated(previous: &[Spring], current: &SpringSystem, frame: &Frame) -> SpringSystem {
+    let between = |before: &Vector, now: &Vector| {
+        Vector::new(frame.interpolate(before.x, now.x), frame.interpolate(before.y, now.y))
+    };
+
+    SpringSystem {
+        springs: previous.iter()
+            .zip(&current.springs)
+            .map(|(before, now)| Spring {
+                origin: between(&before.origin, &now.origin),
+                end: between(&before.end, &now.end),
+                ..now.clone()
+            })
+            .collect(),
+    }
+}
 
 fn create_random_springs(num: usize) -> SpringSystem {
     let mut rng = rand::thread_rng();
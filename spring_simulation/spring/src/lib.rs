@@ -1,4 +1,4 @@
-use vectors::*;
+use forces::*;
 use sdl2::pixels::Color;
 use sdl2::render::Canvas;
 use sdl2::rect::Rect;
@@ -111,7 +111,11 @@ impl Spring {
             return;
         }
 
-        let direction = (&self.end - &self.origin).normalize();
+        // com as duas pontas no mesmo lugar a forca nao tem direcao
+        let direction = match (&self.end - &self.origin).try_normalize() {
+            Some(direction) => direction,
+            None => return,
+        };
         let dx = self.current_length - self.rest_length;
 
         // |force|= k * |dx|
@@ -119,7 +123,7 @@ impl Spring {
 
         let elastic_force = (-self.k * dx) * &direction;
 
-        let force_origin = elastic_force.inverse();
+        let force_origin = -&elastic_force;
         let force_end = elastic_force;
 
         let acceleration_origin = &force_origin * ( 1.0 / self.origin_mass );
@@ -223,6 +227,12 @@ impl SpringSystem {
     }
 }
 
+impl Default for SpringSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 pub struct SpringPendulum {
     pub origin: Vector,
     pub end: Vector,
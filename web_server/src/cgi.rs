@@ -0,0 +1,280 @@
+use std::env;
+use std::io;
+use std::io::prelude::*;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::ThreadPool;
+use crate::http::{self, Request, Response};
+use crate::logger::Logger;
+
+const SERVER_SOFTWARE: &str = concat!("web_server/", env!("CARGO_PKG_VERSION"));
+
+/// Runs a local program per request following CGI/1.1 (RFC 3875).
+pub struct Cgi {
+	program: PathBuf,
+	args: Vec<String>,
+	working_dir: Option<PathBuf>,
+	timeout: Duration,
+}
+
+/// Where a request was routed, used to fill `SCRIPT_NAME` and `PATH_INFO`.
+pub struct Target<'a> {
+	pub script_name: &'a str,
+	pub path_info: &'a str,
+	pub query: &'a str,
+	pub peer: Option<SocketAddr>,
+}
+
+impl Cgi {
+	pub fn new(program: PathBuf) -> Self {
+		Self {
+			program,
+			args: Vec::new(),
+			working_dir: None,
+			timeout: Duration::from_secs(10),
+		}
+	}
+
+	pub fn set_args(&mut self, args: Vec<String>) {
+		self.args = args;
+	}
+
+	pub fn set_working_dir(&mut self, dir: PathBuf) {
+		self.working_dir = Some(dir);
+	}
+
+	/// The script is killed if it has not exited in time, even when it
+	/// already closed its output.
+	pub fn set_timeout(&mut self, timeout: Duration) {
+		self.timeout = timeout;
+	}
+
+	/// Runs the program for `request` and turns its output into a response.
+	///
+	/// Writing the body to stdin and reading stdout happen on `pool` so this
+	/// thread can give up after the timeout. Answers `500` if the program
+	/// cannot start, `502` for malformed output and `504` on timeout; the
+	/// reason is written to `logger`.
+	pub fn execute(
+		&self,
+		pool: &ThreadPool,
+		request: &Request,
+		target: &Target,
+		logger: &Logger,
+	) -> Response
+	{
+		let deadline = Instant::now() + self.timeout;
+		let mut command = Command::new(&self.program);
+
+		command.args(&self.args)
+			.env_clear()
+			.envs(variables(request, target))
+			.stdin(Stdio::piped())
+			.stdout(Stdio::piped())
+			.stderr(Stdio::inherit());
+
+		if let Some(path) = env::var_os("PATH") {
+			command.env("PATH", path);
+		}
+
+		if let Some(dir) = &self.working_dir {
+			command.current_dir(dir);
+		}
+
+		let mut child = match command.spawn() {
+			Ok(child) => child,
+			Err(e) => {
+				logger.error(&format!("could not start {}: {}", self.program.display(), e));
+				return Response::new(500, "Internal Server Error");
+			},
+		};
+
+		let mut stdin = child.stdin.take().unwrap();
+		let stdout = child.stdout.take().unwrap();
+		let body = request.body.clone();
+
+		// escrever e ler em jobs separados evita travar se o script
+		// comecar a responder antes de consumir todo o body
+		pool.execute(move || {
+			let _ = stdin.write_all(&body);
+		});
+
+		let (sender, receiver) = mpsc::channel();
+
+		// um byte alem do limite basta para saber que passou dele
+		pool.execute(move || {
+			let mut output = Vec::new();
+			let result = stdout.take(http::MAX_BODY_SIZE as u64 + 1)
+				.read_to_end(&mut output)
+				.map(|_| output);
+			let _ = sender.send(result);
+		});
+
+		let output = match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+			Ok(Ok(output)) if output.len() > http::MAX_BODY_SIZE => {
+				kill(&mut child);
+				logger.error(&format!("{} wrote more than {} bytes", self.program.display(),
+				                      http::MAX_BODY_SIZE));
+				return Response::new(502, "Bad Gateway");
+			},
+			Ok(output) => output,
+			Err(_) => return self.timed_out(&mut child, logger),
+		};
+
+		// o script pode fechar o stdout e continuar rodando
+		let status = loop {
+			match child.try_wait() {
+				Ok(Some(status)) => break Ok(status),
+				Ok(None) if Instant::now() >= deadline => return self.timed_out(&mut child, logger),
+				Ok(None) => thread::sleep(Duration::from_millis(5)),
+				Err(e) => break Err(e),
+			}
+		};
+
+		match output {
+			Ok(output) if !output.is_empty() => parse_output(&output).unwrap_or_else(|e| {
+				logger.error(&format!("invalid output from {}: {}", self.program.display(), e));
+				Response::new(502, "Bad Gateway")
+			}),
+			_ => {
+				logger.error(&format!("{} produced no output ({:?})", self.program.display(), status));
+				Response::new(502, "Bad Gateway")
+			},
+		}
+	}
+
+	fn timed_out(&self, child: &mut Child, logger: &Logger) -> Response {
+		kill(child);
+
+		logger.error(&format!("{} timed out after {:?}", self.program.display(), self.timeout));
+		Response::new(504, "Gateway Timeout")
+	}
+}
+
+fn kill(child: &mut Child) {
+	let _ = child.kill();
+	let _ = child.wait();
+}
+
+fn variables(request: &Request, target: &Target) -> Vec<(String, String)> {
+	let (server_name, server_port) = http::split_host(request.header("Host").unwrap_or(""));
+
+	let mut variables = vec![
+		("GATEWAY_INTERFACE", "CGI/1.1".to_string()),
+		("SERVER_SOFTWARE", SERVER_SOFTWARE.to_string()),
+		("SERVER_PROTOCOL", request.version.clone()),
+		("SERVER_NAME", server_name.to_string()),
+		("SERVER_PORT", server_port.to_string()),
+		("REQUEST_METHOD", request.method.clone()),
+		("SCRIPT_NAME", target.script_name.to_string()),
+		("PATH_INFO", target.path_info.to_string()),
+		("QUERY_STRING", target.query.to_string()),
+	];
+
+	if let Some(peer) = target.peer {
+		variables.push(("REMOTE_ADDR", peer.ip().to_string()));
+		variables.push(("REMOTE_PORT", peer.port().to_string()));
+	}
+
+	if !request.body.is_empty() {
+		variables.push(("CONTENT_LENGTH", request.body.len().to_string()));
+	}
+
+	if let Some(content_type) = request.header("Content-Type") {
+		variables.push(("CONTENT_TYPE", content_type.to_string()));
+	}
+
+	let mut variables: Vec<(String, String)> = variables.into_iter()
+		.map(|(name, value)| (name.to_string(), value))
+		.collect();
+
+	// os outros headers viram HTTP_NOME_DO_HEADER, menos Proxy: virando
+	// HTTP_PROXY o cliente escolheria o proxy do script (httpoxy)
+	for (name, value) in &request.headers {
+		if name.eq_ignore_ascii_case("Content-Length") ||
+		   name.eq_ignore_ascii_case("Content-Type") ||
+		   name.eq_ignore_ascii_case("Proxy") {
+			continue;
+		}
+
+		let name = format!("HTTP_{}", name.to_ascii_uppercase().replace('-', "_"));
+		variables.push((name, value.clone()));
+	}
+
+	variables
+}
+
+/// Parses a CGI header block followed by the document body.
+///
+/// `Status` sets the status line and must be a code from 100 to 599; a
+/// `Location` without `Status` is a `302 Found` redirect. Anything else
+/// must at least set `Content-Type`.
+pub fn parse_output(output: &[u8]) -> io::Result<Response> {
+	let (head, body) = split_head(output)
+		.ok_or_else(|| invalid("missing blank line after headers"))?;
+
+	let head = String::from_utf8(head.to_vec()).map_err(|_| invalid("headers are not UTF-8"))?;
+
+	let mut response = Response::new(200, "OK");
+	let mut status = None;
+
+	for line in head.lines() {
+		let (name, value) = line.split_once(':')
+			.ok_or_else(|| invalid("malformed header line"))?;
+		let (name, value) = (name.trim(), value.trim());
+
+		if name.eq_ignore_ascii_case("Status") {
+			let (code, reason) = value.split_once(' ').unwrap_or((value, ""));
+			let code = code.parse()
+				.ok()
+				.filter(|code| (100..=599).contains(code))
+				.ok_or_else(|| invalid("invalid Status header"))?;
+
+			status = Some((code, reason.to_string()));
+		} else if !name.eq_ignore_ascii_case("Content-Length") &&
+		          !name.eq_ignore_ascii_case("Connection") {
+			response.headers.push((name.to_string(), value.to_string()));
+		}
+	}
+
+	match status {
+		Some((code, reason)) => {
+			response.status = code;
+			response.reason = reason;
+		},
+		None if response.header("Location").is_some() => {
+			response.status = 302;
+			response.reason = "Found".to_string();
+		},
+		None if response.header("Content-Type").is_none() => {
+			return Err(invalid("missing Content-Type"));
+		},
+		None => {},
+	}
+
+	response.body = body.to_vec();
+
+	Ok(response)
+}
+
+// aceita tanto LF LF quanto CRLF CRLF como fim dos headers
+fn split_head(output: &[u8]) -> Option<(&[u8], &[u8])> {
+	(0..output.len()).find_map(|i| {
+		if output[i..].starts_with(b"\r\n\r\n") {
+			Some((&output[..i], &output[i + 4..]))
+		} else if output[i..].starts_with(b"\n\n") {
+			Some((&output[..i], &output[i + 2..]))
+		} else {
+			None
+		}
+	})
+}
+
+fn invalid(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,58 @@
+use std::io;
+use std::io::prelude::*;
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use crate::http::{Request, Response};
+
+/// Minimal HTTP/1.1 client: one request per connection.
+pub struct Client {
+	timeout: Duration,
+}
+
+impl Client {
+	pub fn new() -> Self {
+		Self {
+			timeout: Duration::from_secs(10),
+		}
+	}
+
+	/// Connect and read/write timeout for every request.
+	pub fn set_timeout(&mut self, timeout: Duration) {
+		self.timeout = timeout;
+	}
+
+	pub fn get(&self, address: SocketAddr, path: &str) -> io::Result<Response> {
+		self.request(address, Request::new("GET", path))
+	}
+
+	/// Sends `request` to `address`, filling in `Host` and `Connection: close`
+	/// when they are missing.
+	pub fn request(&self, address: SocketAddr, mut request: Request) -> io::Result<Response> {
+		if request.header("Host").is_none() {
+			request.headers.push(("Host".to_string(), address.to_string()));
+		}
+
+		if request.header("Connection").is_none() {
+			request.headers.push(("Connection".to_string(), "close".to_string()));
+		}
+
+		let mut stream = TcpStream::connect_timeout(&address, self.timeout)?;
+		stream.set_read_timeout(Some(self.timeout))?;
+		stream.set_write_timeout(Some(self.timeout))?;
+
+		send(&mut stream, &request)
+	}
+}
+
+impl Default for Client {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// Writes `request` on any stream and reads back the response.
+pub fn send<S: Read + Write>(stream: &mut S, request: &Request) -> io::Result<Response> {
+	request.write_to(stream)?;
+	Response::read_from(stream)
+}
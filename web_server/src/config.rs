@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::logger::Level;
+use crate::proxy::Balancing;
+
+#[derive(Debug)]
+pub enum ConfigError {
+	Io(PathBuf, io::Error),
+	Bind(SocketAddr, io::Error),
+	Parse(String),
+	Invalid {
+		field: String,
+		message: String,
+	},
+}
+
+impl ConfigError {
+	pub(crate) fn invalid(field: String, message: String) -> Self {
+		ConfigError::Invalid { field, message }
+	}
+}
+
+impl fmt::Display for ConfigError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			ConfigError::Io(path, error) => write!(f, "could not read {}: {}", path.display(), error),
+			ConfigError::Bind(address, error) => write!(f, "could not listen on {}: {}", address, error),
+			ConfigError::Parse(message) => write!(f, "{}", message),
+			ConfigError::Invalid { field, message } => write!(f, "{}: {}", field, message),
+		}
+	}
+}
+
+impl error::Error for ConfigError {}
+
+/// Server configuration, usually read from a TOML file.
+///
+/// ```toml
+/// threads = 4
+///
+/// [[listeners]]
+/// address = "0.0.0.0:7878"
+///
+/// [[hosts]]
+/// root = "."
+/// not_found = "404.html"
+///
+/// [[hosts.routes]]
+/// path = "/"
+/// file = "hello.html"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+	pub listeners: Vec<Listener>,
+	#[serde(default = "default_threads")]
+	pub threads: usize,
+	#[serde(default)]
+	pub logging: Logging,
+	pub hosts: Vec<Host>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Listener {
+	pub address: SocketAddr,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Logging {
+	#[serde(default)]
+	pub level: Level,
+	/// Appends to this file instead of writing to stderr.
+	pub file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Host {
+	/// Values of the `Host` header served by this host; an empty list makes
+	/// it the default for requests no other host matches.
+	#[serde(default)]
+	pub names: Vec<String>,
+	#[serde(default = "default_root")]
+	pub root: PathBuf,
+	pub not_found: Option<PathBuf>,
+	#[serde(default)]
+	pub routes: Vec<Route>,
+}
+
+/// Maps a request path to exactly one of `file`, `dir`, `proxy`,
+/// `websocket` or `cgi`.
+///
+/// A path ending in `*` (e.g. `/static/*`) matches every request starting
+/// with what comes before it, any other path must match exactly. The first
+/// matching route of a host wins.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Route {
+	pub path: String,
+	pub file: Option<PathBuf>,
+	pub dir: Option<PathBuf>,
+	pub proxy: Option<ProxyRoute>,
+	pub websocket: Option<WebSocketRoute>,
+	pub cgi: Option<CgiRoute>,
+	/// Waits before answering, to simulate a slow handler.
+	pub delay_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProxyRoute {
+	pub upstreams: Vec<SocketAddr>,
+	#[serde(default = "default_balancing")]
+	pub balancing: Balancing,
+	pub connect_timeout_ms: Option<u64>,
+	pub timeout_ms: Option<u64>,
+	pub health_check: Option<String>,
+	#[serde(default = "default_health_interval")]
+	pub health_interval_ms: u64,
+}
+
+/// Runs `program` (resolved against the host root, which is also its
+/// working directory) once per request.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CgiRoute {
+	pub program: PathBuf,
+	#[serde(default)]
+	pub args: Vec<String>,
+	#[serde(default = "default_cgi_timeout")]
+	pub timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WebSocketRoute {
+	Echo,
+}
+
+fn default_threads() -> usize {
+	4
+}
+
+fn default_root() -> PathBuf {
+	PathBuf::from(".")
+}
+
+fn default_balancing() -> Balancing {
+	Balancing::RoundRobin
+}
+
+fn default_health_interval() -> u64 {
+	5000
+}
+
+fn default_cgi_timeout() -> u64 {
+	10_000
+}
+
+impl Config {
+	/// Reads and validates a config file.
+	///
+	/// Relative paths inside the file are resolved against the directory
+	/// that contains it.
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, ConfigError> {
+		let path = path.as_ref();
+
+		let text = fs::read_to_string(path)
+			.map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+
+		let mut config: Config = toml::from_str(&text)
+			.map_err(|e| ConfigError::Parse(format!("{}: {}", path.display(), e)))?;
+
+		if let Some(base) = path.parent() {
+			config.rebase(base);
+		}
+
+		config.validate()?;
+
+		Ok(config)
+	}
+
+	/// Parses and validates a config; relative paths stay relative to the
+	/// current directory.
+	pub fn parse(text: &str) -> Result<Self, ConfigError> {
+		let config: Config = toml::from_str(text)
+			.map_err(|e| ConfigError::Parse(e.to_string()))?;
+
+		config.validate()?;
+
+		Ok(config)
+	}
+
+	pub fn validate(&self) -> Result<(), ConfigError> {
+		if self.listeners.is_empty() {
+			return Err(ConfigError::invalid("listeners".to_string(),
+			                                "at least one listener is required".to_string()));
+		}
+
+		for (i, listener) in self.listeners.iter().enumerate() {
+			if self.listeners[..i].contains(listener) {
+				return Err(ConfigError::invalid(format!("listeners[{}].address", i),
+				                                format!("`{}` is listed twice", listener.address)));
+			}
+		}
+
+		if self.threads == 0 {
+			return Err(ConfigError::invalid("threads".to_string(),
+			                                "must be greater than zero".to_string()));
+		}
+
+		if self.hosts.is_empty() {
+			return Err(ConfigError::invalid("hosts".to_string(),
+			                                "at least one host is required".to_string()));
+		}
+
+		let mut names: HashMap<String, usize> = HashMap::new();
+		let mut default_host = None;
+
+		for (i, host) in self.hosts.iter().enumerate() {
+			let field = format!("hosts[{}]", i);
+
+			if host.names.is_empty() {
+				if let Some(other) = default_host {
+					return Err(ConfigError::invalid(format!("{}.names", field),
+					                                format!("hosts[{}] is already the default host", other)));
+				}
+
+				default_host = Some(i);
+			}
+
+			for name in &host.names {
+				let key = name.to_ascii_lowercase();
+
+				if let Some(other) = names.insert(key, i) {
+					return Err(ConfigError::invalid(format!("{}.names", field),
+					                                format!("`{}` is already used by hosts[{}]", name, other)));
+				}
+			}
+
+			host.validate(&field)?;
+		}
+
+		Ok(())
+	}
+
+	fn rebase(&mut self, base: &Path) {
+		if let Some(file) = &self.logging.file {
+			self.logging.file = Some(base.join(file));
+		}
+
+		for host in &mut self.hosts {
+			host.root = base.join(&host.root);
+		}
+	}
+}
+
+impl Host {
+	/// Resolves a path from the config against this host's root.
+	pub fn resolve(&self, path: &Path) -> PathBuf {
+		self.root.join(path)
+	}
+
+	fn validate(&self, field: &str) -> Result<(), ConfigError> {
+		if !self.root.is_dir() {
+			return Err(ConfigError::invalid(format!("{}.root", field),
+			                                format!("`{}` is not a directory", self.root.display())));
+		}
+
+		if let Some(not_found) = &self.not_found {
+			self.check_file(&format!("{}.not_found", field), not_found)?;
+		}
+
+		for (i, route) in self.routes.iter().enumerate() {
+			route.validate(self, &format!("{}.routes[{}]", field, i))?;
+		}
+
+		Ok(())
+	}
+
+	fn check_file(&self, field: &str, path: &Path) -> Result<(), ConfigError> {
+		if !self.resolve(path).is_file() {
+			return Err(ConfigError::invalid(field.to_string(),
+			                                format!("`{}` does not exist under `{}`",
+			                                        path.display(), self.root.display())));
+		}
+
+		Ok(())
+	}
+}
+
+impl Route {
+	/// Checks whether this route handles `path` (without query string).
+	pub fn matches(&self, path: &str) -> bool {
+		match self.path.strip_suffix('*') {
+			Some(prefix) => path.starts_with(prefix),
+			None => path == self.path,
+		}
+	}
+
+	/// The part of `path` after a prefix route, e.g. `css/a.css` for
+	/// `/static/css/a.css` on `/static/*`.
+	pub fn remainder<'a>(&self, path: &'a str) -> &'a str {
+		let prefix = self.path.strip_suffix('*').unwrap_or(&self.path);
+		path.get(prefix.len()..).unwrap_or("")
+	}
+
+	fn validate(&self, host: &Host, field: &str) -> Result<(), ConfigError> {
+		if !self.path.starts_with('/') {
+			return Err(ConfigError::invalid(format!("{}.path", field),
+			                                format!("`{}` must start with `/`", self.path)));
+		}
+
+		let actions = [self.file.is_some(),
+		               self.dir.is_some(),
+		               self.proxy.is_some(),
+		               self.websocket.is_some(),
+		               self.cgi.is_some()];
+
+		if actions.iter().filter(|set| **set).count() != 1 {
+			return Err(ConfigError::invalid(field.to_string(),
+			                                "route must set exactly one of `file`, `dir`, `proxy`, `websocket`, `cgi`".to_string()));
+		}
+
+		if let Some(file) = &self.file {
+			host.check_file(&format!("{}.file", field), file)?;
+		}
+
+		if let Some(dir) = &self.dir {
+			if !self.path.ends_with("/*") {
+				return Err(ConfigError::invalid(format!("{}.path", field),
+				                                "a `dir` route path must end with `/*`".to_string()));
+			}
+
+			if !host.resolve(dir).is_dir() {
+				return Err(ConfigError::invalid(format!("{}.dir", field),
+				                                format!("`{}` is not a directory under `{}`",
+				                                        dir.display(), host.root.display())));
+			}
+		}
+
+		if let Some(proxy) = &self.proxy {
+			proxy.validate(&format!("{}.proxy", field))?;
+		}
+
+		if let Some(cgi) = &self.cgi {
+			host.check_file(&format!("{}.cgi.program", field), &cgi.program)?;
+
+			if cgi.timeout_ms == 0 {
+				return Err(ConfigError::invalid(format!("{}.cgi.timeout_ms", field),
+				                                "must be greater than zero".to_string()));
+			}
+		}
+
+		Ok(())
+	}
+}
+
+impl ProxyRoute {
+	fn validate(&self, field: &str) -> Result<(), ConfigError> {
+		if self.upstreams.is_empty() {
+			return Err(ConfigError::invalid(format!("{}.upstreams", field),
+			                                "at least one upstream is required".to_string()));
+		}
+
+		let timeouts = [("connect_timeout_ms", self.connect_timeout_ms),
+		                ("timeout_ms", self.timeout_ms),
+		                ("health_interval_ms", Some(self.health_interval_ms))];
+
+		for (name, timeout) in timeouts {
+			if timeout == Some(0) {
+				return Err(ConfigError::invalid(format!("{}.{}", field, name),
+				                                "must be greater than zero".to_string()));
+			}
+		}
+
+		if let Some(path) = &self.health_check {
+			if !path.starts_with('/') {
+				return Err(ConfigError::invalid(format!("{}.health_check", field),
+				                                format!("`{}` must start with `/`", path)));
+			}
+		}
+
+		Ok(())
+	}
+}
@@ -0,0 +1,355 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+
+// limite para a linha de request + headers
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// Largest body `read_from` accepts, however it is delimited.
+pub const MAX_BODY_SIZE: usize = 16 * 1024 * 1024;
+
+/// Error inside the `io::Error` returned for a body over `MAX_BODY_SIZE`;
+/// nothing is allocated for it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyTooLarge;
+
+impl fmt::Display for BodyTooLarge {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "body larger than {} bytes", MAX_BODY_SIZE)
+	}
+}
+
+impl Error for BodyTooLarge {}
+
+impl BodyTooLarge {
+	/// Whether `error` was caused by a body over `MAX_BODY_SIZE`.
+	pub fn is(error: &io::Error) -> bool {
+		error.get_ref().is_some_and(|inner| inner.is::<BodyTooLarge>())
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Request {
+	pub method: String,
+	pub path: String,
+	pub version: String,
+	pub headers: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+impl Request {
+	pub fn new(method: &str, path: &str) -> Self {
+		Self {
+			method: method.to_string(),
+			path: path.to_string(),
+			version: "HTTP/1.1".to_string(),
+			headers: Vec::new(),
+			body: Vec::new(),
+		}
+	}
+
+	/// Reads a request head and its body from `reader`, delimited by
+	/// chunked encoding or `Content-Length`.
+	///
+	/// The head is read one byte at a time so nothing past the request is
+	/// consumed; an upgraded connection (e.g. a WebSocket) can keep using
+	/// the same stream afterwards.
+	pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+		let (start_line, headers) = read_head(reader)?;
+		let mut parts = start_line.split_whitespace();
+
+		let (method, path, version) = match (parts.next(), parts.next(), parts.next()) {
+			(Some(method), Some(path), Some(version)) => (method, path, version),
+			_ => return Err(invalid_data("malformed request line")),
+		};
+
+		let mut request = Self {
+			method: method.to_string(),
+			path: path.to_string(),
+			version: version.to_string(),
+			headers,
+			body: Vec::new(),
+		};
+
+		// com os dois, Transfer-Encoding vence (RFC 9112, 6.3)
+		if is_chunked(&request.headers) {
+			request.body = read_chunked(reader)?;
+		} else if let Some(length) = content_length(&request.headers)? {
+			request.body = vec![0; length];
+			reader.read_exact(&mut request.body)?;
+		}
+
+		Ok(request)
+	}
+
+	/// Writes the request, adding `Content-Length` when there is a body.
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		let mut head = format!("{} {} {}\r\n", self.method, self.path, self.version);
+
+		for (name, value) in &self.headers {
+			head.push_str(&format!("{}: {}\r\n", name, value));
+		}
+
+		if !self.body.is_empty() && self.header("Content-Length").is_none() {
+			head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+		}
+
+		head.push_str("\r\n");
+
+		writer.write_all(head.as_bytes())?;
+		writer.write_all(&self.body)?;
+		writer.flush()
+	}
+
+	/// Returns the value of the first header called `name`, ignoring case.
+	pub fn header(&self, name: &str) -> Option<&str> {
+		header(&self.headers, name)
+	}
+
+	/// Checks whether the comma separated header `name` lists `token`.
+	pub fn header_contains(&self, name: &str, token: &str) -> bool {
+		self.header(name)
+			.map(|value| {
+				value.split(',')
+					.any(|item| item.trim().eq_ignore_ascii_case(token))
+			})
+			.unwrap_or(false)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Response {
+	pub status: u16,
+	pub reason: String,
+	pub headers: Vec<(String, String)>,
+	pub body: Vec<u8>,
+}
+
+impl Response {
+	pub fn new(status: u16, reason: &str) -> Self {
+		Self {
+			status,
+			reason: reason.to_string(),
+			headers: Vec::new(),
+			body: Vec::new(),
+		}
+	}
+
+	pub fn with_body(status: u16, reason: &str, content_type: &str, body: Vec<u8>) -> Self {
+		let mut response = Self::new(status, reason);
+		response.headers.push(("Content-Type".to_string(), content_type.to_string()));
+		response.body = body;
+		response
+	}
+
+	/// Reads a response; the body is delimited by chunked encoding,
+	/// `Content-Length` or the end of the stream, in that order of preference.
+	pub fn read_from<R: Read>(reader: &mut R) -> io::Result<Self> {
+		let (status_line, headers) = read_head(reader)?;
+		let mut parts = status_line.splitn(3, ' ');
+
+		let status = match (parts.next(), parts.next()) {
+			(Some(version), Some(status)) if version.starts_with("HTTP/") => {
+				status.parse().map_err(|_| invalid_data("invalid status code"))?
+			},
+			_ => return Err(invalid_data("malformed status line")),
+		};
+
+		let mut response = Self {
+			status,
+			reason: parts.next().unwrap_or("").to_string(),
+			headers,
+			body: Vec::new(),
+		};
+
+		// 1xx, 204 e 304 nunca tem body
+		if status < 200 || status == 204 || status == 304 {
+			return Ok(response);
+		}
+
+		// com os dois, Transfer-Encoding vence (RFC 9112, 6.3)
+		if is_chunked(&response.headers) {
+			response.body = read_chunked(reader)?;
+		} else if let Some(length) = content_length(&response.headers)? {
+			response.body = vec![0; length];
+			reader.read_exact(&mut response.body)?;
+		} else {
+			reader.take(MAX_BODY_SIZE as u64 + 1).read_to_end(&mut response.body)?;
+
+			if response.body.len() > MAX_BODY_SIZE {
+				return Err(too_large());
+			}
+		}
+
+		Ok(response)
+	}
+
+	/// Returns the value of the first header called `name`, ignoring case.
+	pub fn header(&self, name: &str) -> Option<&str> {
+		header(&self.headers, name)
+	}
+
+	/// Body as text, replacing invalid UTF-8.
+	pub fn text(&self) -> String {
+		String::from_utf8_lossy(&self.body).into_owned()
+	}
+
+	/// Writes the response with a `Content-Length` and `Connection: close`.
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		let mut head = format!("HTTP/1.1 {} {}\r\n", self.status, self.reason);
+
+		for (name, value) in &self.headers {
+			head.push_str(&format!("{}: {}\r\n", name, value));
+		}
+
+		head.push_str(&format!("Content-Length: {}\r\nConnection: close\r\n\r\n",
+		                       self.body.len()));
+
+		writer.write_all(head.as_bytes())?;
+		writer.write_all(&self.body)?;
+		writer.flush()
+	}
+}
+
+/// Splits a `Host` header into name and port, keeping the brackets of an
+/// IPv6 literal: `[::1]:80` gives `("[::1]", "80")` and `[::1]` no port.
+pub fn split_host(host: &str) -> (&str, &str) {
+	let end = match host.strip_prefix('[') {
+		Some(rest) => rest.find(']').map_or(host.len(), |i| i + 2),
+		None => host.find(':').unwrap_or(host.len()),
+	};
+
+	let (name, rest) = host.split_at(end);
+
+	(name, rest.strip_prefix(':').unwrap_or(rest))
+}
+
+pub(crate) fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+	headers.iter()
+		.find(|(key, _)| key.eq_ignore_ascii_case(name))
+		.map(|(_, value)| value.as_str())
+}
+
+pub(crate) fn invalid_data(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+fn is_chunked(headers: &[(String, String)]) -> bool {
+	header(headers, "Transfer-Encoding")
+		.map(|value| value.eq_ignore_ascii_case("chunked"))
+		.unwrap_or(false)
+}
+
+fn too_large() -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, BodyTooLarge)
+}
+
+// recusado antes de alocar qualquer coisa para o body
+fn content_length(headers: &[(String, String)]) -> io::Result<Option<usize>> {
+	let length = match header(headers, "Content-Length") {
+		Some(length) => match length.parse::<usize>() {
+			Ok(length) => length,
+			// so digitos, mas nem cabe em um usize
+			Err(_) if !length.is_empty() && length.bytes().all(|b| b.is_ascii_digit()) => {
+				return Err(too_large());
+			},
+			Err(_) => return Err(invalid_data("invalid Content-Length")),
+		},
+		None => return Ok(None),
+	};
+
+	if length > MAX_BODY_SIZE {
+		return Err(too_large());
+	}
+
+	Ok(Some(length))
+}
+
+// le a primeira linha e os headers, ate o CRLF CRLF
+fn read_head<R: Read>(reader: &mut R) -> io::Result<(String, Vec<(String, String)>)> {
+	let mut head = Vec::new();
+	let mut byte = [0; 1];
+
+	while !head.ends_with(b"\r\n\r\n") {
+		if reader.read(&mut byte)? == 0 {
+			return Err(io::Error::new(io::ErrorKind::UnexpectedEof,
+			                          "connection closed before end of headers"));
+		}
+
+		head.push(byte[0]);
+
+		if head.len() > MAX_HEAD_SIZE {
+			return Err(invalid_data("head too large"));
+		}
+	}
+
+	head.truncate(head.len() - 4);
+
+	let head = String::from_utf8(head).map_err(|_| invalid_data("head is not valid UTF-8"))?;
+	let mut lines = head.split("\r\n");
+
+	let start_line = lines.next().unwrap_or("").to_string();
+	let mut headers = Vec::new();
+
+	for line in lines {
+		match line.split_once(':') {
+			Some((name, value)) => {
+				headers.push((name.trim().to_string(), value.trim().to_string()));
+			},
+			None => return Err(invalid_data("malformed header line")),
+		}
+	}
+
+	Ok((start_line, headers))
+}
+
+fn read_line<R: Read>(reader: &mut R) -> io::Result<String> {
+	let mut line = Vec::new();
+	let mut byte = [0; 1];
+
+	while !line.ends_with(b"\r\n") {
+		reader.read_exact(&mut byte)?;
+		line.push(byte[0]);
+
+		if line.len() > MAX_HEAD_SIZE {
+			return Err(invalid_data("line too large"));
+		}
+	}
+
+	line.truncate(line.len() - 2);
+
+	String::from_utf8(line).map_err(|_| invalid_data("line is not valid UTF-8"))
+}
+
+fn read_chunked<R: Read>(reader: &mut R) -> io::Result<Vec<u8>> {
+	let mut body = Vec::new();
+
+	loop {
+		let line = read_line(reader)?;
+		let size = line.split(';').next().unwrap_or("").trim();
+		let size = usize::from_str_radix(size, 16)
+			.map_err(|_| invalid_data("invalid chunk size"))?;
+
+		if size == 0 {
+			break;
+		}
+
+		let start = body.len();
+		let end = start.checked_add(size)
+			.filter(|&end| end <= MAX_BODY_SIZE)
+			.ok_or_else(too_large)?;
+
+		body.resize(end, 0);
+		reader.read_exact(&mut body[start..])?;
+
+		if !read_line(reader)?.is_empty() {
+			return Err(invalid_data("chunk not followed by CRLF"));
+		}
+	}
+
+	// trailers sao ignorados
+	while !read_line(reader)?.is_empty() {}
+
+	Ok(body)
+}
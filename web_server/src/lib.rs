@@ -1,3 +1,12 @@
+pub mod cgi;
+pub mod client;
+pub mod config;
+pub mod http;
+pub mod logger;
+pub mod proxy;
+pub mod server;
+pub mod websocket;
+
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::thread;
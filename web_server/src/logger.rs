@@ -0,0 +1,79 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::io::prelude::*;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+
+use crate::config::Logging;
+
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+	Error,
+	Warn,
+	#[default]
+	Info,
+	Debug,
+}
+
+impl Level {
+	fn label(self) -> &'static str {
+		match self {
+			Level::Error => "ERROR",
+			Level::Warn => "WARN",
+			Level::Info => "INFO",
+			Level::Debug => "DEBUG",
+		}
+	}
+}
+
+pub struct Logger {
+	level: Level,
+	output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Logger {
+	pub fn new(level: Level, output: Box<dyn Write + Send>) -> Self {
+		Self {
+			level,
+			output: Mutex::new(output),
+		}
+	}
+
+	/// Opens the configured log file for appending, or uses stderr.
+	pub fn from_config(logging: &Logging) -> io::Result<Self> {
+		let output: Box<dyn Write + Send> = match &logging.file {
+			Some(path) => Box::new(OpenOptions::new().create(true).append(true).open(path)?),
+			None => Box::new(io::stderr()),
+		};
+
+		Ok(Self::new(logging.level, output))
+	}
+
+	pub fn log(&self, level: Level, message: &str) {
+		if level > self.level {
+			return;
+		}
+
+		let mut output = self.output.lock().unwrap();
+		let _ = writeln!(output, "[{}] {}", level.label(), message);
+		let _ = output.flush();
+	}
+
+	pub fn error(&self, message: &str) {
+		self.log(Level::Error, message);
+	}
+
+	pub fn warn(&self, message: &str) {
+		self.log(Level::Warn, message);
+	}
+
+	pub fn info(&self, message: &str) {
+		self.log(Level::Info, message);
+	}
+
+	pub fn debug(&self, message: &str) {
+		self.log(Level::Debug, message);
+	}
+}
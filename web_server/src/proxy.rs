@@ -0,0 +1,343 @@
+use std::io;
+use std::io::prelude::*;
+use std::net::{IpAddr, SocketAddr, TcpStream};
+use std::sync::{Arc, Weak};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::ConfigError;
+use crate::http::Request;
+use crate::logger::{Level, Logger};
+
+// headers que valem so para uma conexao e nao devem ser repassados
+const HOP_BY_HOP: [&str; 8] = [
+	"Connection",
+	"Keep-Alive",
+	"Proxy-Authenticate",
+	"Proxy-Authorization",
+	"TE",
+	"Trailer",
+	"Transfer-Encoding",
+	"Upgrade",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Balancing {
+	RoundRobin,
+	LeastConnections,
+}
+
+#[derive(Debug)]
+pub struct Upstream {
+	pub address: SocketAddr,
+	healthy: AtomicBool,
+	active: AtomicUsize,
+}
+
+impl Upstream {
+	fn new(address: SocketAddr) -> Self {
+		Self {
+			address,
+			healthy: AtomicBool::new(true),
+			active: AtomicUsize::new(0),
+		}
+	}
+
+	pub fn is_healthy(&self) -> bool {
+		self.healthy.load(Ordering::SeqCst)
+	}
+
+	pub fn active_connections(&self) -> usize {
+		self.active.load(Ordering::SeqCst)
+	}
+}
+
+// decrementa o contador de conexoes ativas quando sai de escopo
+struct ActiveGuard<'a>(&'a Upstream);
+
+impl<'a> ActiveGuard<'a> {
+	fn new(upstream: &'a Upstream) -> Self {
+		upstream.active.fetch_add(1, Ordering::SeqCst);
+		Self(upstream)
+	}
+}
+
+impl Drop for ActiveGuard<'_> {
+	fn drop(&mut self) {
+		self.0.active.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+pub struct Proxy {
+	upstreams: Vec<Upstream>,
+	balancing: Balancing,
+	next: AtomicUsize,
+	connect_timeout: Duration,
+	timeout: Duration,
+	health_path: Option<String>,
+	logger: Arc<Logger>,
+}
+
+impl Proxy {
+	/// Fails when `addresses` is empty. Messages go to stderr until
+	/// [`Proxy::set_logger`] is called.
+	pub fn new(addresses: Vec<SocketAddr>, balancing: Balancing) -> Result<Self, ConfigError> {
+		if addresses.is_empty() {
+			return Err(ConfigError::invalid("upstreams".to_string(),
+			                                "at least one upstream is required".to_string()));
+		}
+
+		Ok(Self {
+			upstreams: addresses.into_iter().map(Upstream::new).collect(),
+			balancing,
+			next: AtomicUsize::new(0),
+			connect_timeout: Duration::from_secs(2),
+			timeout: Duration::from_secs(30),
+			health_path: None,
+			logger: Arc::new(Logger::new(Level::default(), Box::new(io::stderr()))),
+		})
+	}
+
+	pub fn upstreams(&self) -> &[Upstream] {
+		&self.upstreams
+	}
+
+	pub fn set_connect_timeout(&mut self, timeout: Duration) {
+		self.connect_timeout = timeout;
+	}
+
+	/// Read/write timeout for a single exchange with an upstream.
+	pub fn set_timeout(&mut self, timeout: Duration) {
+		self.timeout = timeout;
+	}
+
+	/// Where unreachable upstreams and health changes are reported.
+	pub fn set_logger(&mut self, logger: Arc<Logger>) {
+		self.logger = logger;
+	}
+
+	/// Makes health checks send `GET path` and expect a 2xx/3xx answer
+	/// instead of only opening a TCP connection.
+	pub fn set_health_check(&mut self, path: &str) {
+		self.health_path = Some(path.to_string());
+	}
+
+	/// Forwards `request` to an upstream and relays the answer to `stream`.
+	///
+	/// Upstreams that refuse the connection are marked unhealthy and the next
+	/// one is tried. Answers `502 Bad Gateway` when none can be reached or the
+	/// request cannot be sent, marking that upstream unhealthy, and
+	/// `504 Gateway Timeout` when the chosen one does not answer in time.
+	pub fn forward<S: Read + Write>(
+		&self,
+		stream: &mut S,
+		request: &Request,
+		client: Option<IpAddr>,
+	) -> io::Result<()>
+	{
+		for _ in 0..self.upstreams.len() {
+			let upstream = match self.pick() {
+				Some(upstream) => upstream,
+				None => break,
+			};
+
+			let _guard = ActiveGuard::new(upstream);
+
+			let mut backend = match TcpStream::connect_timeout(&upstream.address,
+			                                                   self.connect_timeout) {
+				Ok(backend) => backend,
+				Err(e) => {
+					self.logger.warn(&format!("upstream {} unreachable: {}", upstream.address, e));
+					upstream.healthy.store(false, Ordering::SeqCst);
+					continue;
+				},
+			};
+
+			backend.set_read_timeout(Some(self.timeout))?;
+			backend.set_write_timeout(Some(self.timeout))?;
+
+			// parte do request pode ja ter ido, entao nao tenta outro upstream
+			let sent = backend.write_all(&upstream_request(request, client))
+				.and_then(|()| backend.flush());
+
+			if let Err(e) = sent {
+				self.logger.warn(&format!("could not send the request to {}: {}", upstream.address, e));
+				upstream.healthy.store(false, Ordering::SeqCst);
+				return write_status(stream, "502 Bad Gateway");
+			}
+
+			return relay(&mut backend, stream);
+		}
+
+		write_status(stream, "502 Bad Gateway")
+	}
+
+	/// Probes every upstream once and records whether it is healthy.
+	pub fn check_health(&self) {
+		for upstream in &self.upstreams {
+			let healthy = self.probe(upstream.address);
+
+			if healthy && !upstream.is_healthy() {
+				self.logger.info(&format!("upstream {} is now healthy", upstream.address));
+			} else if !healthy && upstream.is_healthy() {
+				self.logger.warn(&format!("upstream {} is now unhealthy", upstream.address));
+			}
+
+			upstream.healthy.store(healthy, Ordering::SeqCst);
+		}
+	}
+
+	/// Runs [`Proxy::check_health`] every `interval` on a background thread.
+	///
+	/// The thread only holds a weak reference and stops once the proxy is
+	/// dropped, e.g. after a config reload replaced it.
+	pub fn spawn_health_checks(proxy: &Arc<Self>, interval: Duration) -> thread::JoinHandle<()> {
+		let proxy: Weak<Self> = Arc::downgrade(proxy);
+
+		thread::spawn(move || {
+			while let Some(proxy) = proxy.upgrade() {
+				proxy.check_health();
+				drop(proxy);
+
+				thread::sleep(interval);
+			}
+		})
+	}
+
+	fn pick(&self) -> Option<&Upstream> {
+		let healthy: Vec<&Upstream> = self.upstreams.iter()
+			.filter(|upstream| upstream.is_healthy())
+			.collect();
+
+		if healthy.is_empty() {
+			return None;
+		}
+
+		match self.balancing {
+			Balancing::RoundRobin => {
+				let next = self.next.fetch_add(1, Ordering::SeqCst);
+				Some(healthy[next % healthy.len()])
+			},
+			Balancing::LeastConnections => {
+				healthy.into_iter().min_by_key(|upstream| upstream.active_connections())
+			},
+		}
+	}
+
+	fn probe(&self, address: SocketAddr) -> bool {
+		let mut backend = match TcpStream::connect_timeout(&address, self.connect_timeout) {
+			Ok(backend) => backend,
+			Err(_) => return false,
+		};
+
+		let path = match &self.health_path {
+			Some(path) => path,
+			None => return true,
+		};
+
+		let _ = backend.set_read_timeout(Some(self.connect_timeout));
+		let check = format!("GET {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n\r\n",
+		                    path, address);
+
+		if backend.write_all(check.as_bytes()).is_err() {
+			return false;
+		}
+
+		let mut status_line = [0; 12];
+
+		match backend.read_exact(&mut status_line) {
+			Ok(()) => status_line.starts_with(b"HTTP/1.") &&
+			          (status_line[9] == b'2' || status_line[9] == b'3'),
+			Err(_) => false,
+		}
+	}
+}
+
+// monta o request que vai para o upstream, com X-Forwarded-For
+fn upstream_request(request: &Request, client: Option<IpAddr>) -> Vec<u8> {
+	let mut head = format!("{} {} {}\r\n", request.method, request.path, request.version);
+
+	// headers listados em Connection tambem sao so desta conexao
+	let listed: Vec<&str> = request.headers.iter()
+		.filter(|(name, _)| name.eq_ignore_ascii_case("Connection"))
+		.flat_map(|(_, value)| value.split(','))
+		.map(str::trim)
+		.collect();
+
+	for (name, value) in &request.headers {
+		if HOP_BY_HOP.iter().chain(&listed).any(|hop| hop.eq_ignore_ascii_case(name)) ||
+		   name.eq_ignore_ascii_case("X-Forwarded-For") ||
+		   name.eq_ignore_ascii_case("Content-Length") {
+			continue;
+		}
+
+		head.push_str(&format!("{}: {}\r\n", name, value));
+	}
+
+	// um body chunked ja foi lido inteiro e segue com tamanho fixo
+	if !request.body.is_empty() || request.header("Content-Length").is_some() {
+		head.push_str(&format!("Content-Length: {}\r\n", request.body.len()));
+	}
+
+	let forwarded = match (request.header("X-Forwarded-For"), client) {
+		(Some(previous), Some(client)) => Some(format!("{}, {}", previous, client)),
+		(Some(previous), None) => Some(previous.to_string()),
+		(None, Some(client)) => Some(client.to_string()),
+		(None, None) => None,
+	};
+
+	if let Some(forwarded) = forwarded {
+		head.push_str(&format!("X-Forwarded-For: {}\r\n", forwarded));
+	}
+
+	// uma conexao por request: a resposta termina quando o upstream fecha
+	head.push_str("Connection: close\r\n\r\n");
+
+	let mut bytes = head.into_bytes();
+	bytes.extend_from_slice(&request.body);
+	bytes
+}
+
+fn relay<S: Write>(backend: &mut TcpStream, stream: &mut S) -> io::Result<()> {
+	let mut buffer = [0; 8 * 1024];
+	let mut relayed = 0;
+
+	loop {
+		let read = match backend.read(&mut buffer) {
+			Ok(read) => read,
+			Err(e) if is_timeout(&e) => {
+				if relayed == 0 {
+					return write_status(stream, "504 Gateway Timeout");
+				}
+
+				return Err(e);
+			},
+			Err(e) => return Err(e),
+		};
+
+		if read == 0 {
+			break;
+		}
+
+		stream.write_all(&buffer[..read])?;
+		relayed += read;
+	}
+
+	stream.flush()
+}
+
+fn is_timeout(error: &io::Error) -> bool {
+	error.kind() == io::ErrorKind::WouldBlock || error.kind() == io::ErrorKind::TimedOut
+}
+
+fn write_status<S: Write>(stream: &mut S, status: &str) -> io::Result<()> {
+	let response = format!("HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+	                       status);
+
+	stream.write_all(response.as_bytes())?;
+	stream.flush()
+}
@@ -0,0 +1,441 @@
+use std::fs;
+use std::io;
+use std::io::prelude::*;
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use crate::ThreadPool;
+use crate::cgi::{Cgi, Target};
+use crate::config::{Config, ConfigError, Host, Route, WebSocketRoute};
+use crate::http::{self, BodyTooLarge, Request, Response};
+use crate::logger::Logger;
+use crate::proxy::Proxy;
+use crate::websocket;
+use crate::websocket::{Echo, WebSocket};
+
+// o que foi carregado da config e pode ser trocado em um reload
+struct Site {
+	config: Config,
+	backends: Vec<Vec<Backend>>,
+	logger: Arc<Logger>,
+}
+
+// estado montado para as rotas que nao servem so arquivos
+enum Backend {
+	None,
+	Proxy(Arc<Proxy>),
+	Cgi(Cgi),
+}
+
+impl Site {
+	fn new(config: Config) -> Result<Self, ConfigError> {
+		let logger = Logger::from_config(&config.logging).map_err(|e| {
+			let path = config.logging.file.clone().unwrap_or_default();
+			ConfigError::Io(path, e)
+		})?;
+		let logger = Arc::new(logger);
+
+		let backends = config.hosts.iter()
+			.map(|host| {
+				host.routes.iter()
+					.map(|route| build_backend(host, route, &logger))
+					.collect()
+			})
+			.collect::<Result<_, _>>()?;
+
+		Ok(Self {
+			config,
+			backends,
+			logger,
+		})
+	}
+
+	fn host(&self, request: &Request) -> usize {
+		let name = request.header("Host")
+			.map(|host| http::split_host(host).0)
+			.unwrap_or("");
+
+		let hosts = &self.config.hosts;
+
+		hosts.iter()
+			.position(|host| host.names.iter().any(|n| n.eq_ignore_ascii_case(name)))
+			.or_else(|| hosts.iter().position(|host| host.names.is_empty()))
+			.unwrap_or(0)
+	}
+}
+
+fn build_backend(host: &Host, route: &Route, logger: &Arc<Logger>) -> Result<Backend, ConfigError> {
+	if let Some(proxy) = build_proxy(route, logger)? {
+		return Ok(Backend::Proxy(proxy));
+	}
+
+	if let Some(settings) = &route.cgi {
+		let mut cgi = Cgi::new(host.resolve(&settings.program));
+		cgi.set_args(settings.args.clone());
+		cgi.set_working_dir(host.root.clone());
+		cgi.set_timeout(Duration::from_millis(settings.timeout_ms));
+
+		return Ok(Backend::Cgi(cgi));
+	}
+
+	Ok(Backend::None)
+}
+
+fn build_proxy(route: &Route, logger: &Arc<Logger>) -> Result<Option<Arc<Proxy>>, ConfigError> {
+	let settings = match &route.proxy {
+		Some(settings) => settings,
+		None => return Ok(None),
+	};
+
+	let mut proxy = Proxy::new(settings.upstreams.clone(), settings.balancing)?;
+	proxy.set_logger(Arc::clone(logger));
+
+	if let Some(timeout) = settings.connect_timeout_ms {
+		proxy.set_connect_timeout(Duration::from_millis(timeout));
+	}
+
+	if let Some(timeout) = settings.timeout_ms {
+		proxy.set_timeout(Duration::from_millis(timeout));
+	}
+
+	if let Some(path) = &settings.health_check {
+		proxy.set_health_check(path);
+	}
+
+	let proxy = Arc::new(proxy);
+	Proxy::spawn_health_checks(&proxy, Duration::from_millis(settings.health_interval_ms));
+
+	Ok(Some(proxy))
+}
+
+struct Shared {
+	site: RwLock<Arc<Site>>,
+	addresses: Vec<SocketAddr>,
+	threads: usize,
+	// workers separados para o I/O dos scripts CGI: se fossem os mesmos
+	// que atendem conexoes, todos poderiam ficar esperando um job na fila
+	cgi_pool: ThreadPool,
+}
+
+impl Shared {
+	fn current(&self) -> Arc<Site> {
+		Arc::clone(&self.site.read().unwrap())
+	}
+
+	fn reload(&self, config: Config) -> Result<(), ConfigError> {
+		config.validate()?;
+
+		let site = Site::new(config)?;
+
+		let addresses: Vec<SocketAddr> = site.config.listeners.iter()
+			.map(|listener| listener.address)
+			.collect();
+
+		if addresses != self.addresses {
+			site.logger.warn("listeners changed; restart the server to apply");
+		}
+
+		if site.config.threads != self.threads {
+			site.logger.warn("threads changed; restart the server to apply");
+		}
+
+		// conexoes em andamento continuam com o Arc da config antiga
+		*self.site.write().unwrap() = Arc::new(site);
+
+		Ok(())
+	}
+}
+
+pub struct Server {
+	listeners: Vec<TcpListener>,
+	pool: Arc<ThreadPool>,
+	shared: Arc<Shared>,
+}
+
+impl Server {
+	/// Binds every configured listener and starts the worker threads.
+	pub fn new(config: Config) -> Result<Self, ConfigError> {
+		config.validate()?;
+
+		let mut listeners = Vec::with_capacity(config.listeners.len());
+
+		for listener in &config.listeners {
+			let bound = TcpListener::bind(listener.address)
+				.map_err(|e| ConfigError::Bind(listener.address, e))?;
+
+			listeners.push(bound);
+		}
+
+		let addresses = config.listeners.iter()
+			.map(|listener| listener.address)
+			.collect();
+		let threads = config.threads;
+
+		let shared = Shared {
+			site: RwLock::new(Arc::new(Site::new(config)?)),
+			addresses,
+			threads,
+			cgi_pool: ThreadPool::new(threads * 2),
+		};
+
+		Ok(Self {
+			listeners,
+			pool: Arc::new(ThreadPool::new(threads)),
+			shared: Arc::new(shared),
+		})
+	}
+
+	/// Addresses actually bound, useful when a listener asked for port 0.
+	pub fn local_addrs(&self) -> Vec<SocketAddr> {
+		self.listeners.iter()
+			.filter_map(|listener| listener.local_addr().ok())
+			.collect()
+	}
+
+	/// Swaps in a new config; requests already being served finish with the
+	/// old one. Listeners and thread count are only read at startup.
+	pub fn reload(&self, config: Config) -> Result<(), ConfigError> {
+		self.shared.reload(config)
+	}
+
+	/// Re-reads `path` whenever the process receives SIGHUP. An invalid file
+	/// is reported and the running config is kept.
+	pub fn reload_on_sighup(&self, path: PathBuf) -> io::Result<()> {
+		let hangup = Arc::new(AtomicBool::new(false));
+		signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&hangup))?;
+
+		let shared = Arc::clone(&self.shared);
+
+		thread::spawn(move || {
+			loop {
+				thread::sleep(Duration::from_millis(200));
+
+				if !hangup.swap(false, Ordering::SeqCst) {
+					continue;
+				}
+
+				let result = Config::from_file(&path)
+					.and_then(|config| shared.reload(config));
+
+				let site = shared.current();
+
+				match result {
+					Ok(()) => site.logger.info(&format!("reloaded {}", path.display())),
+					Err(e) => site.logger.error(&format!("reload failed, keeping old config: {}", e)),
+				}
+			}
+		});
+
+		Ok(())
+	}
+
+	/// Accepts connections on every listener until the process exits.
+	pub fn run(self) {
+		let mut handles = Vec::new();
+
+		for listener in self.listeners {
+			let pool = Arc::clone(&self.pool);
+			let shared = Arc::clone(&self.shared);
+
+			handles.push(thread::spawn(move || {
+				for stream in listener.incoming() {
+					let stream = match stream {
+						Ok(stream) => stream,
+						Err(e) => {
+							shared.current().logger.error(&format!("accept failed: {}", e));
+							continue;
+						},
+					};
+
+					let shared = Arc::clone(&shared);
+
+					pool.execute(move || {
+						let mut stream = stream;
+						let peer = stream.peer_addr().ok();
+
+						handle_connection(&mut stream, peer, &shared.current(), &shared.cgi_pool);
+					});
+				}
+			}));
+		}
+
+		for handle in handles {
+			handle.join().unwrap();
+		}
+	}
+
+	/// Serves one request read from `stream` with the current config.
+	///
+	/// This is what every accepted TCP connection goes through; any other
+	/// `Read + Write` (e.g. an in-memory buffer) can be passed as well.
+	/// `peer` is only used for logging and `X-Forwarded-For`.
+	pub fn handle_connection<S: Read + Write>(&self, stream: &mut S, peer: Option<SocketAddr>) {
+		handle_connection(stream, peer, &self.shared.current(), &self.shared.cgi_pool);
+	}
+}
+
+fn handle_connection<S: Read + Write>(
+	stream: &mut S,
+	peer: Option<SocketAddr>,
+	site: &Site,
+	cgi_pool: &ThreadPool,
+)
+{
+	let request = match Request::read_from(stream) {
+		Ok(request) => request,
+		// a conexao e fechada logo depois, sem ler o resto do body
+		Err(e) if BodyTooLarge::is(&e) => {
+			site.logger.debug(&format!("request from {} refused: {}", describe(peer), e));
+			let _ = Response::new(413, "Content Too Large").write_to(stream);
+			return;
+		},
+		Err(e) => {
+			site.logger.debug(&format!("bad request from {}: {}", describe(peer), e));
+			let _ = Response::new(400, "Bad Request").write_to(stream);
+			return;
+		},
+	};
+
+	let host_index = site.host(&request);
+	let host = &site.config.hosts[host_index];
+	let (path, query) = request.path.split_once('?').unwrap_or((&request.path, ""));
+
+	let route = host.routes.iter()
+		.position(|route| route.matches(path));
+
+	let result = match route {
+		Some(index) => {
+			let route = &host.routes[index];
+
+			if let Some(delay) = route.delay_ms {
+				thread::sleep(Duration::from_millis(delay));
+			}
+
+			match &site.backends[host_index][index] {
+				Backend::Proxy(proxy) => {
+					let client = peer.map(|address| address.ip());
+					proxy.forward(stream, &request, client).map(|()| None)
+				},
+				Backend::Cgi(cgi) => {
+					let script_name = route.path.trim_end_matches('*').trim_end_matches('/');
+					let target = Target {
+						script_name,
+						path_info: &path[script_name.len()..],
+						query,
+						peer,
+					};
+
+					let response = cgi.execute(cgi_pool, &request, &target, &site.logger);
+					response.write_to(stream).map(|()| Some(response.status))
+				},
+				Backend::None => serve_route(stream, &request, host, route, path),
+			}
+		},
+		None => not_found(host).write_to(stream).map(|()| Some(404)),
+	};
+
+	match result {
+		Ok(status) => site.logger.info(&format!("{} \"{} {}\" {}",
+		                                        describe(peer), request.method, request.path,
+		                                        status.map(|s| s.to_string())
+		                                              .unwrap_or_else(|| "proxied".to_string()))),
+		Err(e) => site.logger.error(&format!("{} \"{} {}\" failed: {}",
+		                                     describe(peer), request.method,
+		                                     request.path, e)),
+	}
+}
+
+// responde rotas de arquivo e websocket, devolvendo o status para o log
+fn serve_route<S: Read + Write>(
+	stream: &mut S,
+	request: &Request,
+	host: &Host,
+	route: &Route,
+	path: &str,
+) -> io::Result<Option<u16>>
+{
+
+	if let Some(WebSocketRoute::Echo) = route.websocket {
+		if !websocket::is_upgrade(request) {
+			Response::new(426, "Upgrade Required").write_to(stream)?;
+			return Ok(Some(426));
+		}
+
+		let mut socket = WebSocket::accept(stream, request)
+			.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+
+		socket.run(&mut Echo)
+			.map_err(|e| io::Error::other(e.to_string()))?;
+
+		return Ok(Some(101));
+	}
+
+	let file = match (&route.file, &route.dir) {
+		(Some(file), _) => Some(host.resolve(file)),
+		(None, Some(dir)) => static_path(&host.resolve(dir), route.remainder(path)),
+		(None, None) => None,
+	};
+
+	let response = match file.and_then(|file| read_file(&file)) {
+		Some(response) => response,
+		None => not_found(host),
+	};
+
+	response.write_to(stream)?;
+
+	Ok(Some(response.status))
+}
+
+// caminho dentro de dir, recusando qualquer tentativa de sair dele
+fn static_path(dir: &Path, rest: &str) -> Option<PathBuf> {
+	let relative = Path::new(rest);
+
+	if relative.components().any(|component| !matches!(component, Component::Normal(_))) {
+		return None;
+	}
+
+	let mut path = dir.join(relative);
+
+	if rest.is_empty() || rest.ends_with('/') || path.is_dir() {
+		path.push("index.html");
+	}
+
+	Some(path)
+}
+
+fn read_file(path: &Path) -> Option<Response> {
+	let contents = fs::read(path).ok()?;
+
+	Some(Response::with_body(200, "OK", content_type(path), contents))
+}
+
+fn not_found(host: &Host) -> Response {
+	let body = host.not_found.as_ref()
+		.and_then(|file| fs::read(host.resolve(file)).ok())
+		.unwrap_or_else(|| b"Not Found".to_vec());
+
+	Response::with_body(404, "NOT FOUND", "text/html; charset=utf-8", body)
+}
+
+fn content_type(path: &Path) -> &'static str {
+	match path.extension().and_then(|extension| extension.to_str()) {
+		Some("html") | Some("htm") => "text/html; charset=utf-8",
+		Some("css") => "text/css",
+		Some("js") => "application/javascript",
+		Some("json") => "application/json",
+		Some("txt") => "text/plain; charset=utf-8",
+		Some("png") => "image/png",
+		Some("jpg") | Some("jpeg") => "image/jpeg",
+		Some("svg") => "image/svg+xml",
+		_ => "application/octet-stream",
+	}
+}
+
+fn describe(peer: Option<SocketAddr>) -> String {
+	peer.map(|address| address.to_string())
+		.unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,691 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::io::prelude::*;
+
+use crate::http::Request;
+
+// string magica definida na RFC 6455, concatenada com a chave do cliente
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+// frames de controle nunca podem ter payload maior que isso
+const MAX_CONTROL_PAYLOAD: usize = 125;
+
+const DEFAULT_MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum Error {
+	Io(io::Error),
+	Handshake(&'static str),
+	Protocol(&'static str),
+	InvalidUtf8,
+	TooBig,
+	Closed,
+}
+
+impl Error {
+	/// The close code that should be sent to the peer for this error, if any.
+	pub fn close_code(&self) -> Option<CloseCode> {
+		match self {
+			Error::Protocol(_) => Some(CloseCode::Protocol),
+			Error::InvalidUtf8 => Some(CloseCode::InvalidPayload),
+			Error::TooBig => Some(CloseCode::TooBig),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Error::Io(error) => write!(f, "I/O error: {}", error),
+			Error::Handshake(message) => write!(f, "handshake failed: {}", message),
+			Error::Protocol(message) => write!(f, "protocol error: {}", message),
+			Error::InvalidUtf8 => write!(f, "text message is not valid UTF-8"),
+			Error::TooBig => write!(f, "message exceeds the maximum size"),
+			Error::Closed => write!(f, "connection already closed"),
+		}
+	}
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+	fn from(error: io::Error) -> Self {
+		Error::Io(error)
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Opcode {
+	Continuation,
+	Text,
+	Binary,
+	Close,
+	Ping,
+	Pong,
+}
+
+impl Opcode {
+	pub fn from_u8(value: u8) -> Option<Self> {
+		match value {
+			0x0 => Some(Opcode::Continuation),
+			0x1 => Some(Opcode::Text),
+			0x2 => Some(Opcode::Binary),
+			0x8 => Some(Opcode::Close),
+			0x9 => Some(Opcode::Ping),
+			0xA => Some(Opcode::Pong),
+			_ => None,
+		}
+	}
+
+	pub fn as_u8(self) -> u8 {
+		match self {
+			Opcode::Continuation => 0x0,
+			Opcode::Text => 0x1,
+			Opcode::Binary => 0x2,
+			Opcode::Close => 0x8,
+			Opcode::Ping => 0x9,
+			Opcode::Pong => 0xA,
+		}
+	}
+
+	pub fn is_control(self) -> bool {
+		self.as_u8() & 0x8 != 0
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CloseCode {
+	Normal,
+	GoingAway,
+	Protocol,
+	Unsupported,
+	NoStatus,
+	Abnormal,
+	InvalidPayload,
+	Policy,
+	TooBig,
+	MandatoryExtension,
+	InternalError,
+	Other(u16),
+}
+
+impl CloseCode {
+	pub fn from_u16(code: u16) -> Self {
+		match code {
+			1000 => CloseCode::Normal,
+			1001 => CloseCode::GoingAway,
+			1002 => CloseCode::Protocol,
+			1003 => CloseCode::Unsupported,
+			1005 => CloseCode::NoStatus,
+			1006 => CloseCode::Abnormal,
+			1007 => CloseCode::InvalidPayload,
+			1008 => CloseCode::Policy,
+			1009 => CloseCode::TooBig,
+			1010 => CloseCode::MandatoryExtension,
+			1011 => CloseCode::InternalError,
+			other => CloseCode::Other(other),
+		}
+	}
+
+	pub fn as_u16(self) -> u16 {
+		match self {
+			CloseCode::Normal => 1000,
+			CloseCode::GoingAway => 1001,
+			CloseCode::Protocol => 1002,
+			CloseCode::Unsupported => 1003,
+			CloseCode::NoStatus => 1005,
+			CloseCode::Abnormal => 1006,
+			CloseCode::InvalidPayload => 1007,
+			CloseCode::Policy => 1008,
+			CloseCode::TooBig => 1009,
+			CloseCode::MandatoryExtension => 1010,
+			CloseCode::InternalError => 1011,
+			CloseCode::Other(code) => code,
+		}
+	}
+
+	/// Whether the code may appear in a close frame on the wire.
+	///
+	/// 1005 and 1006 are reserved for reporting locally and 1015 is TLS
+	/// specific; everything below 1000 or above 4999 is invalid.
+	pub fn is_sendable(self) -> bool {
+		matches!(self.as_u16(), 1000..=1003 | 1007..=1011 | 3000..=4999)
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+	pub fin: bool,
+	pub opcode: Opcode,
+	pub mask: Option<[u8; 4]>,
+	pub payload: Vec<u8>,
+}
+
+impl Frame {
+	pub fn new(fin: bool, opcode: Opcode, payload: Vec<u8>) -> Self {
+		Self {
+			fin,
+			opcode,
+			mask: None,
+			payload,
+		}
+	}
+
+	/// Reads a single frame, unmasking the payload if a mask key was sent.
+	pub fn read_from<R: Read>(reader: &mut R, max_payload: usize) -> Result<Self, Error> {
+		let mut header = [0; 2];
+		reader.read_exact(&mut header)?;
+
+		if header[0] & 0x70 != 0 {
+			return Err(Error::Protocol("reserved bits set without an extension"));
+		}
+
+		let fin = header[0] & 0x80 != 0;
+		let opcode = Opcode::from_u8(header[0] & 0x0F)
+			.ok_or(Error::Protocol("unknown opcode"))?;
+		let masked = header[1] & 0x80 != 0;
+
+		let length = match header[1] & 0x7F {
+			126 => {
+				let mut bytes = [0; 2];
+				reader.read_exact(&mut bytes)?;
+				u16::from_be_bytes(bytes) as u64
+			},
+			127 => {
+				let mut bytes = [0; 8];
+				reader.read_exact(&mut bytes)?;
+				let length = u64::from_be_bytes(bytes);
+
+				if length >> 63 != 0 {
+					return Err(Error::Protocol("most significant bit of length set"));
+				}
+
+				length
+			},
+			length => length as u64,
+		};
+
+		if opcode.is_control() {
+			if !fin {
+				return Err(Error::Protocol("fragmented control frame"));
+			}
+
+			if length > MAX_CONTROL_PAYLOAD as u64 {
+				return Err(Error::Protocol("control frame payload too large"));
+			}
+		}
+
+		if length > max_payload as u64 {
+			return Err(Error::TooBig);
+		}
+
+		let mask = if masked {
+			let mut key = [0; 4];
+			reader.read_exact(&mut key)?;
+			Some(key)
+		} else {
+			None
+		};
+
+		let mut payload = vec![0; length as usize];
+		reader.read_exact(&mut payload)?;
+
+		if let Some(key) = mask {
+			apply_mask(&mut payload, key);
+		}
+
+		Ok(Self {
+			fin,
+			opcode,
+			mask,
+			payload,
+		})
+	}
+
+	/// Writes the frame, masking the payload with `self.mask` if present.
+	pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+		let mut bytes = Vec::with_capacity(self.payload.len() + 14);
+
+		let fin = if self.fin { 0x80 } else { 0x00 };
+		bytes.push(fin | self.opcode.as_u8());
+
+		let mask_bit = if self.mask.is_some() { 0x80 } else { 0x00 };
+		let length = self.payload.len();
+
+		if length < 126 {
+			bytes.push(mask_bit | length as u8);
+		} else if length <= u16::MAX as usize {
+			bytes.push(mask_bit | 126);
+			bytes.extend_from_slice(&(length as u16).to_be_bytes());
+		} else {
+			bytes.push(mask_bit | 127);
+			bytes.extend_from_slice(&(length as u64).to_be_bytes());
+		}
+
+		let start = bytes.len();
+
+		match self.mask {
+			Some(key) => {
+				bytes.extend_from_slice(&key);
+				bytes.extend_from_slice(&self.payload);
+				apply_mask(&mut bytes[start + 4..], key);
+			},
+			None => bytes.extend_from_slice(&self.payload),
+		}
+
+		writer.write_all(&bytes)?;
+		writer.flush()
+	}
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+	Text(String),
+	Binary(Vec<u8>),
+}
+
+/// Callbacks for a WebSocket connection driven by [`WebSocket::run`].
+///
+/// Ping/pong and the close handshake are answered by the connection itself;
+/// a handler only sees application messages.
+pub trait Handler<S: Read + Write> {
+	fn on_open(&mut self, _socket: &mut WebSocket<S>) -> Result<(), Error> {
+		Ok(())
+	}
+
+	fn on_text(&mut self, socket: &mut WebSocket<S>, text: String) -> Result<(), Error>;
+
+	fn on_binary(&mut self, socket: &mut WebSocket<S>, data: Vec<u8>) -> Result<(), Error>;
+
+	fn on_close(&mut self, _code: Option<CloseCode>, _reason: &str) {}
+}
+
+/// Sends every message back to the client unchanged.
+pub struct Echo;
+
+impl<S: Read + Write> Handler<S> for Echo {
+	fn on_text(&mut self, socket: &mut WebSocket<S>, text: String) -> Result<(), Error> {
+		socket.send_text(&text)
+	}
+
+	fn on_binary(&mut self, socket: &mut WebSocket<S>, data: Vec<u8>) -> Result<(), Error> {
+		socket.send_binary(&data)
+	}
+}
+
+/// Checks whether `request` asks to switch the connection to WebSocket.
+pub fn is_upgrade(request: &Request) -> bool {
+	request.header_contains("Upgrade", "websocket") &&
+	request.header_contains("Connection", "upgrade")
+}
+
+/// Computes the `Sec-WebSocket-Accept` value for a client key.
+pub fn accept_key(key: &str) -> String {
+	let mut input = key.trim().as_bytes().to_vec();
+	input.extend_from_slice(GUID.as_bytes());
+
+	base64_encode(&sha1(&input))
+}
+
+pub struct WebSocket<S> {
+	stream: S,
+	max_message_size: usize,
+	fragments: Option<(Opcode, Vec<u8>)>,
+	close_sent: bool,
+	peer_close: Option<(Option<CloseCode>, String)>,
+}
+
+impl<S: Read + Write> WebSocket<S> {
+	/// Completes the opening handshake for `request`, which must already have
+	/// been read from `stream`.
+	///
+	/// Invalid handshakes are answered with `400 Bad Request`.
+	pub fn accept(mut stream: S, request: &Request) -> Result<Self, Error> {
+		let key = match validate_handshake(request) {
+			Ok(key) => key,
+			Err(error) => {
+				stream.write_all(b"HTTP/1.1 400 Bad Request\r\n\
+				                   Sec-WebSocket-Version: 13\r\n\
+				                   Content-Length: 0\r\n\r\n")?;
+				stream.flush()?;
+				return Err(error);
+			},
+		};
+
+		let response = format!("HTTP/1.1 101 Switching Protocols\r\n\
+		                        Upgrade: websocket\r\n\
+		                        Connection: Upgrade\r\n\
+		                        Sec-WebSocket-Accept: {}\r\n\r\n",
+		                       accept_key(key));
+
+		stream.write_all(response.as_bytes())?;
+		stream.flush()?;
+
+		Ok(Self::from_stream(stream))
+	}
+
+	/// Wraps a stream on which the handshake has already been done.
+	pub fn from_stream(stream: S) -> Self {
+		Self {
+			stream,
+			max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+			fragments: None,
+			close_sent: false,
+			peer_close: None,
+		}
+	}
+
+	pub fn set_max_message_size(&mut self, size: usize) {
+		self.max_message_size = size;
+	}
+
+	pub fn get_ref(&self) -> &S {
+		&self.stream
+	}
+
+	pub fn get_mut(&mut self) -> &mut S {
+		&mut self.stream
+	}
+
+	/// The close code and reason sent by the peer, once it has closed.
+	pub fn peer_close(&self) -> Option<(Option<CloseCode>, &str)> {
+		self.peer_close.as_ref()
+			.map(|(code, reason)| (*code, reason.as_str()))
+	}
+
+	/// Reads the next complete message.
+	///
+	/// Fragments are reassembled, pings are answered and pongs are dropped.
+	/// Returns `Ok(None)` once the peer has closed the connection.
+	pub fn read_message(&mut self) -> Result<Option<Message>, Error> {
+		if self.peer_close.is_some() {
+			return Ok(None);
+		}
+
+		loop {
+			let frame = Frame::read_from(&mut self.stream, self.max_message_size)?;
+
+			if frame.mask.is_none() {
+				return Err(Error::Protocol("client frames must be masked"));
+			}
+
+			match frame.opcode {
+				Opcode::Ping => {
+					if !self.close_sent {
+						self.send_frame(Frame::new(true, Opcode::Pong, frame.payload))?;
+					}
+				},
+				Opcode::Pong => {},
+				Opcode::Close => {
+					let (code, reason) = parse_close_payload(&frame.payload)?;
+
+					if !self.close_sent {
+						let reply = code.unwrap_or(CloseCode::Normal);
+						self.send_close(reply, "")?;
+					}
+
+					self.peer_close = Some((code, reason));
+					return Ok(None);
+				},
+				Opcode::Text | Opcode::Binary => {
+					if self.fragments.is_some() {
+						return Err(Error::Protocol("new message before the previous one finished"));
+					}
+
+					if frame.fin {
+						return to_message(frame.opcode, frame.payload).map(Some);
+					}
+
+					self.fragments = Some((frame.opcode, frame.payload));
+				},
+				Opcode::Continuation => {
+					let (opcode, mut data) = self.fragments.take()
+						.ok_or(Error::Protocol("continuation frame without a message"))?;
+
+					if data.len() + frame.payload.len() > self.max_message_size {
+						return Err(Error::TooBig);
+					}
+
+					data.extend_from_slice(&frame.payload);
+
+					if frame.fin {
+						return to_message(opcode, data).map(Some);
+					}
+
+					self.fragments = Some((opcode, data));
+				},
+			}
+		}
+	}
+
+	pub fn send(&mut self, message: &Message) -> Result<(), Error> {
+		match message {
+			Message::Text(text) => self.send_text(text),
+			Message::Binary(data) => self.send_binary(data),
+		}
+	}
+
+	pub fn send_text(&mut self, text: &str) -> Result<(), Error> {
+		self.send_data(Frame::new(true, Opcode::Text, text.as_bytes().to_vec()))
+	}
+
+	pub fn send_binary(&mut self, data: &[u8]) -> Result<(), Error> {
+		self.send_data(Frame::new(true, Opcode::Binary, data.to_vec()))
+	}
+
+	pub fn ping(&mut self, payload: &[u8]) -> Result<(), Error> {
+		if payload.len() > MAX_CONTROL_PAYLOAD {
+			return Err(Error::Protocol("ping payload too large"));
+		}
+
+		self.send_data(Frame::new(true, Opcode::Ping, payload.to_vec()))
+	}
+
+	/// Starts the closing handshake; the peer's reply is consumed by
+	/// [`WebSocket::read_message`].
+	pub fn close(&mut self, code: CloseCode, reason: &str) -> Result<(), Error> {
+		if self.close_sent {
+			return Err(Error::Closed);
+		}
+
+		self.send_close(code, reason)
+	}
+
+	/// Dispatches messages to `handler` until the connection is closed.
+	///
+	/// Protocol violations close the connection with the matching code
+	/// before the error is returned.
+	pub fn run<H: Handler<S>>(&mut self, handler: &mut H) -> Result<(), Error> {
+		handler.on_open(self)?;
+
+		loop {
+			match self.read_message() {
+				Ok(Some(Message::Text(text))) => handler.on_text(self, text)?,
+				Ok(Some(Message::Binary(data))) => handler.on_binary(self, data)?,
+				Ok(None) => {
+					let (code, reason) = self.peer_close().unwrap_or((None, ""));
+					handler.on_close(code, reason);
+					return Ok(());
+				},
+				Err(error) => {
+					if let Some(code) = error.close_code() {
+						if !self.close_sent {
+							let _ = self.send_close(code, "");
+						}
+					}
+
+					return Err(error);
+				},
+			}
+		}
+	}
+
+	fn send_data(&mut self, frame: Frame) -> Result<(), Error> {
+		if self.close_sent {
+			return Err(Error::Closed);
+		}
+
+		self.send_frame(frame)
+	}
+
+	fn send_close(&mut self, code: CloseCode, reason: &str) -> Result<(), Error> {
+		let mut payload = code.as_u16().to_be_bytes().to_vec();
+		payload.extend_from_slice(reason.as_bytes());
+		payload.truncate(MAX_CONTROL_PAYLOAD);
+
+		self.close_sent = true;
+		self.send_frame(Frame::new(true, Opcode::Close, payload))
+	}
+
+	fn send_frame(&mut self, frame: Frame) -> Result<(), Error> {
+		frame.write_to(&mut self.stream)?;
+		Ok(())
+	}
+}
+
+fn validate_handshake(request: &Request) -> Result<&str, Error> {
+	if request.method != "GET" {
+		return Err(Error::Handshake("method must be GET"));
+	}
+
+	if !is_upgrade(request) {
+		return Err(Error::Handshake("missing Upgrade or Connection header"));
+	}
+
+	if request.header("Sec-WebSocket-Version") != Some("13") {
+		return Err(Error::Handshake("unsupported Sec-WebSocket-Version"));
+	}
+
+	match request.header("Sec-WebSocket-Key") {
+		Some(key) if !key.is_empty() => Ok(key),
+		_ => Err(Error::Handshake("missing Sec-WebSocket-Key")),
+	}
+}
+
+fn to_message(opcode: Opcode, payload: Vec<u8>) -> Result<Message, Error> {
+	match opcode {
+		Opcode::Text => String::from_utf8(payload)
+			.map(Message::Text)
+			.map_err(|_| Error::InvalidUtf8),
+		_ => Ok(Message::Binary(payload)),
+	}
+}
+
+fn parse_close_payload(payload: &[u8]) -> Result<(Option<CloseCode>, String), Error> {
+	match payload.len() {
+		0 => Ok((None, String::new())),
+		1 => Err(Error::Protocol("close payload of a single byte")),
+		_ => {
+			let code = CloseCode::from_u16(u16::from_be_bytes([payload[0], payload[1]]));
+
+			if !code.is_sendable() {
+				return Err(Error::Protocol("invalid close code"));
+			}
+
+			let reason = String::from_utf8(payload[2..].to_vec())
+				.map_err(|_| Error::InvalidUtf8)?;
+
+			Ok((Some(code), reason))
+		},
+	}
+}
+
+fn apply_mask(data: &mut [u8], key: [u8; 4]) {
+	for (i, byte) in data.iter_mut().enumerate() {
+		*byte ^= key[i % 4];
+	}
+}
+
+fn sha1(data: &[u8]) -> [u8; 20] {
+	let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+	let mut message = data.to_vec();
+	message.push(0x80);
+
+	while message.len() % 64 != 56 {
+		message.push(0);
+	}
+
+	message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+	for chunk in message.chunks(64) {
+		let mut w = [0u32; 80];
+
+		for (i, word) in chunk.chunks(4).enumerate() {
+			w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+		}
+
+		for i in 16..80 {
+			w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+		}
+
+		let (mut a, mut b, mut c, mut d, mut e) = (h[0], h[1], h[2], h[3], h[4]);
+
+		for (i, word) in w.iter().enumerate() {
+			let (f, k) = match i {
+				0..=19 => ((b & c) | (!b & d), 0x5A827999),
+				20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+				40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+				_ => (b ^ c ^ d, 0xCA62C1D6),
+			};
+
+			let temp = a.rotate_left(5)
+				.wrapping_add(f)
+				.wrapping_add(e)
+				.wrapping_add(k)
+				.wrapping_add(*word);
+
+			e = d;
+			d = c;
+			c = b.rotate_left(30);
+			b = a;
+			a = temp;
+		}
+
+		h[0] = h[0].wrapping_add(a);
+		h[1] = h[1].wrapping_add(b);
+		h[2] = h[2].wrapping_add(c);
+		h[3] = h[3].wrapping_add(d);
+		h[4] = h[4].wrapping_add(e);
+	}
+
+	let mut digest = [0; 20];
+
+	for (i, value) in h.iter().enumerate() {
+		digest[i * 4..i * 4 + 4].copy_from_slice(&value.to_be_bytes());
+	}
+
+	digest
+}
+
+fn base64_encode(data: &[u8]) -> String {
+	const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+	let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+	for chunk in data.chunks(3) {
+		let bytes = [chunk[0],
+		             chunk.get(1).copied().unwrap_or(0),
+		             chunk.get(2).copied().unwrap_or(0)];
+		let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+
+		encoded.push(ALPHABET[(n >> 18) as usize & 63] as char);
+		encoded.push(ALPHABET[(n >> 12) as usize & 63] as char);
+
+		if chunk.len() > 1 {
+			encoded.push(ALPHABET[(n >> 6) as usize & 63] as char);
+		} else {
+			encoded.push('=');
+		}
+
+		if chunk.len() > 2 {
+			encoded.push(ALPHABET[n as usize & 63] as char);
+		} else {
+			encoded.push('=');
+		}
+	}
+
+	encoded
+}
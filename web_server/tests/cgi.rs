@@ -0,0 +1,128 @@
+extern crate web_server;
+
+mod common;
+
+use std::time::Instant;
+
+use common::{assert_response, config, TestServer};
+use web_server::cgi::parse_output;
+use web_server::http::Request;
+
+fn cgi_server(program: &str, extra: &str) -> TestServer {
+	TestServer::start(config(&format!(r#"
+		[[hosts.routes]]
+		path = "/cgi/*"
+		cgi = {{ program = "tests/cgi/{}"{} }}
+	"#, program, extra)))
+}
+
+#[test]
+fn passes_request_to_script() {
+	let server = cgi_server("env.sh", "");
+
+	let mut request = Request::new("POST", "/cgi/users/42?sort=name");
+	request.headers.push(("User-Agent".to_string(), "tests".to_string()));
+	request.headers.push(("Content-Type".to_string(), "text/plain".to_string()));
+	request.body = b"hello script".to_vec();
+
+	let response = server.request(request);
+	let text = response.text();
+
+	assert_eq!(response.status, 200);
+	assert_eq!(response.header("X-Script"), Some("env"));
+	assert_eq!(response.header("Content-Type"), Some("text/plain"));
+
+	for line in ["method=POST", "script=/cgi", "path_info=/users/42", "query=sort=name",
+	             "length=12", "type=text/plain", "agent=tests", "remote=127.0.0.1",
+	             "gateway=CGI/1.1", "body=hello script"] {
+		assert!(text.lines().any(|l| l == line), "missing `{}` in:\n{}", line, text);
+	}
+}
+
+#[test]
+fn proxy_header_is_not_exported() {
+	let server = cgi_server("env.sh", "");
+
+	let mut request = Request::new("GET", "/cgi/");
+	request.headers.push(("Proxy".to_string(), "http://attacker:8080".to_string()));
+
+	let text = server.request(request).text();
+
+	assert!(text.lines().any(|l| l == "proxy="), "HTTP_PROXY was set:\n{}", text);
+}
+
+#[test]
+fn status_header_sets_status_line() {
+	let server = cgi_server("status.sh", "");
+
+	let response = server.get("/cgi/");
+	assert_response(&response, 418, "short and stout");
+	assert_eq!(response.reason, "I am a teapot");
+}
+
+#[test]
+fn location_without_status_redirects() {
+	let server = cgi_server("redirect.sh", "");
+
+	let response = server.get("/cgi/");
+	assert_eq!(response.status, 302);
+	assert_eq!(response.header("Location"), Some("/elsewhere"));
+}
+
+#[test]
+fn malformed_output_is_bad_gateway() {
+	let server = cgi_server("broken.sh", "");
+
+	assert_eq!(server.get("/cgi/").status, 502);
+}
+
+#[test]
+fn slow_script_is_killed() {
+	let server = cgi_server("slow.sh", ", timeout_ms = 200");
+	let start = Instant::now();
+
+	assert_eq!(server.get("/cgi/").status, 504);
+	assert!(start.elapsed().as_secs() < 4);
+}
+
+#[test]
+fn script_that_keeps_running_is_killed() {
+	let server = cgi_server("lingering.sh", ", timeout_ms = 200");
+	let start = Instant::now();
+
+	assert_eq!(server.get("/cgi/").status, 504);
+	assert!(start.elapsed().as_secs() < 4);
+}
+
+#[test]
+fn endless_output_is_cut_off() {
+	let server = cgi_server("endless.sh", ", timeout_ms = 10000");
+	let start = Instant::now();
+
+	assert_eq!(server.get("/cgi/").status, 502);
+	assert!(start.elapsed().as_secs() < 8);
+}
+
+#[test]
+fn status_out_of_range_is_rejected() {
+	for status in ["99 Too Low", "600 Too High", "0"] {
+		let output = format!("Status: {}\nContent-Type: text/plain\n\n", status);
+		assert!(parse_output(output.as_bytes()).is_err(), "accepted {}", status);
+	}
+
+	assert!(parse_output(b"Status: 599 Edge\nContent-Type: text/plain\n\n").is_ok());
+}
+
+#[test]
+fn parses_crlf_and_lf_output() {
+	let response = parse_output(b"Content-Type: text/plain\r\nContent-Length: 99\r\n\r\nbody").unwrap();
+	assert_eq!(response.status, 200);
+	assert_eq!(response.header("Content-Length"), None);
+	assert_eq!(response.text(), "body");
+
+	let response = parse_output(b"Status: 201 Created\nContent-Type: text/plain\n\n").unwrap();
+	assert_eq!((response.status, response.reason.as_str()), (201, "Created"));
+
+	assert!(parse_output(b"X-Only: header\n\nbody").is_err());
+	assert!(parse_output(b"Content-Type: text/plain").is_err());
+}
@@ -0,0 +1,4 @@
+#!/bin/sh
+# escreve sem parar, bem alem do limite do body
+printf 'Content-Type: text/plain\r\n\r\n'
+exec yes
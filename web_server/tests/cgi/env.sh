@@ -0,0 +1,16 @@
+#!/bin/sh
+# devolve as variaveis CGI e o body recebido
+printf 'Content-Type: text/plain\r\n'
+printf 'X-Script: env\r\n\r\n'
+echo "method=$REQUEST_METHOD"
+echo "script=$SCRIPT_NAME"
+echo "path_info=$PATH_INFO"
+echo "query=$QUERY_STRING"
+echo "length=$CONTENT_LENGTH"
+echo "type=$CONTENT_TYPE"
+echo "agent=$HTTP_USER_AGENT"
+echo "remote=$REMOTE_ADDR"
+echo "gateway=$GATEWAY_INTERFACE"
+echo "proxy=$HTTP_PROXY"
+printf 'body='
+cat
@@ -0,0 +1,5 @@
+#!/bin/sh
+# responde, fecha o stdout e continua rodando
+printf 'Content-Type: text/plain\r\n\r\ndone'
+exec >&-
+exec sleep 5
@@ -0,0 +1,2 @@
+#!/bin/sh
+printf 'Location: /elsewhere\n\n'
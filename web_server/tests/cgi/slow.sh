@@ -0,0 +1,3 @@
+#!/bin/sh
+# exec para que o kill do servidor atinja o proprio sleep
+exec sleep 5
@@ -0,0 +1,4 @@
+#!/bin/sh
+printf 'Status: 418 I am a teapot\n'
+printf 'Content-Type: text/plain\n\n'
+printf 'short and stout'
@@ -0,0 +1,99 @@
+// helpers compartilhados pelos testes de integracao
+#![allow(dead_code)]
+
+use std::io;
+use std::io::prelude::*;
+use std::net::SocketAddr;
+use std::thread;
+
+use web_server::client::Client;
+use web_server::config::Config;
+use web_server::http::{Request, Response};
+use web_server::server::Server;
+
+/// Config with one default host rooted at the crate directory, where
+/// `hello.html` and `404.html` live; `routes` is appended as TOML.
+pub fn config(routes: &str) -> Config {
+	let text = format!(r#"
+		threads = 2
+
+		[logging]
+		level = "error"
+
+		[[listeners]]
+		address = "127.0.0.1:0"
+
+		[[hosts]]
+		root = "{}"
+		not_found = "404.html"
+
+		{}
+	"#, env!("CARGO_MANIFEST_DIR"), routes);
+
+	Config::parse(&text).unwrap()
+}
+
+/// A `Server` bound to an ephemeral port and running on its own thread.
+pub struct TestServer {
+	pub address: SocketAddr,
+	client: Client,
+}
+
+impl TestServer {
+	pub fn start(config: Config) -> Self {
+		let server = Server::new(config).unwrap();
+		let address = server.local_addrs()[0];
+
+		thread::spawn(move || server.run());
+
+		Self {
+			address,
+			client: Client::new(),
+		}
+	}
+
+	pub fn get(&self, path: &str) -> Response {
+		self.client.get(self.address, path).unwrap()
+	}
+
+	pub fn request(&self, request: Request) -> Response {
+		self.client.request(self.address, request).unwrap()
+	}
+}
+
+pub fn assert_response(response: &Response, status: u16, body_contains: &str) {
+	assert_eq!(response.status, status, "unexpected status, body: {}", response.text());
+	assert!(response.text().contains(body_contains),
+	        "body does not contain {:?}: {}", body_contains, response.text());
+}
+
+/// In-memory stream: reads come from `input`, writes go to `output`.
+pub struct MemoryStream {
+	input: io::Cursor<Vec<u8>>,
+	pub output: Vec<u8>,
+}
+
+impl MemoryStream {
+	pub fn new(input: &[u8]) -> Self {
+		Self {
+			input: io::Cursor::new(input.to_vec()),
+			output: Vec::new(),
+		}
+	}
+}
+
+impl Read for MemoryStream {
+	fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+		self.input.read(buf)
+	}
+}
+
+impl Write for MemoryStream {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.output.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
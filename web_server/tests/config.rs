@@ -0,0 +1,127 @@
+extern crate web_server;
+
+use web_server::config::Config;
+use web_server::logger::Level;
+use web_server::proxy::Balancing;
+
+const MINIMAL: &str = r#"
+[[listeners]]
+address = "127.0.0.1:0"
+
+[[hosts]]
+"#;
+
+fn error(text: &str) -> String {
+	Config::parse(text).unwrap_err().to_string()
+}
+
+#[test]
+fn parses_minimal_config_with_defaults() {
+	let config = Config::parse(MINIMAL).unwrap();
+
+	assert_eq!(config.threads, 4);
+	assert_eq!(config.logging.level, Level::Info);
+	assert!(config.hosts[0].names.is_empty());
+	assert!(config.hosts[0].routes.is_empty());
+}
+
+#[test]
+fn parses_routes() {
+	let config = Config::parse(&format!(r#"{}
+		[[hosts.routes]]
+		path = "/api/*"
+		proxy = {{ upstreams = ["127.0.0.1:9001"], balancing = "least-connections" }}
+
+		[[hosts.routes]]
+		path = "/ws"
+		websocket = "echo"
+	"#, MINIMAL)).unwrap();
+
+	let routes = &config.hosts[0].routes;
+	assert_eq!(routes[0].proxy.as_ref().unwrap().balancing, Balancing::LeastConnections);
+	assert!(routes[0].matches("/api/users"));
+	assert_eq!(routes[0].remainder("/api/users"), "users");
+	assert!(routes[1].matches("/ws"));
+	assert!(!routes[1].matches("/ws/other"));
+}
+
+#[test]
+fn reports_syntax_errors_with_location() {
+	let message = error("threads = \n");
+
+	assert!(message.contains("line 1"), "{}", message);
+}
+
+#[test]
+fn reports_unknown_fields() {
+	let message = error(&format!("{}\ntreads = 2\n", MINIMAL));
+
+	assert!(message.contains("unknown field `treads`"), "{}", message);
+}
+
+#[test]
+fn reports_invalid_listener_address() {
+	let message = error("[[listeners]]\naddress = \"localhost\"\n[[hosts]]\n");
+
+	assert!(message.contains("invalid socket address"), "{}", message);
+	assert!(message.contains("line 2"), "{}", message);
+}
+
+#[test]
+fn rejects_zero_threads() {
+	assert_eq!(error(&format!("threads = 0\n{}", MINIMAL)), "threads: must be greater than zero");
+}
+
+#[test]
+fn rejects_missing_listeners() {
+	assert_eq!(error("listeners = []\n[[hosts]]\n"),
+	           "listeners: at least one listener is required");
+}
+
+#[test]
+fn rejects_route_without_action() {
+	let message = error(&format!("{}\n[[hosts.routes]]\npath = \"/\"\n", MINIMAL));
+
+	assert_eq!(message,
+	           "hosts[0].routes[0]: route must set exactly one of `file`, `dir`, `proxy`, `websocket`, `cgi`");
+}
+
+#[test]
+fn rejects_missing_file() {
+	let message = error(&format!("{}\n[[hosts.routes]]\npath = \"/\"\nfile = \"missing.html\"\n",
+	                             MINIMAL));
+
+	assert_eq!(message, "hosts[0].routes[0].file: `missing.html` does not exist under `.`");
+}
+
+#[test]
+fn rejects_relative_route_path() {
+	let message = error(&format!("{}\n[[hosts.routes]]\npath = \"ws\"\nwebsocket = \"echo\"\n",
+	                             MINIMAL));
+
+	assert_eq!(message, "hosts[0].routes[0].path: `ws` must start with `/`");
+}
+
+#[test]
+fn rejects_duplicate_host_names() {
+	let message = error(r#"
+		[[listeners]]
+		address = "127.0.0.1:0"
+
+		[[hosts]]
+		names = ["example.com"]
+
+		[[hosts]]
+		names = ["EXAMPLE.com"]
+	"#);
+
+	assert_eq!(message, "hosts[1].names: `EXAMPLE.com` is already used by hosts[0]");
+}
+
+#[test]
+fn rejects_proxy_without_upstreams() {
+	let message = error(&format!("{}\n[[hosts.routes]]\npath = \"/api/*\"\nproxy = {{ upstreams = [] }}\n",
+	                             MINIMAL));
+
+	assert_eq!(message, "hosts[0].routes[0].proxy.upstreams: at least one upstream is required");
+}
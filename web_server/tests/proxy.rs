@@ -0,0 +1,275 @@
+extern crate web_server;
+
+use std::io::Cursor;
+use std::io::prelude::*;
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use web_server::http::Request;
+use web_server::proxy::{Balancing, Proxy};
+
+// backend falso: responde com o proprio nome e o X-Forwarded-For recebido
+fn spawn_backend(name: &'static str, delay: Duration) -> SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		for stream in listener.incoming() {
+			let mut stream = stream.unwrap();
+
+			thread::spawn(move || {
+				let request = match Request::read_from(&mut stream) {
+					Ok(request) => request,
+					Err(_) => return,
+				};
+
+				thread::sleep(delay);
+
+				let body = format!("{}|{}", name,
+				                   request.header("X-Forwarded-For").unwrap_or(""));
+				let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+				                       body.len(), body);
+
+				let _ = stream.write_all(response.as_bytes());
+			});
+		}
+	});
+
+	address
+}
+
+// backend falso que devolve os headers e o body que recebeu
+fn spawn_echo_backend() -> SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		for stream in listener.incoming() {
+			let mut stream = stream.unwrap();
+			let request = match Request::read_from(&mut stream) {
+				Ok(request) => request,
+				Err(_) => continue,
+			};
+
+			let mut body: String = request.headers.iter()
+				.map(|(name, value)| format!("{}: {}\n", name, value))
+				.collect();
+			body.push('\n');
+			body.push_str(&String::from_utf8_lossy(&request.body));
+
+			let response = format!("HTTP/1.1 200 OK\r\nContent-Length: {}\r\n\r\n{}",
+			                       body.len(), body);
+
+			let _ = stream.write_all(response.as_bytes());
+		}
+	});
+
+	address
+}
+
+// aceita conexoes e nunca responde
+fn spawn_silent_backend() -> SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		let mut open = Vec::new();
+
+		for stream in listener.incoming() {
+			open.push(stream);
+		}
+	});
+
+	address
+}
+
+// aceita e fecha na hora, sem ler o request
+fn spawn_closing_backend() -> SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		for stream in listener.incoming() {
+			drop(stream);
+		}
+	});
+
+	address
+}
+
+fn closed_port() -> SocketAddr {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	listener.local_addr().unwrap()
+}
+
+fn spawn_proxy(proxy: Proxy) -> SocketAddr {
+	let proxy = Arc::new(proxy);
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		for stream in listener.incoming() {
+			let mut stream = stream.unwrap();
+			let proxy = Arc::clone(&proxy);
+
+			thread::spawn(move || {
+				let request = Request::read_from(&mut stream).unwrap();
+				let client = stream.peer_addr().ok().map(|address| address.ip());
+				proxy.forward(&mut stream, &request, client).unwrap();
+			});
+		}
+	});
+
+	address
+}
+
+fn get(proxy: SocketAddr, extra_headers: &str) -> String {
+	send(proxy, &format!("GET / HTTP/1.1\r\nHost: test\r\n{}\r\n", extra_headers))
+}
+
+fn send(proxy: SocketAddr, request: &str) -> String {
+	let mut stream = TcpStream::connect(proxy).unwrap();
+	stream.write_all(request.as_bytes()).unwrap();
+
+	let mut response = String::new();
+	stream.read_to_string(&mut response).unwrap();
+	response
+}
+
+fn body(response: &str) -> &str {
+	response.split("\r\n\r\n").nth(1).unwrap()
+}
+
+#[test]
+fn round_robin_alternates_upstreams() {
+	let a = spawn_backend("a", Duration::ZERO);
+	let b = spawn_backend("b", Duration::ZERO);
+	let proxy = spawn_proxy(Proxy::new(vec![a, b], Balancing::RoundRobin).unwrap());
+
+	let names: Vec<String> = (0..4)
+		.map(|_| body(&get(proxy, "")).split('|').next().unwrap().to_string())
+		.collect();
+
+	assert_eq!(names, ["a", "b", "a", "b"]);
+}
+
+#[test]
+fn appends_x_forwarded_for() {
+	let a = spawn_backend("a", Duration::ZERO);
+	let proxy = spawn_proxy(Proxy::new(vec![a], Balancing::RoundRobin).unwrap());
+
+	assert_eq!(body(&get(proxy, "")), "a|127.0.0.1");
+	assert_eq!(body(&get(proxy, "X-Forwarded-For: 10.0.0.1\r\n")), "a|10.0.0.1, 127.0.0.1");
+}
+
+#[test]
+fn least_connections_avoids_busy_upstream() {
+	let slow = spawn_backend("slow", Duration::from_millis(500));
+	let fast = spawn_backend("fast", Duration::ZERO);
+	let proxy = spawn_proxy(Proxy::new(vec![slow, fast], Balancing::LeastConnections).unwrap());
+
+	let pending = thread::spawn(move || get(proxy, ""));
+	thread::sleep(Duration::from_millis(100));
+
+	// o primeiro request ainda esta preso no upstream lento
+	assert!(body(&get(proxy, "")).starts_with("fast|"));
+	assert!(body(&get(proxy, "")).starts_with("fast|"));
+
+	assert!(body(&pending.join().unwrap()).starts_with("slow|"));
+}
+
+#[test]
+fn health_check_removes_dead_upstream() {
+	let dead = closed_port();
+	let alive = spawn_backend("alive", Duration::ZERO);
+	let proxy = Proxy::new(vec![dead, alive], Balancing::RoundRobin).unwrap();
+
+	proxy.check_health();
+	assert!(!proxy.upstreams()[0].is_healthy());
+	assert!(proxy.upstreams()[1].is_healthy());
+
+	let proxy = spawn_proxy(proxy);
+
+	for _ in 0..3 {
+		assert!(body(&get(proxy, "")).starts_with("alive|"));
+	}
+}
+
+#[test]
+fn health_check_with_path_requires_success_status() {
+	let alive = spawn_backend("alive", Duration::ZERO);
+	let silent = spawn_silent_backend();
+
+	let mut proxy = Proxy::new(vec![alive, silent], Balancing::RoundRobin).unwrap();
+	proxy.set_connect_timeout(Duration::from_millis(200));
+	proxy.set_health_check("/health");
+	proxy.check_health();
+
+	assert!(proxy.upstreams()[0].is_healthy());
+	assert!(!proxy.upstreams()[1].is_healthy());
+}
+
+#[test]
+fn unreachable_upstreams_answer_bad_gateway() {
+	let proxy = spawn_proxy(Proxy::new(vec![closed_port()], Balancing::RoundRobin).unwrap());
+
+	assert!(get(proxy, "").starts_with("HTTP/1.1 502 Bad Gateway\r\n"));
+}
+
+#[test]
+fn failed_request_write_answers_bad_gateway() {
+	let proxy = Proxy::new(vec![spawn_closing_backend()], Balancing::RoundRobin).unwrap();
+
+	// grande o bastante para nao caber nos buffers do socket
+	let mut request = Request::new("POST", "/");
+	request.body = vec![b'x'; 8 * 1024 * 1024];
+
+	let mut client = Cursor::new(Vec::new());
+	proxy.forward(&mut client, &request, None).unwrap();
+
+	assert!(client.into_inner().starts_with(b"HTTP/1.1 502 Bad Gateway\r\n"));
+	assert!(!proxy.upstreams()[0].is_healthy());
+}
+
+#[test]
+fn slow_upstream_answers_gateway_timeout() {
+	let mut proxy = Proxy::new(vec![spawn_silent_backend()], Balancing::RoundRobin).unwrap();
+	proxy.set_timeout(Duration::from_millis(200));
+	let proxy = spawn_proxy(proxy);
+
+	assert!(get(proxy, "").starts_with("HTTP/1.1 504 Gateway Timeout\r\n"));
+}
+
+#[test]
+fn headers_listed_in_connection_are_not_forwarded() {
+	let proxy = spawn_proxy(Proxy::new(vec![spawn_echo_backend()], Balancing::RoundRobin).unwrap());
+
+	let response = get(proxy, "Connection: X-Secret, keep-alive\r\nX-Secret: 1\r\nX-Kept: 2\r\n");
+	let headers = body(&response);
+
+	assert!(headers.contains("X-Kept: 2"), "{}", headers);
+	assert!(!headers.contains("X-Secret"), "{}", headers);
+	assert!(headers.contains("Connection: close"), "{}", headers);
+}
+
+#[test]
+fn chunked_request_body_is_forwarded() {
+	let proxy = spawn_proxy(Proxy::new(vec![spawn_echo_backend()], Balancing::RoundRobin).unwrap());
+
+	let response = send(proxy, "POST / HTTP/1.1\r\nHost: test\r\nTransfer-Encoding: chunked\r\n\r\n\
+	                            5\r\nhello\r\n6\r\n world\r\n0\r\n\r\n");
+	let echoed = response.split_once("\r\n\r\n").unwrap().1;
+
+	assert!(echoed.contains("Content-Length: 11\n"), "{}", echoed);
+	assert!(!echoed.contains("Transfer-Encoding"), "{}", echoed);
+	assert!(echoed.ends_with("\n\nhello world"), "{}", echoed);
+}
+
+#[test]
+fn empty_upstream_list_is_a_config_error() {
+	let error = Proxy::new(Vec::new(), Balancing::RoundRobin).err().unwrap();
+
+	assert!(error.to_string().contains("at least one upstream"), "{}", error);
+}
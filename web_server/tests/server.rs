@@ -0,0 +1,292 @@
+extern crate web_server;
+
+mod common;
+
+use std::io::prelude::*;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use common::{assert_response, config, MemoryStream, TestServer};
+use web_server::client::Client;
+use web_server::http::{self, BodyTooLarge, Request, Response, MAX_BODY_SIZE};
+use web_server::server::Server;
+
+const HELLO: &str = r#"
+	[[hosts.routes]]
+	path = "/"
+	file = "hello.html"
+"#;
+
+#[test]
+fn serves_file_route() {
+	let server = TestServer::start(config(HELLO));
+	let response = server.get("/");
+
+	assert_response(&response, 200, "Hi from Rust");
+	assert_eq!(response.header("Content-Type"), Some("text/html; charset=utf-8"));
+	assert_eq!(response.header("Content-Length"), Some(response.body.len().to_string().as_str()));
+}
+
+#[test]
+fn ignores_query_string() {
+	let server = TestServer::start(config(HELLO));
+
+	assert_response(&server.get("/?name=rust"), 200, "Hi from Rust");
+}
+
+#[test]
+fn unknown_path_serves_not_found_page() {
+	let server = TestServer::start(config(HELLO));
+
+	assert_response(&server.get("/missing"), 404, "Oops!");
+}
+
+#[test]
+fn dir_route_serves_files_and_blocks_traversal() {
+	let server = TestServer::start(config(r#"
+		[[hosts.routes]]
+		path = "/files/*"
+		dir = "."
+	"#));
+
+	assert_response(&server.get("/files/hello.html"), 200, "Hi from Rust");
+	assert_response(&server.get("/files/../Cargo.toml"), 404, "Oops!");
+}
+
+#[test]
+fn virtual_hosts_match_host_header() {
+	let mut config = config(HELLO);
+	let mut other = config.hosts[0].clone();
+	other.names = vec!["other.test".to_string()];
+	other.routes[0].file = Some("404.html".into());
+	config.hosts.push(other);
+
+	let server = TestServer::start(config);
+
+	let mut request = Request::new("GET", "/");
+	request.headers.push(("Host".to_string(), "other.test:7878".to_string()));
+	assert_response(&server.request(request), 200, "Oops!");
+
+	let mut request = Request::new("GET", "/");
+	request.headers.push(("Host".to_string(), "unknown.test".to_string()));
+	assert_response(&server.request(request), 200, "Hi from Rust");
+}
+
+#[test]
+fn ipv6_hosts_match_with_and_without_port() {
+	let mut config = config(HELLO);
+	let mut other = config.hosts[0].clone();
+	other.names = vec!["[::1]".to_string()];
+	other.routes[0].file = Some("404.html".into());
+	config.hosts.push(other);
+
+	let server = TestServer::start(config);
+
+	for host in ["[::1]", "[::1]:7878"] {
+		let mut request = Request::new("GET", "/");
+		request.headers.push(("Host".to_string(), host.to_string()));
+		assert_response(&server.request(request), 200, "Oops!");
+	}
+}
+
+#[test]
+fn split_host_keeps_ipv6_brackets() {
+	assert_eq!(http::split_host("example.com:8080"), ("example.com", "8080"));
+	assert_eq!(http::split_host("example.com"), ("example.com", ""));
+	assert_eq!(http::split_host("[::1]:80"), ("[::1]", "80"));
+	assert_eq!(http::split_host("[::1]"), ("[::1]", ""));
+	assert_eq!(http::split_host("[fe80::1"), ("[fe80::1", ""));
+}
+
+#[test]
+fn websocket_route_requires_upgrade() {
+	let server = TestServer::start(config(r#"
+		[[hosts.routes]]
+		path = "/ws"
+		websocket = "echo"
+	"#));
+
+	assert_eq!(server.get("/ws").status, 426);
+}
+
+#[test]
+fn proxy_route_forwards_to_upstream() {
+	let upstream = TcpListener::bind("127.0.0.1:0").unwrap();
+	let upstream_address = upstream.local_addr().unwrap();
+
+	thread::spawn(move || {
+		for stream in upstream.incoming() {
+			let mut stream = stream.unwrap();
+
+			// health checks so abrem e fecham a conexao
+			let request = match Request::read_from(&mut stream) {
+				Ok(request) => request,
+				Err(_) => continue,
+			};
+
+			let body = format!("upstream saw {}", request.path).into_bytes();
+			Response::with_body(200, "OK", "text/plain", body).write_to(&mut stream).unwrap();
+		}
+	});
+
+	let server = TestServer::start(config(&format!(r#"
+		[[hosts.routes]]
+		path = "/api/*"
+		proxy = {{ upstreams = ["{}"] }}
+	"#, upstream_address)));
+
+	assert_response(&server.get("/api/users"), 200, "upstream saw /api/users");
+}
+
+#[test]
+fn post_body_reaches_upstream_through_client() {
+	let upstream = TcpListener::bind("127.0.0.1:0").unwrap();
+	let upstream_address = upstream.local_addr().unwrap();
+
+	thread::spawn(move || {
+		for stream in upstream.incoming() {
+			let mut stream = stream.unwrap();
+
+			if let Ok(request) = Request::read_from(&mut stream) {
+				Response::with_body(201, "Created", "text/plain", request.body)
+					.write_to(&mut stream).unwrap();
+			}
+		}
+	});
+
+	let server = TestServer::start(config(&format!(r#"
+		[[hosts.routes]]
+		path = "/echo"
+		proxy = {{ upstreams = ["{}"] }}
+	"#, upstream_address)));
+
+	let mut request = Request::new("POST", "/echo");
+	request.body = b"payload".to_vec();
+
+	assert_response(&server.request(request), 201, "payload");
+}
+
+#[test]
+fn reload_swaps_routes() {
+	let server = Server::new(config(HELLO)).unwrap();
+	let address = server.local_addrs()[0];
+
+	server.reload(config(r#"
+		[[hosts.routes]]
+		path = "/home"
+		file = "hello.html"
+	"#)).unwrap();
+
+	thread::spawn(move || server.run());
+
+	let client = Client::new();
+	assert_eq!(client.get(address, "/").unwrap().status, 404);
+	assert_eq!(client.get(address, "/home").unwrap().status, 200);
+}
+
+#[test]
+fn handles_in_memory_stream() {
+	let server = Server::new(config(HELLO)).unwrap();
+	let mut stream = MemoryStream::new(b"GET / HTTP/1.1\r\nHost: test\r\n\r\n");
+
+	server.handle_connection(&mut stream, None);
+
+	let response = Response::read_from(&mut stream.output.as_slice()).unwrap();
+	assert_response(&response, 200, "Hi from Rust");
+}
+
+#[test]
+fn malformed_request_is_bad_request() {
+	let server = Server::new(config(HELLO)).unwrap();
+	let mut stream = MemoryStream::new(b"NONSENSE\r\n\r\n");
+
+	server.handle_connection(&mut stream, None);
+
+	let response = Response::read_from(&mut stream.output.as_slice()).unwrap();
+	assert_eq!(response.status, 400);
+}
+
+#[test]
+fn oversized_body_is_refused_before_reading_it() {
+	let server = Server::new(config(HELLO)).unwrap();
+
+	for length in [(MAX_BODY_SIZE + 1).to_string(), "99999999999999999999".to_string()] {
+		let head = format!("POST / HTTP/1.1\r\nHost: test\r\nContent-Length: {}\r\n\r\n", length);
+		let mut stream = MemoryStream::new(head.as_bytes());
+
+		server.handle_connection(&mut stream, None);
+
+		let response = Response::read_from(&mut stream.output.as_slice()).unwrap();
+		assert_eq!(response.status, 413);
+		assert_eq!(response.header("Connection"), Some("close"));
+	}
+}
+
+#[test]
+fn oversized_chunks_are_refused() {
+	for size in [format!("{:x}", MAX_BODY_SIZE + 1), "ffffffffffffffff".to_string()] {
+		let text = format!("HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+		                    5\r\nHello\r\n{}\r\n", size);
+
+		let error = Response::read_from(&mut text.as_bytes()).unwrap_err();
+		assert!(BodyTooLarge::is(&error), "unexpected error: {}", error);
+	}
+}
+
+#[test]
+fn chunked_wins_over_content_length_in_responses() {
+	let text = "HTTP/1.1 200 OK\r\nContent-Length: 3\r\nTransfer-Encoding: chunked\r\n\r\n\
+	            5\r\nHello\r\n0\r\n\r\nNEXT";
+	let mut reader = text.as_bytes();
+
+	let response = Response::read_from(&mut reader).unwrap();
+	assert_eq!(response.text(), "Hello");
+	assert_eq!(reader, b"NEXT");
+}
+
+#[test]
+fn client_reads_chunked_response() {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		let (mut stream, _) = listener.accept().unwrap();
+		Request::read_from(&mut stream).unwrap();
+
+		stream.write_all(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+		                   5\r\nHello\r\n7;ext=1\r\n, world\r\n0\r\n\r\n").unwrap();
+
+		// mantem a conexao aberta: o client tem que parar no chunk final
+		thread::sleep(Duration::from_secs(1));
+		drop(stream);
+	});
+
+	let response = Client::new().get(address, "/").unwrap();
+	assert_eq!(response.text(), "Hello, world");
+}
+
+#[test]
+fn serves_several_requests_concurrently() {
+	let server = TestServer::start(config(&format!(r#"{}
+		[[hosts.routes]]
+		path = "/sleep"
+		file = "hello.html"
+		delay_ms = 300
+	"#, HELLO)));
+
+	let address = server.address;
+	let slow: Vec<_> = (0..2)
+		.map(|_| thread::spawn(move || Client::new().get(address, "/sleep")))
+		.collect();
+
+	thread::sleep(Duration::from_millis(50));
+
+	// os dois workers estao ocupados mas a conexao ainda e aceita
+	let stream = TcpStream::connect(address).unwrap();
+	drop(stream);
+
+	for handle in slow {
+		assert_eq!(handle.join().unwrap().unwrap().status, 200);
+	}
+}
@@ -0,0 +1,177 @@
+extern crate web_server;
+
+use std::io::prelude::*;
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use web_server::http::Request;
+use web_server::websocket::*;
+
+const MASK: [u8; 4] = [0x37, 0xfa, 0x21, 0x3d];
+
+// sobe um servidor de eco que aceita uma unica conexao
+fn spawn_echo_server() -> TcpStream {
+	let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+	let address = listener.local_addr().unwrap();
+
+	thread::spawn(move || {
+		let (mut stream, _) = listener.accept().unwrap();
+		let request = Request::read_from(&mut stream).unwrap();
+
+		if let Ok(mut socket) = WebSocket::accept(stream, &request) {
+			let _ = socket.run(&mut Echo);
+		}
+	});
+
+	let stream = TcpStream::connect(address).unwrap();
+	stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+	stream
+}
+
+fn read_response_head(stream: &mut TcpStream) -> String {
+	let mut head = Vec::new();
+	let mut byte = [0; 1];
+
+	while !head.ends_with(b"\r\n\r\n") {
+		stream.read_exact(&mut byte).unwrap();
+		head.push(byte[0]);
+	}
+
+	String::from_utf8(head).unwrap()
+}
+
+fn connect() -> TcpStream {
+	let mut stream = spawn_echo_server();
+
+	stream.write_all(b"GET /chat HTTP/1.1\r\n\
+	                   Host: localhost\r\n\
+	                   Upgrade: websocket\r\n\
+	                   Connection: keep-alive, Upgrade\r\n\
+	                   Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+	                   Sec-WebSocket-Version: 13\r\n\r\n").unwrap();
+
+	let head = read_response_head(&mut stream);
+	assert!(head.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+	assert!(head.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+
+	stream
+}
+
+fn send(stream: &mut TcpStream, fin: bool, opcode: Opcode, payload: &[u8]) {
+	let mut frame = Frame::new(fin, opcode, payload.to_vec());
+	frame.mask = Some(MASK);
+	frame.write_to(stream).unwrap();
+}
+
+fn receive(stream: &mut TcpStream) -> Frame {
+	Frame::read_from(stream, usize::MAX).unwrap()
+}
+
+fn close_code(frame: &Frame) -> u16 {
+	assert_eq!(frame.opcode, Opcode::Close);
+	u16::from_be_bytes([frame.payload[0], frame.payload[1]])
+}
+
+#[test]
+fn accept_key_matches_rfc_example() {
+	assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+}
+
+#[test]
+fn echoes_raw_masked_text_frame() {
+	let mut stream = connect();
+
+	// exemplo da secao 5.7 da RFC: "Hello" mascarado
+	stream.write_all(&[0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d,
+	                   0x7f, 0x9f, 0x4d, 0x51, 0x58]).unwrap();
+
+	let mut reply = [0; 7];
+	stream.read_exact(&mut reply).unwrap();
+	assert_eq!(reply, [0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f]);
+}
+
+#[test]
+fn echoes_binary_with_extended_lengths() {
+	let mut stream = connect();
+
+	for length in [300, 70_000] {
+		let data: Vec<u8> = (0..length).map(|i| (i % 251) as u8).collect();
+		send(&mut stream, true, Opcode::Binary, &data);
+
+		let frame = receive(&mut stream);
+		assert_eq!(frame.opcode, Opcode::Binary);
+		assert!(frame.mask.is_none());
+		assert_eq!(frame.payload, data);
+	}
+}
+
+#[test]
+fn reassembles_fragments_and_answers_interleaved_ping() {
+	let mut stream = connect();
+
+	send(&mut stream, false, Opcode::Text, b"Hel");
+	send(&mut stream, true, Opcode::Ping, b"are you there");
+	send(&mut stream, false, Opcode::Continuation, b"lo, ");
+	send(&mut stream, true, Opcode::Continuation, b"world");
+
+	let pong = receive(&mut stream);
+	assert_eq!(pong.opcode, Opcode::Pong);
+	assert_eq!(pong.payload, b"are you there");
+
+	let text = receive(&mut stream);
+	assert_eq!(text.opcode, Opcode::Text);
+	assert!(text.fin);
+	assert_eq!(text.payload, b"Hello, world");
+}
+
+#[test]
+fn echoes_close_code() {
+	let mut stream = connect();
+
+	let mut payload = 1001u16.to_be_bytes().to_vec();
+	payload.extend_from_slice(b"bye");
+	send(&mut stream, true, Opcode::Close, &payload);
+
+	assert_eq!(close_code(&receive(&mut stream)), 1001);
+}
+
+#[test]
+fn unmasked_client_frame_is_a_protocol_error() {
+	let mut stream = connect();
+
+	Frame::new(true, Opcode::Text, b"oops".to_vec()).write_to(&mut stream).unwrap();
+
+	assert_eq!(close_code(&receive(&mut stream)), 1002);
+}
+
+#[test]
+fn unexpected_continuation_is_a_protocol_error() {
+	let mut stream = connect();
+
+	send(&mut stream, true, Opcode::Continuation, b"orphan");
+
+	assert_eq!(close_code(&receive(&mut stream)), 1002);
+}
+
+#[test]
+fn invalid_utf8_text_closes_with_1007() {
+	let mut stream = connect();
+
+	send(&mut stream, true, Opcode::Text, &[0xff, 0xfe]);
+
+	assert_eq!(close_code(&receive(&mut stream)), 1007);
+}
+
+#[test]
+fn rejects_handshake_without_key() {
+	let mut stream = spawn_echo_server();
+
+	stream.write_all(b"GET / HTTP/1.1\r\n\
+	                   Upgrade: websocket\r\n\
+	                   Connection: Upgrade\r\n\
+	                   Sec-WebSocket-Version: 13\r\n\r\n").unwrap();
+
+	let head = read_response_head(&mut stream);
+	assert!(head.starts_with("HTTP/1.1 400 Bad Request\r\n"));
+}